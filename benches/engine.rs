@@ -0,0 +1,85 @@
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use rand::{Rng, SeedableRng};
+
+use evolution_simulation::agent::ActionSelection;
+use evolution_simulation::agent::gene::{Gene, Genome, MutationWeights};
+use evolution_simulation::simulation::{Basics, Prng};
+use evolution_simulation::{Simulation, SimulationSettings};
+
+// Benchmarks of the engine's hot loop, run with `cargo bench`
+// Every world and genome is seeded, so each run measures the same work
+
+const SEED: u64 = 7;
+
+// Builds a seeded world with the default settings, sized so roughly the same fraction of it is occupied
+fn world(size: usize, complexity: usize) -> Simulation {
+    let settings = SimulationSettings::default();
+    Simulation::new(settings.with_basics(Basics {
+        dimensions: iced::Size::new(size, size),
+        agents: size * size / 32,
+        complexity,
+        seed: Some(SEED),
+        ..settings.basics()
+    } ))
+}
+
+// A single step of worlds of increasing size, each bench starting from the same freshly built world
+fn step(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Simulation::step");
+    for size in [32, 64, 128, 256] {
+        group.bench_with_input(BenchmarkId::from_parameter(format!("{}x{}", size, size)), &size, |b, &size| {
+            b.iter_batched(|| world(size, SimulationSettings::default().basics().complexity), |mut simulation| {
+                simulation.step();
+                simulation
+            }, BatchSize::LargeInput)
+        } );
+    }
+
+    group.finish();
+}
+
+// One decision by the deepest brain among a world of Agents built from long genomes
+fn process(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Agent::process");
+    for complexity in [16, 64, 256] {
+        let simulation = world(64, complexity);
+        let deepest = simulation.agents().into_iter()
+            .max_by_key(|coord| simulation.get(*coord).agent().brain_stats().depth)
+            .expect("the world should hold at least one Agent");
+
+        let agent = simulation.get(deepest).agent();
+        let sense = simulation.sense(deepest);
+        let mut prng = Prng::seed_from_u64(SEED);
+
+        group.bench_function(BenchmarkId::new("genes", complexity), |b| {
+            b.iter(|| agent.process(&sense, 0f32, ActionSelection::Argmax, &mut prng))
+        } );
+    }
+
+    group.finish();
+}
+
+// Mutating genomes of increasing length
+// The rate is raised so that every call applies a structural mutation, the costliest path
+fn mutate(c: &mut Criterion) {
+    let weights = MutationWeights::default();
+
+    let mut group = c.benchmark_group("Genome::mutate");
+    for length in [16, 64, 256] {
+        let mut prng = Prng::seed_from_u64(SEED);
+        let genome = (0..length)
+            .map(|_| Gene::new(prng.gen_range(0..=255)))
+            .collect::<Vec<Gene>>();
+
+        group.bench_function(BenchmarkId::new("genes", length), |b| {
+            b.iter_batched(|| genome.clone(), |genome| {
+                Genome::mutate(genome, &weights, 10f32, &mut prng)
+            }, BatchSize::SmallInput)
+        } );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, step, process, mutate);
+criterion_main!(benches);
@@ -0,0 +1,13 @@
+use std::process::Command;
+
+// Embeds the current commit, so run manifests can name the engine that produced them
+fn main() {
+    let output = Command::new("git").args(["rev-parse", "--short", "HEAD"]).output();
+    if let Ok(output) = output {
+        if output.status.success() {
+            println!("cargo:rustc-env=GIT_HASH={}", String::from_utf8_lossy(&output.stdout).trim());
+        }
+    }
+
+    println!("cargo:rerun-if-changed=.git/HEAD");
+}
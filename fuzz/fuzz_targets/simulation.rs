@@ -0,0 +1,168 @@
+#![no_main]
+// Drives a Simulation through random sequences of operations, the same ones the interface can perform
+// Run with `cargo fuzz run simulation` from the repository root
+use libfuzzer_sys::fuzz_target;
+
+use evolution_simulation::simulation::{Rule, Simulation, SimulationSettings, UpdateMode};
+use evolution_simulation::tile::coord;
+use evolution_simulation::{pulse, weather};
+
+#[derive(Debug, arbitrary::Arbitrary)]
+enum Operation {
+    Step,
+    Steps(u8),
+    // each byte becomes one gene
+    Import(Vec<u8>),
+    // arbitrary text, to exercise genome parsing
+    ImportText(String),
+    Place(u8, u8, Vec<u8>),
+    Remove(u8, u8),
+    PlaceFood(u8, u8),
+    BuildWall(u8, u8),
+    EraseWall(u8, u8),
+    ClearTile(u8, u8),
+    Spawn(u8, u8),
+    Mark(u8, u8, u8),
+    Watch(u8, u8),
+    Inspect(u8, u8),
+    Boundary(u8),
+    Update(bool),
+    Neighborhood(bool),
+    Resurrect(u64),
+    // a truce when true, otherwise the given mutation multiplier, for the given number of steps
+    Pulse(bool, f32, u16),
+    // restarts with new dimensions, as the interface does when the world is resized
+    Resize(u8, u8),
+    // rebuilds the run so far from its recording
+    Replay(u16),
+    Statistics
+}
+
+#[derive(Debug, arbitrary::Arbitrary)]
+struct Input {
+    seed: u64,
+    width: u8,
+    height: u8,
+    agents: u8,
+    complexity: u8,
+    weather: bool,
+    operations: Vec<Operation>
+}
+
+impl Input {
+    // the largest world and the most steps a single input can ask for, so each run stays fast
+    const SIZE: usize = 24;
+    const STEPS: usize = 16;
+
+    // Settings are built the same way they're loaded from a manifest
+    fn settings(&self, width: u8, height: u8) -> SimulationSettings {
+        let mut settings = serde_json::to_value(SimulationSettings::default()).unwrap();
+        settings["dimensions"] = serde_json::json!([
+            1 + width as usize % Self::SIZE,
+            1 + height as usize % Self::SIZE
+        ]);
+        settings["agents"] = serde_json::json!(self.agents % 32);
+        settings["complexity"] = serde_json::json!(self.complexity);
+        settings["seed"] = serde_json::json!(self.seed);
+        if self.weather {
+            settings["weather"] = serde_json::to_value(weather::Weather::default()).unwrap();
+        }
+
+        serde_json::from_value(settings).unwrap()
+    }
+}
+
+// Coordinates are passed through as they are, so operations also target Tiles outside of the world,
+// which the Simulation must refuse rather than panic on
+fn coord(x: u8, y: u8) -> coord::Coord {
+    coord::Coord::new(x as usize, y as usize)
+}
+
+fuzz_target!(|input: Input| {
+    let mut simulation = Simulation::new(input.settings(input.width, input.height));
+
+    for operation in input.operations.iter() {
+        use Operation::*;
+        match operation {
+            Step => simulation.step(),
+            Steps(steps) => for _ in 0..(*steps as usize % Input::STEPS) {
+                simulation.step();
+            },
+            Import(genes) => {
+                let genome = genes.iter()
+                    .map(|gene| format!("{:08b}", gene))
+                    .collect::<Vec<String>>()
+                    .join(" ");
+
+                let _ = simulation.import(genome);
+            },
+            ImportText(genome) => {
+                let _ = simulation.import(genome.clone());
+            },
+            Place(x, y, genes) => {
+                let genome = genes.iter()
+                    .map(|gene| format!("{:08b}", gene))
+                    .collect::<Vec<String>>()
+                    .join(" ");
+
+                let _ = simulation.place(coord(*x, *y), genome);
+            },
+            Remove(x, y) => {
+                let _ = simulation.remove_agent(coord(*x, *y));
+            },
+            PlaceFood(x, y) => {
+                simulation.place_food(coord(*x, *y));
+            },
+            BuildWall(x, y) => {
+                simulation.build_wall(coord(*x, *y));
+            },
+            EraseWall(x, y) => {
+                simulation.erase_wall(coord(*x, *y));
+            },
+            ClearTile(x, y) => {
+                simulation.clear_tile(coord(*x, *y));
+            },
+            Spawn(x, y) => {
+                let _ = simulation.spawn_agent(coord(*x, *y));
+            },
+            Mark(x, y, marker) => {
+                let _ = simulation.mark(coord(*x, *y), *marker);
+            },
+            Watch(x, y) => simulation.watch(coord(*x, *y)),
+            Inspect(x, y) => {
+                let coord = coord(*x, *y);
+                if let Ok(agent) = simulation.try_agent(coord) {
+                    let _ = agent.to_string();
+                }
+
+                let _ = simulation.temperature(coord);
+            },
+            Boundary(boundary) => simulation.change_rule(Rule::Boundary(match boundary % 3 {
+                0 => coord::BoundaryMode::Wrap,
+                1 => coord::BoundaryMode::Wall,
+                _ => coord::BoundaryMode::Kill
+            } )),
+            Update(synchronous) => simulation.change_rule(Rule::Update(
+                if *synchronous { UpdateMode::Synchronous } else { UpdateMode::Sequential })),
+            Neighborhood(moore) => simulation.change_rule(Rule::Neighborhood(
+                if *moore { coord::Neighborhood::Moore } else { coord::Neighborhood::VonNeumann })),
+            Resurrect(id) => {
+                let _ = simulation.resurrect(*id);
+            },
+            Pulse(truce, multiplier, duration) => simulation.pulse(
+                if *truce { pulse::Modifier::Truce } else { pulse::Modifier::Mutation(*multiplier) },
+                *duration as u64),
+            Resize(width, height) => simulation = Simulation::new(input.settings(*width, *height)),
+            Replay(tick) => {
+                let recording = simulation.record();
+                let tick = *tick as u64 % (recording.ticks + 1);
+                simulation = Simulation::replay(&recording, tick);
+            },
+            Statistics => {
+                let _ = simulation.statistics();
+                let _ = simulation.memory();
+                let _ = simulation.ledger();
+            }
+        }
+    }
+});
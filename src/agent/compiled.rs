@@ -0,0 +1,448 @@
+use std::cell;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::ops;
+
+use petgraph::graph;
+use petgraph::graph::{EdgeIndex, NodeIndex};
+use petgraph::visit::EdgeRef;
+
+use strum::IntoEnumIterator;
+
+use crate::agent::{Edge, Node};
+use crate::agent::gene;
+use crate::simulation::Sense;
+
+// A flattened copy of an Agent's brain, built once when the Agent is created
+// Nodes are stored in evaluation order and their inputs in one contiguous array,
+// so evaluating the brain is a single pass without any graph traversal
+#[derive(Debug, Clone, Default)]
+pub struct CompiledBrain {
+    nodes: Box<[CompiledNode]>,
+    inputs: Box<[Input]>,
+    // the slot of each action node, in the same order as the graph
+    actions: Box<[(gene::ActionType, usize)]>
+}
+
+#[derive(Debug, Clone)]
+struct CompiledNode {
+    operation: Operation,
+    inputs: ops::Range<usize>,
+    // the node's output from the previous tick, read by edges that close a cycle
+    state: cell::Cell<f32>,
+    // the node's output during the last evaluation, None if it received no input
+    // unlike the state, this is kept for every kind of node and never read back by evaluation
+    output: cell::Cell<Option<f32>>
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Operation {
+    Sense(gene::SenseType),
+    Internal { bias: f32, activation: gene::ActivationType },
+    Action
+}
+
+#[derive(Debug, Clone)]
+struct Input {
+    source: usize,
+    weight: f32,
+    recurrent: bool,
+    plastic: bool,
+    // the product of this input's value and its node's output during the last evaluation
+    trace: cell::Cell<f32>
+}
+
+impl CompiledBrain {
+    // plastic edges can't grow stronger than this, in either direction
+    const MAXIMUM_WEIGHT: f32 = 2f32;
+    // carried states can't grow beyond this, in either direction
+    // an identity node that feeds itself would otherwise overflow to infinity, and then to NaN, which can't be saved
+    const MAXIMUM_STATE: f32 = 1e6f32;
+
+    pub fn new(brain: &graph::Graph<Node, Edge>) -> Self {
+        let (order, recurrent) = Self::evaluation_order(brain);
+
+        // maps each NodeIndex to its position in the evaluation order
+        let mut slots = vec![0; brain.node_count()];
+        for (slot, index) in order.iter().enumerate() {
+            slots[index.index()] = slot;
+        }
+
+        let mut nodes = Vec::with_capacity(order.len());
+        let mut inputs = Vec::with_capacity(brain.edge_count());
+        for &index in order.iter() {
+            let start = inputs.len();
+            for edge in brain.edges_directed(index, petgraph::Direction::Incoming) {
+                inputs.push(Input {
+                    source: slots[edge.source().index()],
+                    // inhibitory edges invert their input, additive edges pass it through
+                    weight: if edge.weight().additive { 1f32 } else { -1f32 },
+                    recurrent: recurrent.contains(&edge.id()),
+                    plastic: edge.weight().plastic,
+                    trace: cell::Cell::new(0f32)
+                } );
+            }
+
+            let (operation, state) = match &brain[index] {
+                Node::Sense(variant) => (Operation::Sense(*variant), 0f32),
+                Node::Action(..) => (Operation::Action, 0f32),
+                Node::Internal { bias, activation } => (
+                    Operation::Internal { bias: *bias, activation: *activation },
+                    activation.apply(*bias)
+                )
+            };
+
+            nodes.push(CompiledNode {
+                operation,
+                inputs: start..inputs.len(),
+                state: cell::Cell::new(state),
+                output: cell::Cell::new(None)
+            } );
+        }
+
+        let actions = brain.node_indices().filter_map(|index| match &brain[index] {
+            Node::Action(variant) => Some((*variant, slots[index.index()])),
+            _ => None
+        } ).collect::<Vec<(gene::ActionType, usize)>>();
+
+        // the compiled brain never changes shape, so it is stored in fixed-size slices
+        Self {
+            nodes: nodes.into_boxed_slice(),
+            inputs: inputs.into_boxed_slice(),
+            actions: actions.into_boxed_slice()
+        }
+    }
+
+    // Returns the output of every action node that received input this tick
+    // When recording, every node's output is also kept, see CompiledBrain::activations
+    pub fn evaluate(&self, sense: &Sense, recording: bool) -> Vec<(gene::ActionType, f32)> {
+        let mut values: Vec<Option<f32>> = vec![None; self.nodes.len()];
+        for (slot, node) in self.nodes.iter().enumerate() {
+            if let Operation::Sense(variant) = &node.operation {
+                values[slot] = Some(sense.get(variant));
+                continue;
+            }
+
+            let (count, sum) = self.inputs[node.inputs.clone()].iter().fold((0, 0f32), |(count, sum), input| {
+                match self.input_value(input, &values) {
+                    Some(value) => (count + 1, sum + value * input.weight),
+                    None => (count, sum)
+                }
+            } );
+
+            values[slot] = match node.operation {
+                Operation::Internal { bias, activation } => Some(activation.apply(
+                    if count == 0 { bias } else { sum / count as f32 * bias }
+                )),
+                _ => if count == 0 { None } else { Some(sum / count as f32) }
+            };
+        }
+
+        // plastic edges remember how strongly they contributed to their node's output
+        for (slot, node) in self.nodes.iter().enumerate() {
+            for input in self.inputs[node.inputs.clone()].iter().filter(|input| input.plastic) {
+                let trace = match (self.input_value(input, &values), values[slot]) {
+                    (Some(input), Some(output)) => input * output,
+                    _ => 0f32
+                };
+
+                input.trace.set(trace);
+            }
+        }
+
+        // internal nodes carry their output over to the next tick
+        for (slot, node) in self.nodes.iter().enumerate() {
+            if let (Operation::Internal { .. }, Some(value)) = (&node.operation, values[slot]) {
+                node.state.set(value.clamp(-Self::MAXIMUM_STATE, Self::MAXIMUM_STATE));
+            }
+        }
+
+        if recording {
+            for (slot, node) in self.nodes.iter().enumerate() {
+                node.output.set(values[slot]);
+            }
+        }
+
+        self.actions.iter()
+            .filter_map(|(variant, slot)| values[*slot].map(|value| (*variant, value)))
+            .collect()
+    }
+
+    // Returns each node's output during the last recorded evaluation, indexed by NodeIndex
+    // A node is None if it received no input, or if no evaluation has been recorded
+    // The brain must be the one this was compiled from
+    pub fn activations(&self, brain: &graph::Graph<Node, Edge>) -> Vec<Option<f32>> {
+        let (order, _) = Self::evaluation_order(brain);
+
+        let mut activations = vec![None; brain.node_count()];
+        for (slot, index) in order.iter().enumerate() {
+            activations[index.index()] = self.nodes.get(slot).and_then(|node| node.output.get());
+        }
+
+        activations
+    }
+
+    fn input_value(&self, input: &Input, values: &[Option<f32>]) -> Option<f32> {
+        // edges that close a cycle are read before their source is evaluated this tick,
+        // so they receive the source's output from the previous tick instead
+        if input.recurrent {
+            Some(self.nodes[input.source].state.get())
+        } else {
+            values[input.source]
+        }
+    }
+
+    // Writes the brain as a standalone Rust function over the sense vector
+    // Node states persist between calls through the `state` argument, which starts as INITIAL_STATE
+    // Hysteresis and attribute modifiers are folded in, and the strongest action is always chosen
+    pub fn rust(&self, turn_bias: f32, gait: f32, hysteresis: f32) -> String {
+        let float = Self::float_literal;
+
+        let senses = gene::SenseType::iter().collect::<Vec<gene::SenseType>>();
+        let actions = gene::ActionType::iter().collect::<Vec<gene::ActionType>>();
+
+        let names = |names: Vec<String>| names.iter()
+            .map(|name| format!("\"{}\"", name))
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        // writing to a String can't fail
+        let mut source = String::from("// Generated from an evolved brain, do not edit\n\n");
+        let _ = writeln!(source, "pub const SENSES: [&str; {}] = [{}];",
+            senses.len(),
+            names(senses.iter().map(|sense| format!("{:?}", sense)).collect()));
+        let _ = writeln!(source, "pub const ACTIONS: [&str; {}] = [{}];",
+            actions.len(),
+            names(actions.iter().map(|action| format!("{:?}", action)).collect()));
+        let _ = writeln!(source, "pub const INITIAL_STATE: [f32; {}] = [{}];\n",
+            self.nodes.len(),
+            self.states().into_iter().map(float).collect::<Vec<String>>().join(", "));
+
+        let _ = writeln!(source, "// `senses` is indexed like SENSES, `previous` and the result are indices into ACTIONS");
+        let _ = writeln!(source, "#[allow(unused_mut)]");
+        let _ = writeln!(source,
+            "pub fn decide(senses: &[f32; {}], state: &mut [f32; {}], previous: Option<usize>) -> Option<usize> {{",
+            senses.len(),
+            self.nodes.len());
+        let _ = writeln!(source, "    let mut values: [Option<f32>; {}] = [None; {}];", self.nodes.len(), self.nodes.len());
+
+        for (slot, node) in self.nodes.iter().enumerate() {
+            if let Operation::Sense(variant) = &node.operation {
+                let index = senses.iter().position(|sense| sense == variant).unwrap();
+                let _ = writeln!(source, "    values[{}] = Some(senses[{}]);", slot, index);
+                continue;
+            }
+
+            let _ = writeln!(source, "    {{\n        let (mut count, mut sum) = (0u32, 0f32);");
+            for input in self.inputs[node.inputs.clone()].iter() {
+                let _ = if input.recurrent {
+                    writeln!(source, "        count += 1; sum += state[{}] * {};", input.source, float(input.weight))
+                } else {
+                    writeln!(source, "        if let Some(value) = values[{}] {{ count += 1; sum += value * {}; }}", input.source, float(input.weight))
+                };
+            }
+
+            let _ = match node.operation {
+                Operation::Internal { bias, activation } => {
+                    let _ = writeln!(source, "        let x: f32 = if count == 0 {{ {} }} else {{ sum / count as f32 * {} }};", float(bias), float(bias));
+                    writeln!(source, "        values[{}] = Some({});", slot, match activation {
+                        gene::ActivationType::Tanh => "x.tanh()",
+                        gene::ActivationType::Sigmoid => "1f32 / (1f32 + (-x).exp())",
+                        gene::ActivationType::Relu => "x.max(0f32)",
+                        gene::ActivationType::Step => "if x > 0f32 { 1f32 } else { 0f32 }"
+                    } )
+                },
+                _ => writeln!(source, "        values[{}] = if count == 0 {{ None }} else {{ Some(sum / count as f32) }};", slot)
+            };
+
+            let _ = writeln!(source, "    }}");
+        }
+
+        for (slot, node) in self.nodes.iter().enumerate() {
+            if let Operation::Internal { .. } = node.operation {
+                let _ = writeln!(source, "    if let Some(value) = values[{}] {{ state[{}] = value.clamp({}, {}); }}", slot, slot, float(-Self::MAXIMUM_STATE), float(Self::MAXIMUM_STATE));
+            }
+        }
+
+        let index = |variant: gene::ActionType| actions.iter().position(|action| *action == variant).unwrap();
+        let turning = format!("Some({} | {})", index(gene::ActionType::TurnLeft), index(gene::ActionType::TurnRight));
+        let moving = format!("Some({})", index(gene::ActionType::Move));
+
+        let _ = writeln!(source, "\n    let turned = matches!(previous, {});", turning);
+        let _ = writeln!(source, "    let moved = matches!(previous, {});", moving);
+        let _ = writeln!(source, "    let mut best: Option<(usize, f32)> = None;");
+        for (variant, slot) in self.actions.iter() {
+            // the previous action's hysteresis bonus is added before the attribute modifiers, as in Agent::process
+            let mut modifier = format!(" + if previous == Some({}) {{ {} }} else {{ 0f32 }}", index(*variant), float(hysteresis));
+            match variant {
+                gene::ActionType::TurnLeft => modifier.push_str(&format!(" - {}", float(turn_bias))),
+                gene::ActionType::TurnRight => modifier.push_str(&format!(" + {}", float(turn_bias))),
+                _ => {  }
+            }
+
+            match variant {
+                gene::ActionType::Move => modifier.push_str(&format!(" + if turned {{ {} }} else {{ 0f32 }}", float(gait))),
+                gene::ActionType::TurnLeft | gene::ActionType::TurnRight => {
+                    modifier.push_str(&format!(" + if moved {{ {} }} else {{ 0f32 }}", float(gait)))
+                },
+                _ => {  }
+            }
+
+            let _ = writeln!(source, "    if let Some(value) = values[{}] {{", slot);
+            let _ = writeln!(source, "        let value = value{};", modifier);
+            let _ = writeln!(source, "        if best.map_or(true, |(_, highest)| value > highest) {{ best = Some(({}, value)); }}", index(*variant));
+            let _ = writeln!(source, "    }}");
+        }
+
+        let _ = writeln!(source, "\n    let _ = (turned, moved);");
+        let _ = writeln!(source, "    best.map(|(action, _)| action)\n}}");
+        source
+    }
+
+    // Debug formatting writes non-finite floats as NaN and inf, which aren't Rust
+    fn float_literal(value: f32) -> String {
+        if value.is_nan() {
+            String::from("f32::NAN")
+        } else if value == f32::INFINITY {
+            String::from("f32::INFINITY")
+        } else if value == f32::NEG_INFINITY {
+            String::from("f32::NEG_INFINITY")
+        } else {
+            format!("{:?}", value)
+        }
+    }
+
+    // Hebbian learning: plastic edges change in proportion to how active they were during the last evaluation
+    // A positive reward strengthens them, a negative reward weakens them
+    pub fn reinforce(&mut self, reward: f32) {
+        for input in self.inputs.iter_mut().filter(|input| input.plastic) {
+            input.weight = (input.weight + reward * input.trace.get())
+                .clamp(-Self::MAXIMUM_WEIGHT, Self::MAXIMUM_WEIGHT);
+        }
+    }
+
+    // The weight of every input, including any changes made by learning
+    pub fn weights(&self) -> Vec<f32> {
+        self.inputs.iter().map(|input| input.weight).collect()
+    }
+
+    // The output of every node from the previous tick
+    pub fn states(&self) -> Vec<f32> {
+        self.nodes.iter().map(|node| node.state.get()).collect()
+    }
+
+    // Restores values taken from CompiledBrain::weights and CompiledBrain::states
+    // Values are ignored if they were taken from a brain of a different shape
+    pub fn restore(&mut self, weights: &[f32], states: &[f32]) {
+        if weights.len() == self.inputs.len() {
+            for (input, weight) in self.inputs.iter_mut().zip(weights) {
+                input.weight = *weight;
+            }
+        }
+
+        if states.len() == self.nodes.len() {
+            for (node, state) in self.nodes.iter().zip(states) {
+                node.state.set(*state);
+            }
+        }
+    }
+
+    // Approximate heap memory held by the compiled brain, in bytes
+    pub fn memory(&self) -> usize {
+        self.nodes.len() * std::mem::size_of::<CompiledNode>()
+            + self.inputs.len() * std::mem::size_of::<Input>()
+            + self.actions.len() * std::mem::size_of::<(gene::ActionType, usize)>()
+    }
+
+    // The work done by a single evaluation, counting each node and each input once
+    pub fn cost(&self) -> usize {
+        self.nodes.len() + self.inputs.len()
+    }
+
+    // The number of edges that close a cycle
+    pub fn recurrent(&self) -> usize {
+        self.inputs.iter().filter(|input| input.recurrent).count()
+    }
+
+    // The longest chain of edges through the brain, ignoring edges that close a cycle
+    pub fn depth(&self) -> usize {
+        let mut depths = vec![0usize; self.nodes.len()];
+        for (slot, node) in self.nodes.iter().enumerate() {
+            depths[slot] = self.inputs[node.inputs.clone()].iter()
+                .filter(|input| !input.recurrent)
+                .map(|input| depths[input.source] + 1)
+                .max()
+                .unwrap_or(0);
+        }
+
+        depths.into_iter().max().unwrap_or(0)
+    }
+
+    // Orders the brain's nodes so that each one follows all of its inputs
+    // Edges that would violate this ordering close a cycle and are returned separately
+    fn evaluation_order(brain: &graph::Graph<Node, Edge>) -> (Vec<NodeIndex>, HashSet<EdgeIndex>) {
+        #[derive(Copy, Clone, PartialEq, Eq)]
+        enum Mark {
+            Unvisited,
+            Visiting,
+            Visited
+        }
+
+        let incoming = |index: NodeIndex| {
+            brain.edges_directed(index, petgraph::Direction::Incoming)
+                .map(|edge| edge.id())
+                .collect::<Vec<EdgeIndex>>()
+        };
+
+        let mut marks = vec![Mark::Unvisited; brain.node_count()];
+        let mut order: Vec<NodeIndex> = Vec::with_capacity(brain.node_count());
+        let mut recurrent: HashSet<EdgeIndex> = HashSet::new();
+
+        // depth-first search along incoming edges, using an explicit stack
+        for root in brain.node_indices() {
+            if marks[root.index()] != Mark::Unvisited {
+                continue;
+            }
+
+            marks[root.index()] = Mark::Visiting;
+            let mut stack = vec![(root, incoming(root))];
+            while !stack.is_empty() {
+                let top = stack.len() - 1;
+                match stack[top].1.pop() {
+                    Some(edge) => {
+                        let (source, _) = brain.edge_endpoints(edge).unwrap();
+                        match marks[source.index()] {
+                            Mark::Visiting => { recurrent.insert(edge); },
+                            Mark::Unvisited => {
+                                marks[source.index()] = Mark::Visiting;
+                                stack.push((source, incoming(source)));
+                            },
+                            Mark::Visited => {  }
+                        }
+                    },
+                    None => {
+                        let (index, _) = stack.pop().unwrap();
+                        marks[index.index()] = Mark::Visited;
+                        order.push(index);
+                    }
+                }
+            }
+        }
+
+        (order, recurrent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn float_literals_are_valid_rust() {
+        assert_eq!(CompiledBrain::float_literal(0.5f32), "0.5");
+        assert_eq!(CompiledBrain::float_literal(-2f32), "-2.0");
+        assert_eq!(CompiledBrain::float_literal(f32::NAN), "f32::NAN");
+        assert_eq!(CompiledBrain::float_literal(f32::INFINITY), "f32::INFINITY");
+        assert_eq!(CompiledBrain::float_literal(f32::NEG_INFINITY), "f32::NEG_INFINITY");
+    }
+}
@@ -1,19 +1,23 @@
 use std::fmt;
 use std::fmt::Formatter;
 
-use rand::{Rng, thread_rng};
+use rand::Rng;
 
 use strum::IntoEnumIterator;
 
-#[derive(Clone)]
-pub(crate) struct Gene(pub(crate) u8);
+use crate::error::{GenomeEditError, GenomeError};
+use crate::simulation::Prng;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
+pub struct Gene(pub u8);
 
 impl Gene {
-    pub(crate) fn new(data: u8) -> Self {
+    pub fn new(data: u8) -> Self {
         Gene(data)
     }
 
-    pub(crate) fn parse(&self) -> GeneParse {
+    pub fn parse(&self) -> GeneParse {
         use GeneParse::*;
 
         if Gene::get_bit(self.0, 7) {
@@ -22,31 +26,79 @@ impl Gene {
                 Gene::get_bit(self.0, 6)
             )
         } else if Gene::get_bit(self.0, 6) {
+            // 2 bits pick the activation, and 4 more the bias in eighths, from 0 to 1.875
+            let index = Gene::get_bit_range(self.0, 4..6) as usize;
             Internal(
-                Gene::get_bit_range(self.0, 0..6) as f32 / 32f32
+                Gene::get_bit_range(self.0, 0..4) as f32 / 8f32,
+                Gene::ACTIVATIONS[index]
             )
-        } else {
-            let index = Gene::get_bit_range(self.0, 0..5) as usize;
-            if Gene::get_bit(self.0, 5) {
-                Action(ActionType::iter().nth(index % ActionType::iter().count()).unwrap())
+        } else if Gene::get_bit(self.0, 5) {
+            if Gene::get_bit(self.0, 4) {
+                // attribute genes don't add a node to the brain
+                let index = Gene::get_bit_range(self.0, 2..4) as usize;
+                Attribute(
+                    Gene::ATTRIBUTES[index],
+                    Gene::get_bit_range(self.0, 0..2) as f32 / 3f32
+                )
             } else {
-                Sense(SenseType::iter().nth(index % SenseType::iter().count()).unwrap())
+                let index = Gene::get_bit_range(self.0, 0..4) as usize;
+                Action(Gene::ACTIONS[index])
             }
+        } else {
+            let index = Gene::get_bit_range(self.0, 0..5) as usize;
+            Sense(Gene::SENSES[index])
         }
     }
 
-    pub(crate) fn mutate(&mut self) {
-        self.0 ^= 1u8.rotate_left(thread_rng().gen_range(0..8));
+    pub fn mutate(&mut self, prng: &mut Prng) {
+        self.0 ^= 1u8.rotate_left(prng.gen_range(0..8));
     }
 
-    pub(crate) fn from_string(data: &str) -> Result<Self, std::io::Error> {
-        match u8::from_str_radix(data, 2) {
-            Ok(d) => Ok(Gene::new(d)),
-            _ => Err(std::io::Error::new(std::io::ErrorKind::Other, ""))
+    // None unless the data is exactly 8 binary digits
+    // from_str_radix alone would also accept shorter genes and a leading '+'
+    pub fn from_string(data: &str) -> Option<Self> {
+        if data.len() != 8 || !data.bytes().all(|bit| bit == b'0' || bit == b'1') {
+            return None;
         }
+
+        u8::from_str_radix(data, 2).ok().map(Gene::new)
     }
 }
 
+// Genes index these tables rather than counting through the variants of each enum,
+// so adding a variant never changes what an existing genome decodes to
+// Tables with more slots than variants repeat them in order
+// Giving a new variant a slot changes the layout, so Genome::LAYOUT must be bumped along with the tables
+impl Gene {
+    pub const SENSES: [SenseType; 32] = {
+        use SenseType::*;
+        [
+            Blocked, Agent, AgentDensity, Food, FoodDensity, Direction, LastAction, FoodToxicity,
+            Water, Thirst, Temperature, Weather, Blocked, Agent, AgentDensity, Food,
+            FoodDensity, Direction, LastAction, FoodToxicity, Water, Thirst, Temperature, Weather,
+            Blocked, Agent, AgentDensity, Food, FoodDensity, Direction, LastAction, FoodToxicity
+        ]
+    };
+
+    pub const ACTIONS: [ActionType; 16] = {
+        use ActionType::*;
+        [
+            Move, TurnLeft, TurnRight, Kill, ProduceFood, Drink, Move, TurnLeft,
+            TurnRight, Kill, ProduceFood, Drink, Move, TurnLeft, TurnRight, Kill
+        ]
+    };
+
+    pub const ACTIVATIONS: [ActivationType; 4] = {
+        use ActivationType::*;
+        [Tanh, Sigmoid, Relu, Step]
+    };
+
+    pub const ATTRIBUTES: [AttributeType; 4] = {
+        use AttributeType::*;
+        [TurnBias, Gait, Spread, Species]
+    };
+}
+
 // some helper functions
 impl Gene {
     fn get_bit(data: u8, index: usize) -> bool {
@@ -77,73 +129,358 @@ impl fmt::Display for Gene {
     }
 }
 
-pub(crate) struct Genome;
+/// Operations on genomes, which are sequences of [`Gene`]s.
+/// Each gene is a byte that becomes a sense, action or internal node of the brain, a connection between nodes, or an attribute.
+pub struct Genome;
 
 impl Genome {
+    /// The version of the gene tables in [`Gene`] and of the values in [`ActionType::sensed`],
+    /// which changes whenever a genome would grow or sense differently.
+    pub const LAYOUT: u32 = 4;
+
     const MUTATION_FREQUENCY: f32 = 0.15;
 
-    pub(crate) fn mutate(mut genome: Vec<Gene>) -> String {
-        if thread_rng().gen_range(0..100) as f32 / 100f32 < Self::MUTATION_FREQUENCY {
-            if thread_rng().gen_bool(0.5f64) {
-                genome.push(Gene::new(thread_rng().gen_range(0..=255)));
+    // the longest run of genes that a single structural mutation can affect
+    const MAX_SEGMENT_LENGTH: usize = 8;
+
+    /// Returns a mutated copy of the genome, written as space-separated genes.
+    /// `rate` scales MUTATION_FREQUENCY, it is 1 unless the population is hypermutating.
+    pub fn mutate(mut genome: Vec<Gene>, weights: &MutationWeights, rate: f32, prng: &mut Prng) -> String {
+        let frequency = (Self::MUTATION_FREQUENCY * rate).min(1f32);
+        if prng.gen_range(0..100) as f32 / 100f32 < frequency {
+            // an empty genome can only grow
+            let mutation = if genome.is_empty() {
+                Some(MutationType::Insertion)
             } else {
-                genome.remove(thread_rng().gen_range(0..genome.len()));
+                weights.choose(prng)
+            };
+
+            if let Some(mutation) = mutation {
+                Self::mutate_structure(&mut genome, mutation, prng);
             }
 
         } else {
             let length = genome.len();
-            for _ in 0..(length as f32 * Self::MUTATION_FREQUENCY) as usize {
-                genome[thread_rng().gen_range(0..length)].mutate();
+            for _ in 0..(length as f32 * frequency) as usize {
+                genome[prng.gen_range(0..length)].mutate(prng);
             }
         }
 
         Genome::get(genome)
     }
 
-    pub(crate) fn get(genome: Vec<Gene>) -> String {
+    fn mutate_structure(genome: &mut Vec<Gene>, mutation: MutationType, prng: &mut Prng) {
+        use MutationType::*;
+        match mutation {
+            Insertion => {
+                genome.push(Gene::new(prng.gen_range(0..=255)));
+            },
+            Deletion => {
+                genome.remove(prng.gen_range(0..genome.len()));
+            },
+            Duplication => {
+                let segment = Self::segment(genome.len(), prng);
+                let copy = genome[segment.clone()].to_vec();
+
+                // the copy is placed directly after the original segment
+                genome.splice(segment.end..segment.end, copy);
+            },
+            Inversion => {
+                let segment = Self::segment(genome.len(), prng);
+                genome[segment].reverse();
+            },
+            Translocation => {
+                let segment = Self::segment(genome.len(), prng);
+                let moved = genome.drain(segment).collect::<Vec<Gene>>();
+
+                let position = prng.gen_range(0..=genome.len());
+                genome.splice(position..position, moved);
+            }
+        }
+    }
+
+    // picks a random, non-empty run of genes from a genome of the given length
+    fn segment(length: usize, prng: &mut Prng) -> std::ops::Range<usize> {
+        let start = prng.gen_range(0..length);
+        let end = prng.gen_range(
+            (start + 1)..=usize::min(length, start + Self::MAX_SEGMENT_LENGTH)
+        );
+
+        start..end
+    }
+
+    /// Writes the genome as space-separated genes.
+    pub fn get(genome: Vec<Gene>) -> String {
         Self::get_with_delim(genome, " ")
     }
 
-    pub(crate) fn get_with_delim(genome: Vec<Gene>, delim: &str) -> String {
+    pub fn get_with_delim(genome: Vec<Gene>, delim: &str) -> String {
         genome.iter().fold("".to_owned(), |mut genome: String, current| {
-            genome.push_str(&*format!("{}{}", current, delim));
+            genome.push_str(&format!("{}{}", current, delim));
             genome
         }).trim_end().to_string()
     }
 
-    pub(crate) fn from_string(data: String) -> Vec<Gene> {
+    /// Parses a genome written as whitespace-separated 8-bit binary genes.
+    pub fn from_string(data: String) -> Result<Vec<Gene>, GenomeError> {
         let mut genome: Vec<Gene> = Vec::new();
-        for g in data.split(' ') {
-            if let Ok(gene) = Gene::from_string(g) { genome.push(gene) }
+        for (index, g) in data.split_whitespace().enumerate() {
+            genome.push(Gene::from_string(g).ok_or_else(|| GenomeError::MalformedGene {
+                index,
+                gene: g.to_string()
+            } )?);
+        }
+
+        Ok(genome)
+    }
+}
+
+// Genome arithmetic, for designing hybrids by hand
+// Regions are ranges of gene indices, and may be empty to insert rather than replace
+impl Genome {
+    // Appends the second genome to the end of the first
+    pub fn merge(mut first: Vec<Gene>, mut second: Vec<Gene>) -> Vec<Gene> {
+        first.append(&mut second);
+        first
+    }
+
+    // Replaces a region of the target with the donor's genes
+    pub fn splice(mut target: Vec<Gene>, region: std::ops::Range<usize>, donor: &[Gene]) -> Vec<Gene> {
+        target.splice(region, donor.iter().cloned());
+        target
+    }
+
+    // Replaces every gene in a region with a random one
+    pub fn randomize(mut genome: Vec<Gene>, region: std::ops::Range<usize>, prng: &mut Prng) -> Vec<Gene> {
+        for gene in genome[region].iter_mut() {
+            *gene = Gene::new(prng.gen_range(0..=255));
         }
 
         genome
     }
+
+    // Parses a region such as `4..12`, covering genes 4 to 11, or `4` for a single gene
+    // An empty string covers the whole genome
+    pub fn region(text: &str, length: usize) -> Result<std::ops::Range<usize>, GenomeEditError> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(0..length);
+        }
+
+        let invalid = || GenomeEditError::InvalidRegion(text.to_string(), length);
+        let region = match text.split_once("..") {
+            Some((start, end)) => {
+                let start = start.trim().parse::<usize>().map_err(|_| invalid())?;
+                let end = end.trim().parse::<usize>().map_err(|_| invalid())?;
+                start..end
+            },
+            None => {
+                let index = text.parse::<usize>().map_err(|_| invalid())?;
+                index..(index + 1)
+            }
+        };
+
+        if region.start > region.end || region.end > length {
+            return Err(invalid());
+        }
+
+        Ok(region)
+    }
+}
+
+#[derive(Debug, Copy, Clone, strum_macros::EnumIter)]
+pub enum MutationType {
+    Insertion,
+    Deletion,
+    Duplication,
+    Inversion,
+    Translocation
+}
+
+// Relative likelihood of each structural mutation
+// Structural mutations occur in place of point mutations with probability MUTATION_FREQUENCY
+#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub struct MutationWeights {
+    pub insertion: u32,
+    pub deletion: u32,
+    pub duplication: u32,
+    pub inversion: u32,
+    pub translocation: u32
+}
+
+impl Default for MutationWeights {
+    fn default() -> Self {
+        Self {
+            insertion: 3,
+            deletion: 3,
+            duplication: 2,
+            inversion: 1,
+            translocation: 1
+        }
+    }
+}
+
+impl MutationWeights {
+    pub fn weight(&self, mutation: MutationType) -> u32 {
+        use MutationType::*;
+        match mutation {
+            Insertion => self.insertion,
+            Deletion => self.deletion,
+            Duplication => self.duplication,
+            Inversion => self.inversion,
+            Translocation => self.translocation
+        }
+    }
+
+    // Returns None if every weight is zero
+    pub fn choose(&self, prng: &mut Prng) -> Option<MutationType> {
+        let total = MutationType::iter().map(|mutation| self.weight(mutation)).sum::<u32>();
+        if total == 0 {
+            return None;
+        }
+
+        let mut choice = prng.gen_range(0..total);
+        for mutation in MutationType::iter() {
+            if choice < self.weight(mutation) {
+                return Some(mutation);
+            }
+
+            choice -= self.weight(mutation);
+        }
+
+        None
+    }
 }
 
 #[derive(Debug, Clone)]
-pub(crate) enum GeneParse {
+pub enum GeneParse {
     Sense(SenseType),
     Action(ActionType),
-    Internal(f32),
+    Internal(f32, ActivationType),
     Connection(usize, bool),
+    Attribute(AttributeType, f32)
 }
 
-#[derive(Debug, Copy, Clone, strum_macros::EnumIter)]
-pub(crate) enum SenseType {
+// Attributes modify how an Agent's action outputs are resolved
+// Each attribute gene carries a strength between 0 and 1
+#[derive(Debug, Copy, Clone, PartialEq, Eq, strum_macros::EnumIter)]
+pub enum AttributeType {
+    // weak genes favor turning left, strong genes favor turning right
+    TurnBias,
+    // favors alternating between moving and turning
+    Gait,
+    // spreads produced food further, see YieldPattern
+    Spread,
+    // the first species gene decides which species the Agent belongs to
+    Species
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, strum_macros::EnumIter)]
+pub enum SenseType {
     Blocked,
     Agent,
     AgentDensity,
     Food,
     FoodDensity,
-    Direction
+    Direction,
+    // the Agent's previous action, 0 if it hasn't acted yet
+    LastAction,
+    // 1 if the faced Tile holds toxic food
+    FoodToxicity,
+    // 1 if the faced Tile holds water
+    Water,
+    // 0 when the Agent has just drunk, 1 once it is dehydrated
+    Thirst,
+    // the temperature of the Agent's own Tile, 0.5 is mild
+    Temperature,
+    // the severity of the weather, 0 is calm and always 0 without Weather
+    Weather
 }
 
-#[derive(Debug, Copy, Clone, strum_macros::EnumIter)]
-pub(crate) enum ActionType {
+// Applied to the output of internal nodes
+// There is one for each of the 4 values of an internal gene's activation bits, so none is favored
+#[derive(Debug, Copy, Clone, PartialEq, Eq, strum_macros::EnumIter)]
+pub enum ActivationType {
+    Tanh,
+    Sigmoid,
+    Relu,
+    Step
+}
+
+impl ActivationType {
+    pub fn apply(&self, value: f32) -> f32 {
+        use ActivationType::*;
+        match self {
+            Tanh => value.tanh(),
+            Sigmoid => 1f32 / (1f32 + (-value).exp()),
+            Relu => value.max(0f32),
+            Step => if value > 0f32 { 1f32 } else { 0f32 }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, strum_macros::EnumIter, serde::Serialize, serde::Deserialize)]
+pub enum ActionType {
     Move,
     TurnLeft,
     TurnRight,
     Kill,
     ProduceFood,
-}
\ No newline at end of file
+    Drink,
+}
+
+impl ActionType {
+    // What the LastAction sense reports after the action, pinned like the gene tables
+    // Actions added later take values that are still free, leaving the rest as evolved brains know them
+    // 0 is left for Agents that haven't acted yet
+    pub fn sensed(&self) -> f32 {
+        use ActionType::*;
+        match self {
+            Move => 0.2f32,
+            TurnLeft => 0.4f32,
+            TurnRight => 0.6f32,
+            Kill => 0.8f32,
+            ProduceFood => 1f32,
+            Drink => 0.1f32
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_variant_has_a_gene() {
+        assert!(SenseType::iter().all(|sense| Gene::SENSES.contains(&sense)));
+        assert!(ActionType::iter().all(|action| Gene::ACTIONS.contains(&action)));
+        assert!(ActivationType::iter().all(|activation| Gene::ACTIVATIONS.contains(&activation)));
+        assert!(AttributeType::iter().all(|attribute| Gene::ATTRIBUTES.contains(&attribute)));
+    }
+
+    // Every action is sensed as a distinct value in (0, 1]
+    #[test]
+    fn actions_are_sensed_apart() {
+        let sensed = ActionType::iter().map(|action| action.sensed()).collect::<Vec<f32>>();
+        assert!(sensed.iter().all(|value| *value > 0f32 && *value <= 1f32));
+        assert!(sensed.iter().enumerate().all(|(index, value)| !sensed[index + 1..].contains(value)));
+    }
+
+    #[test]
+    fn genes_are_exactly_eight_bits() {
+        assert_eq!(Gene::from_string("00101111").map(|gene| gene.0), Some(0b00101111));
+        assert!(Gene::from_string("101").is_none());
+        assert!(Gene::from_string("+1010101").is_none());
+        assert!(Gene::from_string("001011110").is_none());
+        assert!(matches!(Genome::from_string(String::from("00101111 11")),
+            Err(GenomeError::MalformedGene { index: 1, .. })));
+    }
+
+    #[test]
+    fn genes_decode_through_the_tables() {
+        assert!(matches!(Gene::new(0b00001100).parse(), GeneParse::Sense(SenseType::Blocked)));
+        assert!(matches!(Gene::new(0b00101111).parse(), GeneParse::Action(ActionType::Kill)));
+        assert!(matches!(Gene::new(0b00111011).parse(), GeneParse::Attribute(AttributeType::Spread, strength) if strength == 1f32));
+        assert!(matches!(Gene::new(0b01100100).parse(), GeneParse::Internal(bias, ActivationType::Relu) if bias == 0.5));
+    }
+}
@@ -0,0 +1,48 @@
+// A hand-written genome with a known behavior
+// These serve as baselines to compare evolved agents against, and as seeds for experiments
+#[derive(Debug, Copy, Clone)]
+pub struct Primitive {
+    pub name: &'static str,
+    pub description: &'static str,
+    // in the same format as an imported genome
+    pub genome: &'static str
+}
+
+// ReLU internal nodes with a bias of 1.75 are chained to amplify weak senses like food density
+// Every signal they see is positive, so ReLU passes it through unchanged
+// The genomes only use the first senses and actions of each list, so they keep their meaning as more are added
+pub const LIBRARY: [Primitive; 4] = [
+    Primitive {
+        name: "Wall Follower",
+        description: "Keeps a wall on its right. A gait gene makes it alternate between moving and turning right, \
+            and it turns left whenever it's blocked, so it traces the edge of any wall or water it finds. \
+            In open ground it circles on the spot",
+        genome: "00000000 00100000 00100001 00100010 01100010 01101110 01101110 01101110 00110111 \
+            11000100 10000001 10000000 10000001 11000100 10000011 11000000 10000101 \
+            11000101 10000110 11000110 10000111 11000111 10000010"
+    },
+    Primitive {
+        name: "Food Seeker",
+        description: "Walks towards food while any is in sight, eating whatever it reaches, \
+            and turns on the spot to scan when it can't see any or is blocked",
+        genome: "00000011 00000100 00000000 00100000 00100010 01101110 01101110 01101110 01100010 \
+            11000000 10000011 11000001 10000101 11000101 10000110 11000110 10000111 \
+            11000111 10000011 10000000 10000011 11000010 10000100 11001000 10000100"
+    },
+    Primitive {
+        name: "Pacifist Producer",
+        description: "Never kills. It produces food whenever the tile ahead is free, eats any food in front of it, \
+            and turns away from anything blocking it",
+        genome: "00000011 00000000 00100000 00100100 00100010 01100010 \
+            11000000 10000010 11000101 10000011 11000001 10000100"
+    },
+    Primitive {
+        name: "Random Walker",
+        description: "Walks straight ahead, veering left whenever food comes into view and right when it's blocked, \
+            and only eats food it happens to bump into. Its turns are set off by whatever is nearby rather than by any goal, \
+            so it wanders the world and makes a null model for the others",
+        genome: "00000100 00000010 00000000 00100000 00100001 00100010 01100010 01101110 01101110 01101110 01101110 00000011 \
+            11000110 10000011 11001011 10000011 11000000 10000111 11000111 10001000 11001000 10000100 \
+            11000001 10001001 11001001 10001010 11001010 10000101 11000010 10000101"
+    }
+];
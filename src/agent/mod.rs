@@ -1,49 +1,74 @@
-pub(crate) mod gene;
+pub mod gene;
+pub mod compiled;
+pub mod library;
 
+use std::cell;
 use std::fmt;
 use std::fmt::Formatter;
 
 use petgraph::graph;
 use petgraph::graph::NodeIndex;
 
-use rand::{Rng, thread_rng};
-use rand::rngs::StdRng;
+use rand::Rng;
 
 use gene::Gene;
 use gene::GeneParse;
 
-use crate::simulation::Sense;
+use crate::error::GenomeError;
+use crate::simulation::{Prng, Sense};
 
 #[derive(Debug, Clone)]
-pub(crate) enum Node {
+pub enum Node {
     Sense(gene::SenseType),
     Action(gene::ActionType),
-    Internal(f32)
+    Internal {
+        bias: f32,
+        activation: gene::ActivationType
+    }
+}
+
+// A connection between two nodes
+#[derive(Debug, Copy, Clone)]
+pub struct Edge {
+    // additive edges pass their input through, inhibitory edges invert it
+    pub additive: bool,
+    // plastic edges change strength over the Agent's lifetime
+    pub plastic: bool
+}
+
+impl fmt::Display for Edge {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}",
+            if self.additive { "additive" } else { "inhibitory" },
+            if self.plastic { ", plastic" } else { "" }
+        )
+    }
 }
 
 impl fmt::Display for Node {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            Node::Internal { bias, activation } => write!(f, "Internal({}, {:?})", bias, activation),
+            _ => write!(f, "{:?}", self)
+        }
     }
 }
 
-#[derive(Debug, Copy, Clone)]
-pub(crate) enum Direction {
+#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub enum Direction {
     Up,
     Down,
     Left,
     Right
 }
 
-impl Default for Direction {
-    fn default() -> Self {
+impl Direction {
+    pub fn random(prng: &mut Prng) -> Self {
         use Direction::*;
-        vec![Up, Down, Left, Right][thread_rng().gen_range(0..3)]
+        [Up, Down, Left, Right][prng.gen_range(0..4)]
     }
-}
 
-impl Direction {
-    pub(crate) fn left(&self) -> Self {
+    pub fn left(&self) -> Self {
         use Direction::*;
 
         match self {
@@ -54,7 +79,7 @@ impl Direction {
         }
     }
 
-    pub(crate) fn right(&self) -> Self {
+    pub fn right(&self) -> Self {
         use Direction::*;
 
         match self {
@@ -65,7 +90,7 @@ impl Direction {
         }
     }
 
-    pub(crate) fn opposite(&self) -> Self {
+    pub fn opposite(&self) -> Self {
         use Direction::*;
         match self {
             Up => Down,
@@ -76,22 +101,234 @@ impl Direction {
     }
 }
 
+// Determines how an Agent picks between its activated action nodes
+#[derive(Debug, Copy, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub enum ActionSelection {
+    // always take the most strongly activated action
+    #[default]
+    Argmax,
+    // sample actions in proportion to exp(weight / temperature)
+    // higher temperatures make the choice more random
+    Softmax { temperature: f32 }
+}
+
+impl ActionSelection {
+    fn select(&self, candidates: &[(gene::ActionType, f32)], prng: &mut Prng) -> Option<gene::ActionType> {
+        let mut dominant: Option<(gene::ActionType, f32)> = None;
+        for &(variant, weight) in candidates {
+            dominant = Some(
+                if let Some(highest) = dominant {
+                    if weight > highest.1 {
+                        (variant, weight)
+                    } else { highest }
+                } else {
+                    (variant, weight)
+                }
+            )
+        }
+
+        match *self {
+            ActionSelection::Softmax { temperature } if temperature > 0f32 => {
+                // subtracting the highest weight keeps exp from overflowing
+                let highest = dominant?.1;
+                let weights = candidates.iter().map(|(_, weight)| {
+                    ((weight - highest) / temperature).exp()
+                } ).collect::<Vec<f32>>();
+
+                let mut choice = prng.gen::<f32>() * weights.iter().sum::<f32>();
+                for (&(variant, _), weight) in candidates.iter().zip(weights.iter()) {
+                    if choice < *weight {
+                        return Some(variant);
+                    }
+
+                    choice -= weight;
+                }
+
+                dominant.map(|t| t.0)
+            },
+            _ => dominant.map(|t| t.0)
+        }
+    }
+}
+
+// A summary of an Agent's brain, used to track how brains evolve
+#[derive(Debug, Clone, Default)]
+pub struct BrainStats {
+    pub senses: usize,
+    pub internals: usize,
+    pub actions: usize,
+    pub edges: usize,
+    // edges that close a cycle
+    pub recurrent: usize,
+    pub plastic: usize,
+    // the longest chain of edges from an input to an output, ignoring recurrent edges
+    pub depth: usize,
+    pub connectivity: Vec<ActionConnectivity>
+}
+
+#[derive(Debug, Clone)]
+pub struct ActionConnectivity {
+    pub action: gene::ActionType,
+    // directly connected nodes
+    pub inputs: usize,
+    // all nodes with a path to the action
+    pub upstream: usize
+}
+
+impl fmt::Display for BrainStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "nodes: {}\n  senses: {}\n  internals: {}\n  actions: {}\nedges: {} ({} recurrent, {} plastic)\ndepth: {}",
+            self.senses + self.internals + self.actions,
+            self.senses,
+            self.internals,
+            self.actions,
+            self.edges,
+            self.recurrent,
+            self.plastic,
+            self.depth
+        )?;
+
+        for connectivity in self.connectivity.iter() {
+            write!(f, "\n\n{:?}\n  inputs: {}\n  upstream nodes: {}",
+                connectivity.action,
+                connectivity.inputs,
+                connectivity.upstream
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// Where an Agent's ProduceFood action deposits food
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum YieldPattern {
+    // a single unit on the facing tile
+    #[default]
+    Ahead,
+    // a unit on the facing tile and on each tile diagonally ahead of the Agent
+    Arc,
+    // a single unit dropped where the Agent stands, landing on the tile behind it
+    Beneath
+}
+
+// Heritable modifiers coded by attribute genes
+// Repeated attribute genes accumulate, up to Attributes::LIMIT
+#[derive(Debug, Copy, Clone, Default)]
+pub struct Attributes {
+    // positive values favor turning right, negative values favor turning left
+    pub turn_bias: f32,
+    pub gait: f32,
+    pub spread: f32,
+    // unlike the others, species genes don't accumulate
+    species: Option<u8>
+}
+
+impl Attributes {
+    const LIMIT: f32 = 1f32;
+    // the number of distinct species
+    pub const SPECIES: u8 = 4;
+
+    fn express(&mut self, attribute: gene::AttributeType, strength: f32) {
+        use gene::AttributeType::*;
+        match attribute {
+            TurnBias => self.turn_bias = (self.turn_bias + strength * 2f32 - 1f32).clamp(-Self::LIMIT, Self::LIMIT),
+            Gait => self.gait = (self.gait + strength).min(Self::LIMIT),
+            Spread => self.spread = (self.spread + strength).min(Self::LIMIT),
+            Species => if self.species.is_none() {
+                self.species = Some((strength * (Self::SPECIES - 1) as f32).round() as u8);
+            }
+        }
+    }
+
+    // Agents without a species gene belong to species 0
+    pub fn species(&self) -> u8 {
+        self.species.unwrap_or(0)
+    }
+
+    // Agents without spread genes produce food directly ahead
+    pub fn yield_pattern(&self) -> YieldPattern {
+        use YieldPattern::*;
+        match self.spread {
+            spread if spread < Self::LIMIT / 3f32 => Ahead,
+            spread if spread < Self::LIMIT * 2f32 / 3f32 => Arc,
+            _ => Beneath
+        }
+    }
+
+    // Adjusts the output of each candidate action, given the previous action
+    fn apply(&self, candidates: &mut [(gene::ActionType, f32)], previous: Option<gene::ActionType>) {
+        use gene::ActionType::*;
+
+        let turned = matches!(previous, Some(TurnLeft | TurnRight));
+        let moved = matches!(previous, Some(Move));
+        for (variant, weight) in candidates.iter_mut() {
+            match variant {
+                TurnLeft => *weight -= self.turn_bias,
+                TurnRight => *weight += self.turn_bias,
+                _ => {  }
+            }
+
+            match variant {
+                Move if turned => *weight += self.gait,
+                TurnLeft | TurnRight if moved => *weight += self.gait,
+                _ => {  }
+            }
+        }
+    }
+}
+
+/// A creature, whose brain is built from its genome when it is created.
+/// Each step it senses its surroundings, and its brain chooses an action, see [`Agent::process`].
 #[derive(Clone)]
-pub(crate) struct Agent {
-    pub(crate) brain: graph::Graph<Node, bool>,
-    pub(crate) genome: Vec<Gene>,
-    pub(crate) fitness: ux::u5,
-    pub(crate) direction: Direction,
-    pub(crate) history: Vec<gene::ActionType>,
-    pub(crate) energy: ux::u5
+pub struct Agent {
+    pub brain: graph::Graph<Node, Edge>,
+    pub genome: Vec<Gene>,
+    pub fitness: ux::u5,
+    pub direction: Direction,
+    // the tick of each recent action, and whether it had any effect, newest first
+    pub history: Vec<(u64, gene::ActionType, bool)>,
+    pub energy: ux::u5,
+    // rises with each action in worlds with water, and is reset by drinking
+    pub thirst: ux::u5,
+    pub marker: Option<u8>,
+    // assigned by the Simulation when the Agent is placed, unique within a run
+    pub id: u64,
+    // the tick the Agent was placed on
+    pub born: u64,
+    pub offspring: u32,
+    pub attributes: Attributes,
+    // set when the Agent eats, and cleared once it has learned from the action
+    fed: bool,
+    // set while the Agent is followed, so its brain keeps each node's output for display
+    recording: cell::Cell<bool>,
+    // fractional energy owed for running the brain, drained once it reaches a whole unit
+    metabolism: f32,
+    // the brain is only kept as a graph for display, evaluation uses the compiled copy
+    compiled: compiled::CompiledBrain
 }
 
 impl Agent {
     const HISTORY_SIZE: usize = 20;
 
-    pub(crate) fn new(genome: Vec<Gene>) -> Result<Self, std::io::Error> {
+    /// Builds an Agent from a genome, failing if the genome can't produce a brain that acts.
+    pub fn new(genome: Vec<Gene>, prng: &mut Prng) -> Result<Self, GenomeError> {
+        let mut agent = Self::from_genome(genome)?;
+        agent.fitness = ux::u5::new(prng.gen_range(0..=15));
+        agent.direction = Direction::random(prng);
+
+        Ok(agent)
+    }
+
+    // Builds the Agent's brain, leaving the rest of its state at its defaults
+    fn from_genome(genome: Vec<Gene>) -> Result<Self, GenomeError> {
         use GeneParse::*;
-        let mut brain: graph::Graph<Node, bool> = graph::Graph::new();
+
+        if genome.is_empty() {
+            return Err(GenomeError::EmptyGenome);
+        }
+
+        let mut brain: graph::Graph<Node, Edge> = graph::Graph::new();
 
         let mut edges: Vec<GeneParse> = Vec::new();
         for gene in genome.iter() {
@@ -99,21 +336,26 @@ impl Agent {
             match parsed {
                 Sense(variant) => { brain.add_node(Node::Sense(variant)); },
                 Action(variant) => { brain.add_node(Node::Action(variant)); },
-                Internal(bias) => { brain.add_node(Node::Internal(bias)); },
-                Connection(..) => { edges.push(parsed); }
+                Internal(bias, activation) => { brain.add_node(Node::Internal { bias, activation }); },
+                Connection(..) => { edges.push(parsed); },
+                Attribute(..) => {  }
             }
         }
 
+        if brain.node_count() == 0 {
+            return Err(GenomeError::EmptyBrain);
+        }
+
         for i in 0..(edges.len() / 2) {
-            if let Connection(a, inverted) = &edges[i * 2] {
-                if let Connection(b, ..) = &edges[i * 2 + 1] {
-                    if brain.node_count() == 0 {
-                        return Err(std::io::Error::new(std::io::ErrorKind::Other, "Invalid Genome"));
-                    }
+            if let Connection(a, additive) = &edges[i * 2] {
+                if let Connection(b, plastic) = &edges[i * 2 + 1] {
                     let a = (*a % brain.node_count()) as u32;
                     let b = (*b % brain.node_count()) as u32;
 
-                    brain.add_edge(NodeIndex::from(a), NodeIndex::from(b), *inverted);
+                    brain.add_edge(NodeIndex::from(a), NodeIndex::from(b), Edge {
+                        additive: *additive,
+                        plastic: *plastic
+                    } );
                 }
             }
         }
@@ -146,14 +388,7 @@ impl Agent {
             }
         }
 
-        let mut agent = Self {
-            brain,
-            genome,
-            fitness: ux::u5::new(thread_rng().gen_range(0..=15)),
-            direction: Direction::default(),
-            history: Vec::new(),
-            energy: ux::u5::MAX,
-        };
+        let mut agent = Self::with_brain(genome, brain);
 
         let mut retain: Vec<NodeIndex> = Vec::new();
         for index in agent.brain.node_indices() {
@@ -173,11 +408,56 @@ impl Agent {
             }
         });
 
-        agent.brain.shrink_to_fit();
+        // laid out exactly as a cached brain would be, since evaluation follows the order of each node's edges
+        if let Some(brain) = CachedBrain::decode(&CachedBrain::encode(&agent.brain)) {
+            agent.brain = brain;
+        }
+
+        if !agent.brain.node_weights().any(|node| matches!(node, Node::Action(..))) {
+            return Err(GenomeError::NoActionNodes);
+        }
+
+        agent.compiled = compiled::CompiledBrain::new(&agent.brain);
 
         Ok(agent)
     }
 
+    // Skips building and pruning the brain, which has already been done for this genome
+    fn from_cache(genome: Vec<Gene>, brain: graph::Graph<Node, Edge>) -> Self {
+        let mut agent = Self::with_brain(genome, brain);
+        agent.compiled = compiled::CompiledBrain::new(&agent.brain);
+        agent
+    }
+
+    // The brain is left uncompiled
+    fn with_brain(genome: Vec<Gene>, brain: graph::Graph<Node, Edge>) -> Self {
+        let mut attributes = Attributes::default();
+        for gene in genome.iter() {
+            if let GeneParse::Attribute(attribute, strength) = gene.parse() {
+                attributes.express(attribute, strength);
+            }
+        }
+
+        Self {
+            brain,
+            genome,
+            fitness: ux::u5::MIN,
+            direction: Direction::Up,
+            history: Vec::new(),
+            energy: ux::u5::MAX,
+            thirst: ux::u5::MIN,
+            marker: None,
+            id: 0,
+            born: 0,
+            offspring: 0,
+            attributes,
+            fed: false,
+            recording: cell::Cell::new(false),
+            metabolism: 0f32,
+            compiled: compiled::CompiledBrain::default()
+        }
+    }
+
     fn prune(&mut self, index: NodeIndex, processed: &mut Vec<NodeIndex>) {
         processed.push(index);
         let mut walk = self.brain.neighbors_directed(index, petgraph::Direction::Incoming).detach();
@@ -188,98 +468,61 @@ impl Agent {
         }
     }
 
-    pub(crate) fn process(&self, sense: &Sense) -> Option<gene::ActionType> {
-        let mut dominant: Option<(gene::ActionType, f32)> = None;
-        for index in self.brain.externals(petgraph::Direction::Outgoing) {
-            if let Node::Action(variant) = &self.brain[index] {
-                if let Some(weight) = self.process_node(index, sense, &mut Vec::new()) {
-                    dominant = Some(
-                        if let Some(highest) = dominant {
-                            if weight > highest.1 {
-                                (*variant, weight)
-                            } else { highest }
-                        } else {
-                            (*variant, weight)
-                        }
-                    )
-                }
+    /// Evaluates the brain against what the Agent senses and chooses an action, if any action node received input.
+    /// The previously chosen action receives a bonus of `hysteresis`,
+    /// which keeps near-tied actions from rapidly alternating.
+    pub fn process(&self, sense: &Sense, hysteresis: f32, selection: ActionSelection, prng: &mut Prng) -> Option<gene::ActionType> {
+        let previous = self.last_action();
+
+        let mut candidates = self.compiled.evaluate(sense, self.recording.get());
+        for (variant, weight) in candidates.iter_mut() {
+            if previous == Some(*variant) {
+                *weight += hysteresis;
             }
         }
 
-        dominant.map(|t| t.0)
-    }
+        self.attributes.apply(&mut candidates, previous);
 
-    fn process_node(&self, index: NodeIndex, sense: &Sense, history: &mut Vec<NodeIndex>) -> Option<f32> {
-        // check if the node walk is self-referential
-        // internal nodes return their bias as a constant
-        if history.contains(&index) {
-            if let Internal(bias) = self.brain[index] {
-                if self.brain.neighbors_directed(index, petgraph::Direction::Incoming).count() == 0 {
-                    return Some(bias);
-                }
-            }
-        }
+        selection.select(&candidates, prng)
+    }
 
-        use Node::*;
-        let mut bias = 1f32;
-        match &self.brain[index] {
-            Sense(variant) => {
-                return Some(sense.get(variant))
+    /// Creates an offspring from a mutated copy of the genome.
+    pub fn reproduce(&self, weights: &gene::MutationWeights, rate: f32, prng: &mut Prng) -> Result<Self, GenomeError> {
+        match Self::from_string(gene::Genome::mutate(self.genome.clone(), weights, rate, prng), prng) {
+            Ok(mut agent) => {
+                // markers are neutral and passed down unchanged
+                agent.marker = self.marker;
+                Ok(agent)
             },
-            Internal(b) => {
-                bias = *b;
-            }
-            _ => {}
-        };
-
-        if history.contains(&index) {
-            return if let Internal(bias) = self.brain[index] {
-                Some(bias)
-            } else {
-                None
-            }
+            Err(e) => Err(e)
         }
+    }
 
-        // get the corresponding edge between the `index` node and its parent
-        let edge = match history.last() {
-            Some(&t) => {
-                self.brain.find_edge(index, t).map(|k| self.brain[k])
-            },
-            None => None
-        };
+    // Starts keeping the output of each node in the brain every time the Agent is processed
+    pub fn record_activations(&self) {
+        self.recording.set(true);
+    }
 
-        history.push(index);
+    // Each node's output the last time the Agent was processed, indexed like the brain's nodes
+    // Returns None unless activations are being recorded
+    pub fn activations(&self) -> Option<Vec<Option<f32>>> {
+        self.recording.get().then(|| self.compiled.activations(&self.brain))
+    }
 
-        match self.brain.neighbors_directed(index, petgraph::Direction::Incoming).fold((0, 0f32), |(c, sum), r| {
-            if let Some(t) = self.process_node(r, sense, history) {
-                let mut t = t;
-                if let Some(b) = edge {
-                    t *= if b { 1f32 } else { -1f32 };
-                }
-                (c + 1, sum + t)
-            } else {
-                (c, sum)
-            }
-        }) {
-            (0, ..) => {
-                if let Internal(..) = &self.brain[index] {
-                    Some(bias)
-                } else {
-                    None
-                }
-            },
-            (c, sum) => Some(sum / c as f32 * bias)
-        }
+    // The Agent's brain as a standalone Rust function, see CompiledBrain::rust
+    pub fn controller(&self, hysteresis: f32) -> String {
+        self.compiled.rust(self.attributes.turn_bias, self.attributes.gait, hysteresis)
     }
 
-    pub(crate) fn reproduce(&self) -> Result<Self, std::io::Error> {
-        match Self::from_string(gene::Genome::mutate(self.genome.clone())) {
-            Ok(agent) => Ok(agent),
-            Err(e) => Err(e)
-        }
+    // Tags the Agent (and all of its future descendants) with a neutral marker
+    pub fn mark(&mut self, marker: u8) {
+        self.marker = Some(marker);
     }
 
-    pub(crate) fn acted(&mut self, action: gene::ActionType) {
+    // `metabolism` is the energy each brain node costs per action
+    // `plasticity` is the learning rate of plastic edges, which learn nothing when it is zero
+    // `thirst` is false in worlds without water, where Agents never grow thirsty
+    pub fn acted(&mut self, action: gene::ActionType, metabolism: f32, stress: f32, plasticity: f32, thirst: bool) {
         // actions reduce energy
         if self.energy > ux::u5::MIN {
             self.energy = self.energy - ux::u5::new(1);
@@ -297,6 +540,44 @@ impl Agent {
             self.energy = ux::u5::MIN;
         }
 
+        // dehydration costs fitness just like starvation
+        if thirst {
+            if self.thirst < ux::u5::MAX {
+                self.thirst = self.thirst + ux::u5::new(1);
+            } else if self.fitness > ux::u5::MIN {
+                self.fitness = self.fitness - ux::u5::new(1);
+            }
+        }
+
+        // larger brains are more expensive to run, and environmental stress drains energy on top
+        self.metabolism += metabolism * self.brain.node_count() as f32 + stress;
+        if self.metabolism >= 1f32 {
+            let drain = self.metabolism.floor();
+            self.metabolism -= drain;
+
+            let drain = (drain as u8).min(u8::from(self.energy));
+            self.energy = self.energy - ux::u5::new(drain);
+        }
+
+        // plastic edges that contributed to this action are strengthened if it led to food,
+        // and weakened if the Agent is starving
+        if plasticity > 0f32 {
+            let reward = if self.fed {
+                1f32
+            } else if self.starving() {
+                -1f32
+            } else {
+                0f32
+            };
+
+            self.compiled.reinforce(plasticity * reward);
+        }
+
+        self.fed = false;
+    }
+
+    // Records an action in the creature's history, once the Simulation knows whether it had any effect
+    pub fn remember(&mut self, tick: u64, action: gene::ActionType, successful: bool) {
         // truncate the creature's action history
         // its behavior can be generalized without a completely log of its actions
         if self.history.len() > Self::HISTORY_SIZE {
@@ -304,50 +585,151 @@ impl Agent {
         }
 
         // append the action to the creature's history
-        self.history.insert(0, action)
+        self.history.insert(0, (tick, action, successful))
+    }
+
+    pub fn last_action(&self) -> Option<gene::ActionType> {
+        self.history.first().map(|(_, action, _)| *action)
     }
 
     // Agents are sated upon eating food
     // This refills their energy and increases their fitness
-    pub(crate) fn sate(&mut self) {
+    pub fn sate(&mut self) {
         self.energy = ux::u5::MAX;
+        self.fed = true;
 
         if self.fitness < ux::u5::MAX {
             self.fitness = self.fitness + ux::u5::new(1);
         }
     }
 
+    pub fn drink(&mut self) {
+        self.thirst = ux::u5::MIN;
+    }
+
+    // Toxic food costs fitness, and leaves the creature as hungry as it was
+    pub fn poison(&mut self) {
+        if self.fitness > ux::u5::MIN {
+            self.fitness = self.fitness - ux::u5::new(1);
+        }
+    }
+
+    pub fn brain_stats(&self) -> BrainStats {
+        let mut stats = BrainStats {
+            edges: self.brain.edge_count(),
+            recurrent: self.compiled.recurrent(),
+            plastic: self.brain.edge_weights().filter(|edge| edge.plastic).count(),
+            depth: self.compiled.depth(),
+            ..BrainStats::default()
+        };
+
+        for node in self.brain.node_weights() {
+            match node {
+                Node::Sense(..) => stats.senses += 1,
+                Node::Action(..) => stats.actions += 1,
+                Node::Internal { .. } => stats.internals += 1
+            }
+        }
+
+        for index in self.brain.node_indices() {
+            if let Node::Action(variant) = &self.brain[index] {
+                stats.connectivity.push(ActionConnectivity {
+                    action: *variant,
+                    inputs: self.brain.neighbors_directed(index, petgraph::Direction::Incoming).count(),
+                    upstream: self.upstream(index) - 1
+                } );
+            }
+        }
+
+        stats
+    }
+
+    // Counts the given node and every node that feeds into it
+    // Walked with an explicit stack, so long chains of internal nodes can't overflow the call stack
+    fn upstream(&self, index: NodeIndex) -> usize {
+        use petgraph::visit::{VisitMap, Visitable};
+
+        // a FixedBitSet over the brain's nodes
+        let mut visited = self.brain.visit_map();
+        visited.visit(index);
+
+        let mut stack = vec![index];
+        while let Some(node) = stack.pop() {
+            for input in self.brain.neighbors_directed(node, petgraph::Direction::Incoming) {
+                if visited.visit(input) {
+                    stack.push(input);
+                }
+            }
+        }
+
+        visited.count_ones(..)
+    }
+
+    // Approximate heap memory held by the Agent, in bytes
+    // petgraph stores two adjacency indices alongside each node and four alongside each edge
+    pub fn memory(&self) -> usize {
+        use std::mem::size_of;
+
+        self.brain.node_count() * (size_of::<Node>() + 2 * size_of::<u32>())
+            + self.brain.edge_count() * (size_of::<Edge>() + 4 * size_of::<u32>())
+            + self.genome.capacity() * size_of::<Gene>()
+            + self.history.capacity() * size_of::<(u64, gene::ActionType, bool)>()
+            + self.compiled.memory()
+    }
+
+    // The work its brain does each time the Agent is processed, see CompiledBrain::cost
+    pub fn evaluation_cost(&self) -> usize {
+        self.compiled.cost()
+    }
+
+    // The fraction of consecutive actions in the Agent's history that repeat the previous one
+    // Returns None until at least two actions have been taken
+    pub fn stability(&self) -> Option<f32> {
+        if self.history.len() < 2 {
+            return None;
+        }
+
+        let repeats = self.history.windows(2).filter(|pair| pair[0].1 == pair[1].1).count();
+
+        Some(repeats as f32 / (self.history.len() - 1) as f32)
+    }
+
     // Agents are considered starving when they are out of energy
-    pub(crate) fn starving(&self) -> bool {
+    pub fn starving(&self) -> bool {
         self.energy == ux::u5::MIN
     }
+
+    pub fn dehydrated(&self) -> bool {
+        self.thirst == ux::u5::MAX
+    }
 }
 
 impl Agent {
-    pub(crate) fn from_prng(complexity: usize, prng: &mut StdRng) -> Result<Self, std::io::Error> {
+    pub fn from_prng(complexity: usize, prng: &mut Prng) -> Result<Self, GenomeError> {
         let mut genome: Vec<Gene> = Vec::new();
         for _ in 0..complexity {
             genome.push(Gene::new(prng.gen_range(0..=255)));
         }
 
-        Self::new(genome)
+        Self::new(genome, prng)
     }
 
-    pub(crate) fn from_seed(complexity: usize, seed: u64) -> Result<Self, std::io::Error> {
-        let mut prng: StdRng = rand::SeedableRng::seed_from_u64(seed);
+    pub fn from_seed(complexity: usize, seed: u64) -> Result<Self, GenomeError> {
+        let mut prng: Prng = rand::SeedableRng::seed_from_u64(seed);
 
         Agent::from_prng(complexity, &mut prng)
     }
 
-    pub(crate) fn from_string(data: String) -> Result<Self, std::io::Error> {
-        Self::new(gene::Genome::from_string(data))
+    /// Builds an Agent from a genome written as space-separated 8-bit binary genes, such as `10010110 01101001`.
+    pub fn from_string(data: String, prng: &mut Prng) -> Result<Self, GenomeError> {
+        Self::new(gene::Genome::from_string(data)?, prng)
     }
 }
 
 impl fmt::Debug for Agent {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "Agent{}, facing {:?}", {
-            match self.history.first() {
+            match self.last_action() {
                 Some(action) => format!(" ({:?})", action),
                 None => String::default()
             }
@@ -359,4 +741,227 @@ impl fmt::Display for Agent {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         write!(f, "{:?}", self)
     }
-}
\ No newline at end of file
+}
+
+// The parts of an Agent that can't be rebuilt from its genome
+// Agents are saved in this form, and their brains are rebuilt when they are loaded unless a cached copy was saved
+#[derive(serde::Serialize, serde::Deserialize)]
+struct AgentState {
+    genome: Vec<Gene>,
+    fitness: u8,
+    direction: Direction,
+    history: Vec<SavedAction>,
+    energy: u8,
+    #[serde(default)]
+    thirst: u8,
+    marker: Option<u8>,
+    id: u64,
+    #[serde(default)]
+    born: u64,
+    #[serde(default)]
+    offspring: u32,
+    fed: bool,
+    metabolism: f32,
+    weights: Vec<f32>,
+    states: Vec<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    brain: Option<CachedBrain>
+}
+
+// Saves made before actions were recorded with their tick and outcome hold only the action
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+enum SavedAction {
+    Recorded(u64, gene::ActionType, bool),
+    Bare(gene::ActionType)
+}
+
+// A pruned brain saved alongside its genome
+// Large worlds load much faster, but their saves are several times larger
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CachedBrain {
+    // the encoded graph, as a hex string since that is far quicker to parse than a JSON array
+    graph: String,
+    // covers the genome as well, so a brain is never paired with the wrong genome
+    checksum: u64
+}
+
+impl CachedBrain {
+    fn new(agent: &Agent) -> Self {
+        let graph = Self::encode(&agent.brain);
+        Self {
+            checksum: Self::checksum(&agent.genome, &graph),
+            graph: graph.iter().map(|byte| format!("{:02x}", byte)).collect()
+        }
+    }
+
+    // FNV-1a, which unlike the standard library's hasher is stable between releases
+    // Covers the gene layout too, so brains cached under another layout are rebuilt from their genomes
+    fn checksum(genome: &[Gene], graph: &[u8]) -> u64 {
+        const OFFSET: u64 = 0xcbf29ce484222325;
+        const PRIME: u64 = 0x100000001b3;
+
+        gene::Genome::LAYOUT.to_le_bytes().into_iter()
+            .chain(genome.iter().map(|gene| gene.0))
+            .chain(graph.iter().copied())
+            .fold(OFFSET, |hash, byte| (hash ^ byte as u64).wrapping_mul(PRIME))
+    }
+
+    // None if the cached brain doesn't match the genome, or has been corrupted
+    fn brain(self, genome: &[Gene]) -> Option<graph::Graph<Node, Edge>> {
+        let graph = (0..self.graph.len() / 2)
+            .map(|index| u8::from_str_radix(self.graph.get(index * 2..index * 2 + 2)?, 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+
+        if Self::checksum(genome, &graph) != self.checksum {
+            return None;
+        }
+
+        Self::decode(&graph)
+    }
+
+    // The node count, then each node as its kind, variant and (for internal nodes) bias,
+    // then each edge as its endpoints and flags
+    // Variants are written as their slot in the Gene tables, so they are read back the same way genes are
+    fn encode(brain: &graph::Graph<Node, Edge>) -> Vec<u8> {
+        fn slot<T: PartialEq>(table: &[T], variant: &T) -> u8 {
+            table.iter().position(|entry| entry == variant).expect("every variant should have a slot in its gene table") as u8
+        }
+
+        let mut bytes = Vec::new();
+        bytes.extend((brain.node_count() as u32).to_le_bytes());
+        for node in brain.node_weights() {
+            match node {
+                Node::Sense(sense) => bytes.extend([0, slot(&Gene::SENSES, sense)]),
+                Node::Action(action) => bytes.extend([1, slot(&Gene::ACTIONS, action)]),
+                Node::Internal { bias, activation } => {
+                    bytes.extend([2, slot(&Gene::ACTIVATIONS, activation)]);
+                    bytes.extend(bias.to_le_bytes());
+                }
+            }
+        }
+
+        for edge in brain.raw_edges() {
+            bytes.extend((edge.source().index() as u32).to_le_bytes());
+            bytes.extend((edge.target().index() as u32).to_le_bytes());
+            bytes.extend([edge.weight.additive as u8, edge.weight.plastic as u8]);
+        }
+
+        bytes
+    }
+
+    // Edges are added in order, so the result doesn't depend on how the brain was pruned
+    fn decode(mut bytes: &[u8]) -> Option<graph::Graph<Node, Edge>> {
+        let mut take = |count: usize| {
+            let (taken, rest) = (bytes.get(..count)?, bytes.get(count..)?);
+            bytes = rest;
+            Some(taken)
+        };
+
+        let u32 = |bytes: &[u8]| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+
+        let mut brain = graph::Graph::new();
+        for _ in 0..u32(take(4)?) {
+            let header = take(2)?;
+            brain.add_node(match header[0] {
+                0 => Node::Sense(*Gene::SENSES.get(header[1] as usize)?),
+                1 => Node::Action(*Gene::ACTIONS.get(header[1] as usize)?),
+                2 => Node::Internal {
+                    activation: *Gene::ACTIVATIONS.get(header[1] as usize)?,
+                    bias: f32::from_le_bytes(take(4)?.try_into().ok()?)
+                },
+                _ => return None
+            } );
+        }
+
+        while let Some(source) = take(4) {
+            let (source, target, flags) = (u32(source) as usize, u32(take(4)?) as usize, take(2)?);
+            if source >= brain.node_count() || target >= brain.node_count() {
+                return None;
+            }
+
+            brain.add_edge(NodeIndex::new(source), NodeIndex::new(target), Edge {
+                additive: flags[0] != 0,
+                plastic: flags[1] != 0
+            } );
+        }
+
+        Some(brain)
+    }
+}
+
+impl Agent {
+    fn state(&self, cache: bool) -> AgentState {
+        AgentState {
+            genome: self.genome.clone(),
+            fitness: u8::from(self.fitness),
+            direction: self.direction,
+            history: self.history.iter().map(|(tick, action, successful)| SavedAction::Recorded(*tick, *action, *successful)).collect(),
+            energy: u8::from(self.energy),
+            thirst: u8::from(self.thirst),
+            marker: self.marker,
+            id: self.id,
+            born: self.born,
+            offspring: self.offspring,
+            fed: self.fed,
+            metabolism: self.metabolism,
+            weights: self.compiled.weights(),
+            states: self.compiled.states(),
+            brain: if cache { Some(CachedBrain::new(self)) } else { None }
+        }
+    }
+}
+
+impl serde::Serialize for Agent {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.state(false).serialize(serializer)
+    }
+}
+
+// Serializes an Agent along with its cached brain
+pub struct WithBrain<'a>(pub &'a Agent);
+
+impl serde::Serialize for WithBrain<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.state(true).serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Agent {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state = AgentState::deserialize(deserializer)?;
+
+        let brain = state.brain.and_then(|brain| {
+            let brain = brain.brain(&state.genome);
+            if brain.is_none() {
+                tracing::warn!(id = state.id, "discarded a cached brain that failed its integrity check");
+            }
+
+            brain
+        } );
+
+        let mut agent = match brain {
+            Some(brain) => Self::from_cache(state.genome, brain),
+            None => Self::from_genome(state.genome).map_err(serde::de::Error::custom)?
+        };
+
+        agent.fitness = ux::u5::new(state.fitness.min(u8::from(ux::u5::MAX)));
+        agent.direction = state.direction;
+        agent.history = state.history.into_iter().map(|action| match action {
+            SavedAction::Recorded(tick, action, successful) => (tick, action, successful),
+            // the tick of an older action is unknown, and it is assumed to have had an effect
+            SavedAction::Bare(action) => (0, action, true)
+        } ).collect();
+        agent.energy = ux::u5::new(state.energy.min(u8::from(ux::u5::MAX)));
+        agent.thirst = ux::u5::new(state.thirst.min(u8::from(ux::u5::MAX)));
+        agent.marker = state.marker;
+        agent.id = state.id;
+        agent.born = state.born;
+        agent.offspring = state.offspring;
+        agent.fed = state.fed;
+        agent.metabolism = state.metabolism;
+        agent.compiled.restore(&state.weights, &state.states);
+
+        Ok(agent)
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::mpsc;
+
+use strum::IntoEnumIterator;
+
+use crate::agent::gene;
+use crate::simulation::{BenchmarkTask, Simulation};
+
+// The Agents a representative is chosen from
+// Every Agent belongs to a species, and marked Agents to a lineage as well
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Group {
+    Species(u8),
+    Lineage(u8)
+}
+
+impl std::fmt::Display for Group {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Group::*;
+        match self {
+            Species(species) => write!(f, "Species {}", species),
+            Lineage(marker) => write!(f, "Lineage {}", marker)
+        }
+    }
+}
+
+// A group's representative, scored in every BenchmarkTask
+#[derive(Debug, Clone)]
+pub struct Sample {
+    pub tick: u64,
+    pub group: Group,
+    // the group's size when the representative was chosen
+    pub population: usize,
+    // indexed like BenchmarkTask::iter
+    pub scores: Vec<usize>
+}
+
+impl Sample {
+    pub fn mean(&self) -> f32 {
+        self.scores.iter().sum::<usize>() as f32 / self.scores.len().max(1) as f32
+    }
+}
+
+// Every INTERVAL steps, the fittest member of each species and of each marked lineage is evaluated on the rayon thread pool,
+// so the Simulation keeps stepping while the arenas run
+#[derive(Default)]
+pub struct Benchmarks {
+    samples: VecDeque<Sample>,
+    pending: Option<mpsc::Receiver<Vec<Sample>>>
+}
+
+impl Benchmarks {
+    pub const INTERVAL: u64 = 250;
+    pub const CAPACITY: usize = 1024;
+
+    // Collects finished evaluations, then starts the next round if one is due
+    // A round that comes due while the previous one is still running is skipped
+    pub fn poll(&mut self, simulation: &Simulation) {
+        if let Some(pending) = &self.pending {
+            match pending.try_recv() {
+                Ok(samples) => {
+                    for sample in samples {
+                        if self.samples.len() == Self::CAPACITY {
+                            self.samples.pop_front();
+                        }
+
+                        self.samples.push_back(sample);
+                    }
+
+                    self.pending = None;
+                },
+                Err(mpsc::TryRecvError::Empty) => return,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    tracing::error!("a benchmark round ended without reporting its scores");
+                    self.pending = None;
+                }
+            }
+        }
+
+        if simulation.tick() > 0 && simulation.tick().is_multiple_of(Self::INTERVAL) {
+            self.start(simulation);
+        }
+    }
+
+    fn start(&mut self, simulation: &Simulation) {
+        // Simulation::agents is sorted by fitness, so the last member seen is the fittest
+        let mut groups: BTreeMap<Group, (usize, Vec<gene::Gene>)> = BTreeMap::new();
+        for coord in simulation.agents() {
+            let agent = simulation.get(coord).agent();
+
+            let species = Some(Group::Species(agent.attributes.species()));
+            for group in species.into_iter().chain(agent.marker.map(Group::Lineage)) {
+                let members = groups.entry(group).or_insert((0, Vec::new()));
+                members.0 += 1;
+                members.1 = agent.genome.clone();
+            }
+        }
+
+        if groups.is_empty() {
+            return;
+        }
+
+        let tick = simulation.tick();
+        let settings = simulation.settings().clone();
+
+        let (sender, receiver) = mpsc::channel();
+        rayon::spawn(move || {
+            let samples = groups.into_iter().map(|(group, (population, genome))| {
+                Sample {
+                    tick,
+                    group,
+                    population,
+                    scores: BenchmarkTask::iter()
+                        .map(|task| Simulation::benchmark(&settings, genome.clone(), task))
+                        .collect()
+                }
+            } ).collect::<Vec<Sample>>();
+
+            // the receiver is gone if the run was restarted in the meantime
+            let _ = sender.send(samples);
+        } );
+
+        self.pending = Some(receiver);
+    }
+
+    // Each group's mean score over time, oldest first
+    pub fn series(&self) -> BTreeMap<Group, Vec<(u64, f32)>> {
+        let mut series: BTreeMap<Group, Vec<(u64, f32)>> = BTreeMap::new();
+        for sample in self.samples.iter() {
+            series.entry(sample.group).or_default().push((sample.tick, sample.mean()));
+        }
+
+        series
+    }
+
+    // Pearson's correlation between a group's size and its mean score
+    // None until there are enough samples, or if either varies too little to compare
+    pub fn correlation(&self) -> Option<f32> {
+        if self.samples.len() < 3 {
+            return None;
+        }
+
+        let count = self.samples.len() as f32;
+        let (populations, scores) = self.samples.iter()
+            .fold((0f32, 0f32), |totals, sample| (totals.0 + sample.population as f32, totals.1 + sample.mean()));
+        let (population, score) = (populations / count, scores / count);
+
+        let (covariance, population_variance, score_variance) = self.samples.iter().fold((0f32, 0f32, 0f32), |totals, sample| {
+            let population = sample.population as f32 - population;
+            let score = sample.mean() - score;
+            (totals.0 + population * score, totals.1 + population * population, totals.2 + score * score)
+        } );
+
+        if population_variance <= f32::EPSILON || score_variance <= f32::EPSILON {
+            return None;
+        }
+
+        Some(covariance / (population_variance * score_variance).sqrt())
+    }
+
+    pub fn report(&self) -> String {
+        if self.samples.is_empty() {
+            return format!("Each species and marked lineage is benchmarked every {} steps{}",
+                Self::INTERVAL,
+                if self.pending.is_some() { "\nThe first round is running" } else { "" });
+        }
+
+        let mut report = match self.correlation() {
+            Some(correlation) => format!("Correlation between group size and benchmark score: {:.2}\n", correlation),
+            None => String::from("Not enough variation yet to correlate group size with benchmark score\n")
+        };
+
+        // the most recent sample of each group
+        let latest = self.samples.iter().fold(BTreeMap::new(), |mut latest, sample| {
+            latest.insert(sample.group, sample);
+            latest
+        } );
+
+        for (group, sample) in latest {
+            report.push_str(&format!("\n{} ({} agents at step {})", group, sample.population, sample.tick));
+            for (task, score) in BenchmarkTask::iter().zip(sample.scores.iter()) {
+                report.push_str(&format!("\n  {}: ate {}", task, score));
+            }
+        }
+
+        report
+    }
+}
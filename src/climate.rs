@@ -0,0 +1,155 @@
+use rand::Rng;
+
+use crate::simulation::Prng;
+use crate::tile::coord;
+
+// The direction in which a static field warms up
+#[derive(Debug, Copy, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub enum Gradient {
+    // cold on the left edge, hot on the right
+    #[default]
+    Horizontal,
+    // cold on the top edge, hot on the bottom
+    Vertical,
+    // hot in the center, cold in the corners
+    Radial
+}
+
+#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub enum Field {
+    Gradient(Gradient),
+    // hot and cold sources are scattered across the world, and the rest of the field starts mild
+    // each step, every other tile closes `rate` of the gap to its neighbors' mean temperature
+    Diffusing { sources: usize, rate: f32 }
+}
+
+impl Default for Field {
+    fn default() -> Self {
+        Field::Gradient(Gradient::default())
+    }
+}
+
+// Lays a temperature between 0 (coldest) and 1 (hottest) over every tile
+// Agents standing in extreme tiles burn extra energy
+#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Climate {
+    pub field: Field,
+    // how far from mild (0.5) a tile can be before it costs energy, as a fraction of the full range
+    tolerance: f32,
+    // the energy drained each step in the hottest and coldest tiles
+    drain: f32
+}
+
+impl Default for Climate {
+    fn default() -> Self {
+        Self {
+            field: Field::default(),
+            tolerance: 0.5f32,
+            drain: 0.5f32
+        }
+    }
+}
+
+impl Climate {
+    // The energy drained each step from an Agent at the given temperature
+    pub fn exposure(&self, temperature: f32) -> f32 {
+        let extremity = (temperature - Temperature::MILD).abs() * 2f32;
+        let tolerance = self.tolerance.clamp(0f32, 1f32);
+        if extremity <= tolerance {
+            return 0f32;
+        }
+
+        self.drain * (extremity - tolerance) / (1f32 - tolerance)
+    }
+}
+
+// The current temperature of every tile
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Temperature {
+    width: usize,
+    values: Vec<f32>,
+    // indices that hold their temperature while the rest of the field diffuses
+    sources: Vec<usize>
+}
+
+impl Temperature {
+    pub const MILD: f32 = 0.5f32;
+
+    // Only a diffusing field draws from the Prng, to place its sources
+    pub fn new(field: Field, width: usize, height: usize, prng: &mut Prng) -> Self {
+        let center = (width as f32 / 2f32, height as f32 / 2f32);
+        let reach = (center.0 * center.0 + center.1 * center.1).sqrt().max(f32::EPSILON);
+
+        let mut temperature = Self {
+            width,
+            values: vec![Self::MILD; width * height],
+            sources: Vec::new()
+        };
+
+        match field {
+            Field::Gradient(gradient) => for y in 0..height {
+                for x in 0..width {
+                    let (cx, cy) = (x as f32 + 0.5f32, y as f32 + 0.5f32);
+
+                    use Gradient::*;
+                    temperature.values[y * width + x] = match gradient {
+                        Horizontal => cx / width as f32,
+                        Vertical => cy / height as f32,
+                        Radial => 1f32 - ((cx - center.0).powi(2) + (cy - center.1).powi(2)).sqrt() / reach
+                    };
+                }
+            },
+            Field::Diffusing { sources, .. } => {
+                // sources alternate between hot and cold
+                for source in 0..sources.min(width * height) {
+                    let index = prng.gen_range(0..width * height);
+                    temperature.values[index] = if source % 2 == 0 { 1f32 } else { 0f32 };
+                    temperature.sources.push(index);
+                }
+            }
+        }
+
+        temperature
+    }
+
+    pub fn get(&self, coord: coord::Coord) -> f32 {
+        self.values.get(coord.y * self.width + coord.x).copied().unwrap_or(Self::MILD)
+    }
+
+    // A static field never changes
+    pub fn diffuse(&mut self, field: Field) {
+        let rate = match field {
+            Field::Diffusing { rate, .. } => rate.clamp(0f32, 1f32),
+            Field::Gradient(..) => return
+        };
+
+        let width = self.width.max(1);
+        let height = self.values.len() / width;
+
+        // heat doesn't flow past the edges of the world
+        let previous = self.values.clone();
+        for y in 0..height {
+            for x in 0..width {
+                let index = y * width + x;
+                if self.sources.contains(&index) {
+                    continue;
+                }
+
+                let neighbors = [
+                    (x > 0).then(|| index - 1),
+                    (x + 1 < width).then(|| index + 1),
+                    (y > 0).then(|| index - width),
+                    (y + 1 < height).then(|| index + width)
+                ];
+
+                let (total, count) = neighbors.iter()
+                    .flatten()
+                    .fold((0f32, 0usize), |(total, count), neighbor| (total + previous[*neighbor], count + 1));
+
+                if count > 0 {
+                    self.values[index] += rate * (total / count as f32 - previous[index]);
+                }
+            }
+        }
+    }
+}
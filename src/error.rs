@@ -0,0 +1,117 @@
+/// Describes why a genome could not be turned into an Agent.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum GenomeError {
+    /// The genome contains no genes.
+    #[error("the genome is empty")]
+    EmptyGenome,
+    /// The genome's genes are all attributes or connections, so its brain would have no nodes.
+    #[error("the genome does not encode any sense, action or internal nodes")]
+    EmptyBrain,
+    /// None of the brain's action nodes are wired to an input, so the Agent could never act.
+    #[error("the brain has no action nodes connected to a sense")]
+    NoActionNodes,
+    /// A gene was not an 8-bit binary string.
+    #[error("gene {index} ('{gene}') is not an 8-bit binary string")]
+    MalformedGene { index: usize, gene: String }
+}
+
+/// Describes why a run manifest or recording could not be loaded.
+#[derive(Debug, thiserror::Error)]
+pub enum LoadError {
+    /// The file could not be read from disk.
+    #[error("the file could not be read: {0}")]
+    Io(std::io::Error),
+    /// The file's contents are not valid.
+    #[error("the file is malformed: {0}")]
+    Parse(serde_json::Error),
+    /// The file's contents are not a valid TOML configuration.
+    #[error("the configuration is malformed: {0}")]
+    Config(toml::de::Error),
+    /// The settings are out of the range a Simulation can be built from.
+    #[error("the settings are invalid: {0}")]
+    Invalid(String)
+}
+
+/// Describes why the Agent on a Tile could not be accessed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AccessError {
+    /// The Tile is empty or holds food.
+    #[error("the tile does not contain an agent")]
+    NoAgent,
+    /// The Agent is already mutably borrowed elsewhere.
+    #[error("the agent is in use")]
+    Borrowed
+}
+
+/// Describes why a Tile could not be put in a TileMap.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PlacementError {
+    /// The Coord lies outside of the world.
+    #[error("({x}, {y}) is outside of the world")]
+    OutOfBounds { x: usize, y: usize }
+}
+
+/// Describes why a canvas color rule could not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ColorRuleError {
+    /// The rule is not of the form `property comparison value = color`.
+    #[error("'{0}' should look like 'fitness > 20 = gold'")]
+    Malformed(String),
+    /// The rule tests something other than fitness, energy, thirst, lineage or action.
+    #[error("'{0}' is not fitness, energy, thirst, lineage or action")]
+    UnknownProperty(String),
+    /// The value can't be compared against the property.
+    #[error("'{0}' can't be compared against that property")]
+    InvalidValue(String),
+    /// The color is neither a known name nor a hex code such as `#FFD700`.
+    #[error("'{0}' is not a known color")]
+    UnknownColor(String)
+}
+
+/// Describes why the settings entered in the interface could not be applied.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SettingsError {
+    /// A field is not a number, or is outside of its range.
+    #[error("'{value}' is not a valid {field}")]
+    InvalidValue { field: &'static str, value: String },
+    /// The world would contain no Tiles.
+    #[error("the world must be at least 1 tile wide and tall")]
+    EmptyWorld,
+    /// The settings can't be used to build a Simulation.
+    #[error("{0}")]
+    Invalid(String)
+}
+
+/// Describes why a designed genome could not be created.
+#[derive(Debug, thiserror::Error)]
+pub enum GenomeEditError {
+    /// A genome file could not be read or written.
+    #[error("{0} could not be accessed: {1}")]
+    Io(String, std::io::Error),
+    /// A genome file does not contain a genome.
+    #[error("{0} is not a genome, {1}")]
+    Genome(String, GenomeError),
+    /// The region is not of the form `start..end` within a genome of the given length.
+    #[error("'{0}' is not a region of a genome with {1} genes")]
+    InvalidRegion(String, usize)
+}
+
+/// Describes why a headless run could not be started or finished.
+#[derive(Debug, thiserror::Error)]
+pub enum CliError {
+    /// A flag was given without the value that follows it.
+    #[error("{0} must be followed by a value")]
+    MissingValue(String),
+    /// A flag was followed by a value it can't take.
+    #[error("'{value}' is not a valid value for {flag}")]
+    InvalidValue { flag: String, value: String },
+    /// An argument is not one of the recognized flags.
+    #[error("'{0}' is not a recognized argument")]
+    UnknownArgument(String),
+    /// The settings file could not be loaded.
+    #[error("the settings in {0} could not be loaded, {1}")]
+    Settings(String, LoadError),
+    /// The summary or statistics could not be written.
+    #[error("{0} could not be written: {1}")]
+    Output(String, std::io::Error)
+}
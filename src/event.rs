@@ -0,0 +1,175 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::io;
+
+use crate::simulation::Simulation;
+use crate::tile::coord::Coord;
+
+// Something that happened to an Agent during a step
+// Agents are identified by Agent::id
+#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Event {
+    pub tick: u64,
+    pub coord: Coord,
+    pub kind: EventKind
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum EventKind {
+    // the child was placed behind its parent
+    Birth { parent: u64, child: u64 },
+    // recorded for every death, whatever the cause
+    Death { agent: u64 },
+    Kill { killer: u64, victim: u64 },
+    Eat { agent: u64 },
+    ProduceFood { agent: u64 },
+    // the Agent used up the last of its energy
+    Starvation { agent: u64 }
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "step {} ({}, {}): ", self.tick, self.coord.x, self.coord.y)?;
+
+        use EventKind::*;
+        match self.kind {
+            Birth { parent, child } => write!(f, "agent {} gave birth to agent {}", parent, child),
+            Death { agent } => write!(f, "agent {} died", agent),
+            Kill { killer, victim } => write!(f, "agent {} killed agent {}", killer, victim),
+            Eat { agent } => write!(f, "agent {} ate", agent),
+            ProduceFood { agent } => write!(f, "agent {} produced food", agent),
+            Starvation { agent } => write!(f, "agent {} is starving", agent)
+        }
+    }
+}
+
+// A ring buffer holding the most recent events, oldest first
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct EventLog {
+    events: VecDeque<Event>
+}
+
+impl EventLog {
+    pub const CAPACITY: usize = 4096;
+
+    pub fn record(&mut self, event: Event) {
+        if self.events.len() == Self::CAPACITY {
+            self.events.pop_front();
+        }
+
+        self.events.push_back(event);
+    }
+
+    // Events are recorded in tick order, so the first match can be found with a binary search
+    pub fn since(&self, tick: u64) -> impl Iterator<Item = &Event> {
+        let start = self.events.partition_point(|event| event.tick < tick);
+        self.events.range(start..)
+    }
+}
+
+impl EventKind {
+    // Whether the Agent took part in the event, on either side
+    pub fn involves(&self, id: u64) -> bool {
+        use EventKind::*;
+        match *self {
+            Birth { parent, child } => parent == id || child == id,
+            Kill { killer, victim } => killer == id || victim == id,
+            Death { agent } | Eat { agent } | ProduceFood { agent } | Starvation { agent } => agent == id
+        }
+    }
+}
+
+// Collects the events involving a single Agent as they are recorded
+// The feed outlives both the Agent and the EventLog's capacity, so a busy world can't push it out
+#[derive(Debug, Clone)]
+pub struct Subscription {
+    pub agent: u64,
+    // the first tick that hasn't been read
+    next: u64,
+    events: VecDeque<Event>
+}
+
+impl Subscription {
+    pub const CAPACITY: usize = 64;
+
+    // Only events recorded after the given tick are collected
+    pub fn new(agent: u64, tick: u64) -> Self {
+        Self {
+            agent,
+            next: tick + 1,
+            events: VecDeque::new()
+        }
+    }
+
+    // Reads the Agent's events from every tick since the last poll, up to and including the given tick
+    pub fn poll<'a>(&mut self, events: impl Iterator<Item = &'a Event>, tick: u64) {
+        for event in events.filter(|event| event.kind.involves(self.agent)) {
+            if self.events.len() == Self::CAPACITY {
+                self.events.pop_front();
+            }
+
+            self.events.push_back(*event);
+        }
+
+        self.next = tick + 1;
+    }
+
+    pub fn next(&self) -> u64 {
+        self.next
+    }
+
+    // Newest first
+    pub fn feed(&self) -> impl Iterator<Item = &Event> {
+        self.events.iter().rev()
+    }
+}
+
+// Writes every step as a line of JSON, for external analysis pipelines
+// Each line holds the step, the events recorded during it, and how many Agents took each action
+// Enabled by the event_stream setting, which names a file to append to, or `-` for standard output
+pub struct EventStream {
+    output: Box<dyn io::Write>
+}
+
+#[derive(serde::Serialize)]
+struct StepRecord<'a> {
+    tick: u64,
+    events: Vec<&'a Event>,
+    // keyed by the action's name, actions nobody took are left out
+    actions: BTreeMap<String, usize>
+}
+
+impl EventStream {
+    pub const STDOUT: &'static str = "-";
+
+    pub fn open(target: &str) -> io::Result<Self> {
+        let output: Box<dyn io::Write> = if target == Self::STDOUT {
+            Box::new(io::stdout())
+        } else {
+            Box::new(crate::platform::append(target)?)
+        };
+
+        Ok(Self { output })
+    }
+
+    // Writes the Simulation's latest step
+    pub fn write(&mut self, simulation: &Simulation) -> io::Result<()> {
+        let mut actions = BTreeMap::new();
+        for action in simulation.actions() {
+            *actions.entry(format!("{:?}", action.action)).or_insert(0) += 1;
+        }
+
+        let record = StepRecord {
+            tick: simulation.tick(),
+            events: simulation.step_events().iter().collect(),
+            actions
+        };
+
+        // each line is written at once and flushed, so readers never see part of a step
+        let mut line = serde_json::to_string(&record).map_err(io::Error::other)?;
+        line.push('\n');
+
+        self.output.write_all(line.as_bytes())?;
+        self.output.flush()
+    }
+}
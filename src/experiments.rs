@@ -0,0 +1,186 @@
+use std::fmt;
+
+use rayon::prelude::*;
+
+use crate::simulation::{Basics, Simulation, SimulationSettings};
+use crate::stats::Sample;
+
+// Runs replicates of several parameter sets side by side, summarizing each run in one row of a results table
+// Replicate r of every parameter set uses the seed `seed + r`, so parameter sets are compared on the same seeds
+// Every run is independent, so they are spread over the rayon thread pool
+
+// A named parameter set
+#[derive(Debug, Clone)]
+pub struct Experiment {
+    pub name: String,
+    pub settings: SimulationSettings
+}
+
+// The outcome of a single run
+#[derive(Debug, Clone)]
+pub struct Summary {
+    pub experiment: String,
+    pub seed: u64,
+    // the steps taken, fewer than requested if every Agent died
+    pub ticks: u64,
+    pub extinct: bool,
+    pub population: usize,
+    pub peak_population: usize,
+    // births and deaths over the whole run
+    pub births: usize,
+    pub deaths: usize,
+    pub mean_fitness: f32,
+    pub max_fitness: u8,
+    pub food: usize,
+    pub brain_size: f32
+}
+
+impl Summary {
+    pub const HEADER: &'static str = "experiment,seed,ticks,extinct,population,peak_population,births,deaths,mean_fitness,max_fitness,food,mean_brain_size";
+
+    fn csv(&self) -> String {
+        format!("{},{},{},{},{},{},{},{},{:.3},{},{},{:.3}",
+            Self::quote(&self.experiment),
+            self.seed,
+            self.ticks,
+            self.extinct,
+            self.population,
+            self.peak_population,
+            self.births,
+            self.deaths,
+            self.mean_fitness,
+            self.max_fitness,
+            self.food,
+            self.brain_size)
+    }
+
+    // Names are chosen by the user, so one holding a comma, quote or line break is quoted,
+    // with its own quotes doubled, rather than splitting the row
+    fn quote(field: &str) -> String {
+        if field.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+}
+
+// One row per run, grouped by parameter set in the order they were given, then by seed
+pub struct Results {
+    pub summaries: Vec<Summary>
+}
+
+impl Results {
+    pub fn csv(&self) -> String {
+        self.summaries.iter().fold(format!("{}\n", Summary::HEADER), |output, summary| {
+            output + &summary.csv() + "\n"
+        } )
+    }
+}
+
+// An aligned table, with each parameter set's means beneath its runs
+impl fmt::Display for Results {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.summaries.iter()
+            .map(|summary| summary.experiment.len())
+            .max()
+            .unwrap_or(0)
+            .max("experiment".len());
+
+        write!(f, "{:<width$} {:>20} {:>6} {:>10} {:>6} {:>7} {:>7} {:>8} {:>6}",
+            "experiment", "seed", "ticks", "population", "peak", "births", "deaths", "fitness", "brain")?;
+
+        for runs in self.summaries.chunk_by(|a, b| a.experiment == b.experiment) {
+            for summary in runs {
+                write!(f, "\n{:<width$} {:>20} {:>6} {:>10} {:>6} {:>7} {:>7} {:>8.2} {:>6.1}",
+                    summary.experiment,
+                    summary.seed,
+                    summary.ticks,
+                    if summary.extinct { String::from("extinct") } else { summary.population.to_string() },
+                    summary.peak_population,
+                    summary.births,
+                    summary.deaths,
+                    summary.mean_fitness,
+                    summary.brain_size)?;
+            }
+
+            let mean = |value: &dyn Fn(&Summary) -> f32| runs.iter().map(value).sum::<f32>() / runs.len() as f32;
+            write!(f, "\n{:<width$} {:>20} {:>6.0} {:>10.1} {:>6.1} {:>7.1} {:>7.1} {:>8.2} {:>6.1}",
+                runs[0].experiment,
+                "mean",
+                mean(&|summary| summary.ticks as f32),
+                mean(&|summary| summary.population as f32),
+                mean(&|summary| summary.peak_population as f32),
+                mean(&|summary| summary.births as f32),
+                mean(&|summary| summary.deaths as f32),
+                mean(&|summary| summary.mean_fitness),
+                mean(&|summary| summary.brain_size))?;
+        }
+
+        Ok(())
+    }
+}
+
+// Runs every replicate of every parameter set for up to `ticks` steps
+pub fn run(experiments: &[Experiment], replicates: u64, ticks: u64, seed: u64) -> Results {
+    let runs = experiments.iter()
+        .flat_map(|experiment| (0..replicates).map(move |replicate| (experiment, seed.wrapping_add(replicate))))
+        .collect::<Vec<(&Experiment, u64)>>();
+
+    Results {
+        summaries: runs.into_par_iter()
+            .map(|(experiment, seed)| run_one(experiment, seed, ticks))
+            .collect()
+    }
+}
+
+fn run_one(experiment: &Experiment, seed: u64, ticks: u64) -> Summary {
+    let settings = &experiment.settings;
+    let mut simulation = Simulation::new(settings.with_basics(Basics { seed: Some(seed), ..settings.basics() }));
+
+    let initial = Sample::new(&simulation);
+    let mut summary = Summary {
+        experiment: experiment.name.clone(),
+        seed,
+        ticks: 0,
+        extinct: false,
+        population: initial.population,
+        peak_population: initial.population,
+        births: 0,
+        deaths: 0,
+        mean_fitness: initial.mean_fitness,
+        max_fitness: initial.max_fitness,
+        food: initial.food,
+        brain_size: initial.brain_size
+    };
+
+    while simulation.tick() < ticks && !simulation.agents().is_empty() {
+        simulation.step();
+
+        let sample = Sample::new(&simulation);
+        summary.ticks = sample.tick;
+        summary.population = sample.population;
+        summary.peak_population = summary.peak_population.max(sample.population);
+        summary.births += sample.births;
+        summary.deaths += sample.deaths;
+        summary.mean_fitness = sample.mean_fitness;
+        summary.max_fitness = sample.max_fitness;
+        summary.food = sample.food;
+        summary.brain_size = sample.brain_size;
+    }
+
+    summary.extinct = simulation.agents().is_empty();
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_stay_in_their_column() {
+        assert_eq!(Summary::quote("baseline"), "baseline");
+        assert_eq!(Summary::quote("food, doubled"), "\"food, doubled\"");
+        assert_eq!(Summary::quote("the \"fast\" run"), "\"the \"\"fast\"\" run\"");
+    }
+}
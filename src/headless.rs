@@ -0,0 +1,212 @@
+use std::time::Instant;
+
+use crate::error::CliError;
+use crate::event::EventStream;
+use crate::experiments::Experiment;
+use crate::simulation::{Basics, Simulation, SimulationSettings};
+use crate::stats::{CsvWriter, PopulationStats};
+
+// Runs a world without opening the interface, so long experiments can run on machines without a display
+// `evolution_simulation --headless --ticks 10000 --seed 7 --settings world.toml --output summary.txt --csv stats.csv`
+// The summary is printed once the run ends, and also written to the output path if one is given
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Options {
+    // None runs for Options::DEFAULT_TICKS steps
+    ticks: Option<u64>,
+    // overrides the settings file's seed, a seed is drawn if neither gives one
+    seed: Option<u64>,
+    // a TOML file of SimulationSettings, the fields it leaves out keep their defaults
+    settings: Option<String>,
+    output: Option<String>,
+    // the statistics of every step are appended here, see CsvWriter
+    csv: Option<String>
+}
+
+impl Options {
+    pub(crate) const FLAG: &'static str = "--headless";
+
+    const DEFAULT_TICKS: u64 = 1000;
+    // progress is logged this often
+    const PROGRESS_INTERVAL: u64 = 1000;
+
+    // Parses the arguments that follow the program's name
+    pub(crate) fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, CliError> {
+        let mut options = Self::default();
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if arg == Self::FLAG {
+                continue;
+            }
+
+            let mut value = || args.next().ok_or_else(|| CliError::MissingValue(arg.clone()));
+            match arg.as_str() {
+                "--ticks" => options.ticks = Some(Self::number(&arg, value()?)?),
+                "--seed" => options.seed = Some(Self::number(&arg, value()?)?),
+                "--settings" => options.settings = Some(value()?),
+                "--output" => options.output = Some(value()?),
+                "--csv" => options.csv = Some(value()?),
+                _ => return Err(CliError::UnknownArgument(arg))
+            }
+        }
+
+        Ok(options)
+    }
+
+    fn number(flag: &str, value: String) -> Result<u64, CliError> {
+        value.trim().parse::<u64>().map_err(|_| CliError::InvalidValue {
+            flag: String::from(flag),
+            value
+        } )
+    }
+
+    fn settings(&self) -> Result<SimulationSettings, CliError> {
+        let settings = match &self.settings {
+            Some(path) => SimulationSettings::from_path(path).map_err(|e| CliError::Settings(path.clone(), e))?,
+            None => SimulationSettings::default()
+        };
+
+        Ok(match self.seed {
+            Some(seed) => settings.with_basics(Basics { seed: Some(seed), ..settings.basics() }),
+            None => settings
+        } )
+    }
+}
+
+// Steps the world until the requested number of steps have passed or every Agent has died
+// Returns the summary, which has already been written to the output path if one was given
+pub(crate) fn run(options: &Options) -> Result<String, CliError> {
+    let ticks = options.ticks.unwrap_or(Options::DEFAULT_TICKS);
+
+    let mut simulation = Simulation::new(options.settings()?);
+    let mut stats = PopulationStats::default();
+    let mut csv = match &options.csv {
+        Some(path) => Some(CsvWriter::create(path).map_err(|e| CliError::Output(path.clone(), e))?),
+        None => None
+    };
+
+    let mut events = match simulation.settings().event_stream().map(String::from) {
+        Some(target) => Some((EventStream::open(&target).map_err(|e| CliError::Output(target.clone(), e))?, target)),
+        None => None
+    };
+
+    let started = Instant::now();
+    while simulation.tick() < ticks && !simulation.agents().is_empty() {
+        simulation.step();
+        stats.poll(&simulation);
+
+        if let (Some(csv), Some(sample), Some(path)) = (csv.as_mut(), stats.latest(), &options.csv) {
+            csv.write(sample).map_err(|e| CliError::Output(path.clone(), e))?;
+        }
+
+        if let Some((events, target)) = events.as_mut() {
+            events.write(&simulation).map_err(|e| CliError::Output(target.clone(), e))?;
+        }
+
+        if simulation.tick().is_multiple_of(Options::PROGRESS_INTERVAL) {
+            tracing::info!("step {} of {}, {} agents", simulation.tick(), ticks, simulation.agents().len());
+        }
+    }
+
+    let elapsed = started.elapsed().as_secs_f64();
+
+    let mut summary = format!("Seed {}, {} steps in {:.1}s ({:.0} steps/s)",
+        simulation.settings().seed().unwrap_or_default(),
+        simulation.tick(),
+        elapsed,
+        simulation.tick() as f64 / elapsed.max(f64::EPSILON));
+
+    if simulation.tick() < ticks {
+        summary.push_str(&format!("\nEvery agent died by step {}", simulation.tick()));
+    }
+
+    summary.push_str("\n\n");
+    summary.push_str(&stats.report());
+
+    if let Some(path) = &options.output {
+        crate::platform::write(path, &summary).map_err(|e| CliError::Output(path.clone(), e))?;
+    }
+
+    Ok(summary)
+}
+
+// Runs replicates of several parameter sets in parallel, see experiments::run
+// `evolution_simulation experiment --replicates 5 --ticks 1000 --seed 1 --output results.csv sparse.toml dense.toml`
+// Each settings file is a parameter set named after the file, the default settings are used if none are given
+#[derive(Debug, Clone)]
+pub(crate) struct ExperimentOptions {
+    replicates: u64,
+    ticks: u64,
+    // the first replicate's seed, later replicates count up from it
+    seed: u64,
+    settings: Vec<String>,
+    // the results are also written here as CSV
+    output: Option<String>
+}
+
+impl Default for ExperimentOptions {
+    fn default() -> Self {
+        Self {
+            replicates: 5,
+            ticks: Options::DEFAULT_TICKS,
+            seed: 1,
+            settings: Vec::new(),
+            output: None
+        }
+    }
+}
+
+impl ExperimentOptions {
+    pub(crate) const COMMAND: &'static str = "experiment";
+
+    // Parses the arguments that follow the program's name
+    pub(crate) fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, CliError> {
+        let mut options = Self::default();
+
+        let mut args = args.into_iter().skip_while(|arg| arg == Self::COMMAND);
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| CliError::MissingValue(arg.clone()));
+            match arg.as_str() {
+                "--replicates" => options.replicates = Options::number(&arg, value()?)?,
+                "--ticks" => options.ticks = Options::number(&arg, value()?)?,
+                "--seed" => options.seed = Options::number(&arg, value()?)?,
+                "--output" => options.output = Some(value()?),
+                _ if arg.starts_with("--") => return Err(CliError::UnknownArgument(arg)),
+                _ => options.settings.push(arg)
+            }
+        }
+
+        Ok(options)
+    }
+
+    fn experiments(&self) -> Result<Vec<Experiment>, CliError> {
+        if self.settings.is_empty() {
+            return Ok(vec![Experiment {
+                name: String::from("default"),
+                settings: SimulationSettings::default()
+            } ]);
+        }
+
+        self.settings.iter().map(|path| {
+            let settings = SimulationSettings::from_path(path).map_err(|e| CliError::Settings(path.clone(), e))?;
+            let name = std::path::Path::new(path).file_stem()
+                .map_or_else(|| path.clone(), |stem| stem.to_string_lossy().into_owned());
+
+            Ok(Experiment { name, settings })
+        } ).collect()
+    }
+}
+
+// Returns the results table, which has already been written to the output path as CSV if one was given
+pub(crate) fn run_experiments(options: &ExperimentOptions) -> Result<String, CliError> {
+    let experiments = options.experiments()?;
+    tracing::info!("running {} replicates of {} parameter sets", options.replicates, experiments.len());
+
+    let results = crate::experiments::run(&experiments, options.replicates, options.ticks, options.seed);
+    if let Some(path) = &options.output {
+        crate::platform::write(path, results.csv()).map_err(|e| CliError::Output(path.clone(), e))?;
+    }
+
+    Ok(results.to_string())
+}
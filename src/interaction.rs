@@ -0,0 +1,127 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::fmt::Write;
+
+// A directed interaction between two agents, identified by Agent::id
+#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Interaction {
+    pub tick: u64,
+    pub source: u64,
+    pub target: u64,
+    pub kind: InteractionKind
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+pub enum InteractionKind {
+    // the source killed the target
+    Killed,
+    // the target ate food that the source produced, or left behind when it died
+    Fed
+}
+
+impl fmt::Display for InteractionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            InteractionKind::Killed => "killed",
+            InteractionKind::Fed => "fed"
+        } )
+    }
+}
+
+// Interactions aggregated by their endpoints and kind
+#[derive(Debug, Copy, Clone)]
+pub struct InteractionEdge {
+    pub source: u64,
+    pub target: u64,
+    pub kind: InteractionKind,
+    pub count: usize
+}
+
+// Keeps the interactions from the most recent `window` steps
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct InteractionLog {
+    window: u64,
+    interactions: VecDeque<Interaction>
+}
+
+impl InteractionLog {
+    pub fn new(window: u64) -> Self {
+        Self {
+            window,
+            interactions: VecDeque::new()
+        }
+    }
+
+    pub fn record(&mut self, interaction: Interaction) {
+        while let Some(oldest) = self.interactions.front() {
+            if oldest.tick + self.window >= interaction.tick {
+                break;
+            }
+
+            self.interactions.pop_front();
+        }
+
+        self.interactions.push_back(interaction);
+    }
+
+    pub fn len(&self) -> usize {
+        self.interactions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.interactions.is_empty()
+    }
+
+    // Sorted by source, then target, then kind
+    pub fn edges(&self) -> Vec<InteractionEdge> {
+        let mut counts: BTreeMap<(u64, u64, InteractionKind), usize> = BTreeMap::new();
+        for interaction in self.interactions.iter() {
+            *counts.entry((interaction.source, interaction.target, interaction.kind)).or_insert(0) += 1;
+        }
+
+        counts.into_iter().map(|((source, target, kind), count)| {
+            InteractionEdge { source, target, kind, count }
+        } ).collect()
+    }
+
+    // The ids of every agent in the network, in ascending order
+    pub fn agents(&self) -> Vec<u64> {
+        let mut agents = self.interactions.iter()
+            .flat_map(|interaction| [interaction.source, interaction.target])
+            .collect::<Vec<u64>>();
+
+        agents.sort_unstable();
+        agents.dedup();
+        agents
+    }
+
+    // Writes the network as a GraphML document
+    // Parallel interactions are merged into a single edge with a count
+    pub fn graphml(&self) -> String {
+        let mut graphml = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"kind\" for=\"edge\" attr.name=\"kind\" attr.type=\"string\"/>\n",
+            "  <key id=\"count\" for=\"edge\" attr.name=\"count\" attr.type=\"int\"/>\n",
+            "  <graph id=\"interactions\" edgedefault=\"directed\">\n"
+        ));
+
+        // writing to a String can't fail
+        for agent in self.agents() {
+            let _ = writeln!(graphml, "    <node id=\"a{}\"/>", agent);
+        }
+
+        for (index, edge) in self.edges().iter().enumerate() {
+            let _ = writeln!(graphml,
+                "    <edge id=\"e{}\" source=\"a{}\" target=\"a{}\"><data key=\"kind\">{}</data><data key=\"count\">{}</data></edge>",
+                index,
+                edge.source,
+                edge.target,
+                edge.kind,
+                edge.count);
+        }
+
+        graphml.push_str("  </graph>\n</graphml>\n");
+        graphml
+    }
+}
@@ -0,0 +1,180 @@
+use std::io;
+
+use crate::interface::frame_export::{Frame, FrameExport};
+use crate::interface::Message;
+
+// Records the run as an animated GIF, or an APNG when the export path ends in .png
+// While recording, a frame is rasterized like an exported PNG after every `skip` steps,
+// with each Tile `scale` pixels across, and the animation is encoded once recording stops
+pub(crate) struct AnimationRecorder {
+    // None unless recording
+    recording: Option<Recording>,
+    pub(crate) skip: String,
+    pub(crate) scale: String,
+    state_toggle: iced::button::State,
+    state_skip: iced::text_input::State,
+    state_scale: iced::text_input::State
+}
+
+// The options chosen when recording started, and the frames captured since
+struct Recording {
+    skip: u64,
+    scale: u32,
+    // the steps taken since the last frame was captured
+    steps: u64,
+    frames: Vec<image::RgbImage>
+}
+
+impl Default for AnimationRecorder {
+    fn default() -> Self {
+        Self {
+            recording: None,
+            skip: String::from("1"),
+            scale: String::from("4"),
+            state_toggle: iced::button::State::default(),
+            state_skip: iced::text_input::State::default(),
+            state_scale: iced::text_input::State::default()
+        }
+    }
+}
+
+impl AnimationRecorder {
+    const PADDING: u16 = 10;
+
+    // each frame is shown for this long
+    const FRAME_DELAY_MS: u16 = 100;
+    // bounds the memory held by the frames, recording stops once this many are captured
+    const MAX_FRAMES: usize = 500;
+
+    pub(crate) fn recording(&self) -> bool {
+        self.recording.is_some()
+    }
+
+    // Returns None if the frame skip or scale aren't positive whole numbers
+    pub(crate) fn start(&mut self) -> Option<()> {
+        let skip = self.skip.trim().parse::<u64>().ok().filter(|skip| *skip > 0)?;
+        let scale = self.scale.trim().parse::<u32>().ok().filter(|scale| *scale > 0)?;
+
+        self.recording = Some(Recording {
+            skip,
+            scale,
+            // the first frame is captured after the next step
+            steps: skip,
+            frames: Vec::new()
+        } );
+
+        Some(())
+    }
+
+    // Called after every step, captures a frame if enough steps have passed
+    // Returns false once the animation is full
+    pub(crate) fn capture(&mut self, frame: Frame<'_>) -> bool {
+        let recording = match self.recording.as_mut() {
+            Some(recording) => recording,
+            None => return true
+        };
+
+        if recording.steps >= recording.skip {
+            recording.steps = 0;
+            recording.frames.push(FrameExport::rasterize(frame, recording.scale, false));
+        }
+
+        recording.steps += 1;
+        recording.frames.len() < Self::MAX_FRAMES
+    }
+
+    // Stops recording and encodes the frames, returning the number of frames written
+    // Nothing is written if no frames were captured
+    pub(crate) fn finish(&mut self, path: &str) -> io::Result<usize> {
+        let frames = match self.recording.take() {
+            Some(recording) if !recording.frames.is_empty() => recording.frames,
+            _ => return Ok(0)
+        };
+
+        let encoded = if path.ends_with(".png") {
+            Self::apng(&frames).map_err(io::Error::other)?
+        } else {
+            Self::gif(&frames).map_err(io::Error::other)?
+        };
+
+        crate::platform::write(path, encoded)?;
+        Ok(frames.len())
+    }
+
+    fn gif(frames: &[image::RgbImage]) -> Result<Vec<u8>, image::ImageError> {
+        use image::codecs::gif::{GifEncoder, Repeat};
+
+        let mut encoded = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut encoded);
+            encoder.set_repeat(Repeat::Infinite)?;
+            encoder.encode_frames(frames.iter().map(|frame| {
+                image::Frame::from_parts(
+                    image::DynamicImage::ImageRgb8(frame.clone()).into_rgba8(),
+                    0,
+                    0,
+                    image::Delay::from_numer_denom_ms(Self::FRAME_DELAY_MS as u32, 1))
+            } ))?;
+        }
+
+        Ok(encoded)
+    }
+
+    fn apng(frames: &[image::RgbImage]) -> Result<Vec<u8>, png::EncodingError> {
+        // every frame is the same size, as recording stops whenever the world is replaced
+        let (width, height) = frames.first().map_or((1, 1), |frame| frame.dimensions());
+
+        let mut encoded = Vec::new();
+        {
+            let mut encoder = png::Encoder::new(&mut encoded, width, height);
+            encoder.set_color(png::ColorType::Rgb);
+            encoder.set_depth(png::BitDepth::Eight);
+            // plays forever
+            encoder.set_animated(frames.len() as u32, 0)?;
+            encoder.set_frame_delay(Self::FRAME_DELAY_MS, 1000)?;
+
+            let mut writer = encoder.write_header()?;
+            for frame in frames {
+                writer.write_image_data(frame.as_raw())?;
+            }
+
+            writer.finish()?;
+        }
+
+        Ok(encoded)
+    }
+
+    pub(crate) fn view(&mut self) -> iced::Element<'_, Message> {
+        use iced::Length;
+
+        use Message::*;
+        let label = match &self.recording {
+            Some(recording) => format!("Stop Recording ({} frames)", recording.frames.len()),
+            None => String::from("Record Animation")
+        };
+
+        iced::Row::new()
+            .push(
+                iced::TextInput::new(
+                    &mut self.state_skip,
+                    "Steps per frame",
+                    &self.skip,
+                    AnimationSkipChange)
+                    .width(Length::Fill))
+            .push(
+                iced::TextInput::new(
+                    &mut self.state_scale,
+                    "Pixels per tile",
+                    &self.scale,
+                    AnimationScaleChange)
+                    .width(Length::Fill))
+            .push(
+                iced::Button::new(
+                    &mut self.state_toggle,
+                    iced::Text::new(label))
+                    .on_press(ToggleAnimation))
+            .width(Length::Fill)
+            .spacing(Self::PADDING)
+            .into()
+    }
+}
@@ -0,0 +1,133 @@
+use std::collections::BTreeMap;
+
+use iced::canvas;
+
+use crate::benchmark::Group;
+use crate::interface::Message;
+
+// Charts each species' and lineage's mean benchmark score over time,
+// in the species' color or the lineage's marker color, as on the Canvas
+// Steps covered by a rule pulse are shaded
+pub(crate) struct BenchmarkView {
+    series: BTreeMap<Group, Vec<(u64, f32)>>,
+    // the first and last tick of each pulse
+    bands: Vec<(u64, u64)>,
+    // the theme's species and marker colors, so groups match their Agents on the Canvas
+    species: [[u8; 3]; 4],
+    markers: [[u8; 3]; 4],
+    cache: canvas::Cache
+}
+
+impl BenchmarkView {
+    const HEIGHT: u16 = 240;
+    const PADDING: f32 = 16f32;
+    const POINT_RADIUS: f32 = 3f32;
+    const LINE_WIDTH: f32 = 2f32;
+
+    const COLOR_BACKGROUND: [u8; 3] = [0x1A, 0x1A, 0x1A];
+    const COLOR_AXES: [u8; 3] = [0x5A, 0x5A, 0x5A];
+    const COLOR_BAND: [u8; 3] = [0xFF, 0xFF, 0xFF];
+    const BAND_ALPHA: f32 = 0.08f32;
+
+    pub(crate) fn new(series: BTreeMap<Group, Vec<(u64, f32)>>, bands: Vec<(u64, u64)>, species: [[u8; 3]; 4], markers: [[u8; 3]; 4]) -> Self {
+        Self {
+            series,
+            bands,
+            species,
+            markers,
+            cache: canvas::Cache::new()
+        }
+    }
+
+    pub(crate) fn view(self) -> iced::Element<'static, Message> {
+        use iced::Length;
+        iced::Canvas::new(self)
+            .width(Length::Fill)
+            .height(Length::Units(Self::HEIGHT))
+            .into()
+    }
+
+    fn color(color: [u8; 3]) -> iced::Color {
+        iced::Color::from_rgb8(color[0], color[1], color[2])
+    }
+}
+
+impl canvas::Program<Message> for BenchmarkView {
+    fn draw(&self, bounds: iced::Rectangle, _cursor: canvas::Cursor) -> Vec<canvas::Geometry> {
+        vec![
+            self.cache.draw(bounds.size(), |frame| {
+                frame.fill_rectangle(
+                    iced::Point::new(0f32, 0f32),
+                    bounds.size(),
+                    Self::color(Self::COLOR_BACKGROUND)
+                );
+
+                let width = bounds.width - Self::PADDING * 2f32;
+                let height = bounds.height - Self::PADDING * 2f32;
+
+                frame.stroke(
+                    &canvas::Path::new(|path| {
+                        path.move_to(iced::Point::new(Self::PADDING, Self::PADDING));
+                        path.line_to(iced::Point::new(Self::PADDING, Self::PADDING + height));
+                        path.line_to(iced::Point::new(Self::PADDING + width, Self::PADDING + height));
+                    } ),
+                    canvas::Stroke::default().with_color(Self::color(Self::COLOR_AXES))
+                );
+
+                let points = self.series.values().flatten();
+                let first = points.clone().map(|(tick, _)| *tick).min().unwrap_or(0);
+                let last = points.clone().map(|(tick, _)| *tick).max().unwrap_or(0);
+                let best = points.map(|(_, score)| *score).fold(1f32, f32::max);
+
+                // a single round is drawn in the middle of the chart
+                let position = |tick: u64, score: f32| iced::Point::new(
+                    Self::PADDING + if last > first {
+                        width * (tick - first) as f32 / (last - first) as f32
+                    } else {
+                        width / 2f32
+                    },
+                    Self::PADDING + height * (1f32 - score / best)
+                );
+
+                // bands are clipped to the charted steps
+                if last > first {
+                    for (start, end) in self.bands.iter().map(|(start, end)| ((*start).max(first), (*end).min(last))) {
+                        if start >= end {
+                            continue;
+                        }
+
+                        let left = position(start, 0f32).x;
+                        frame.fill_rectangle(
+                            iced::Point::new(left, Self::PADDING),
+                            iced::Size::new(position(end, 0f32).x - left, height),
+                            iced::Color::from_rgba8(Self::COLOR_BAND[0], Self::COLOR_BAND[1], Self::COLOR_BAND[2], Self::BAND_ALPHA));
+                    }
+                }
+
+                for (group, samples) in self.series.iter() {
+                    let color = Self::color(match group {
+                        Group::Species(species) => self.species[*species as usize % self.species.len()],
+                        Group::Lineage(marker) => self.markers[*marker as usize % self.markers.len()]
+                    } );
+
+                    frame.stroke(
+                        &canvas::Path::new(|path| {
+                            for (index, (tick, score)) in samples.iter().enumerate() {
+                                if index == 0 {
+                                    path.move_to(position(*tick, *score));
+                                } else {
+                                    path.line_to(position(*tick, *score));
+                                }
+                            }
+                        } ),
+                        canvas::Stroke::default().with_width(Self::LINE_WIDTH).with_color(color)
+                    );
+
+                    for (tick, score) in samples.iter() {
+                        frame.fill(&canvas::Path::circle(position(*tick, *score), Self::POINT_RADIUS), color);
+                    }
+                }
+            })
+        ]
+    }
+}
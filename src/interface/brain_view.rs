@@ -0,0 +1,287 @@
+use std::fmt::Write;
+
+use iced::canvas;
+
+use petgraph::graph;
+use petgraph::visit::EdgeRef;
+
+use crate::agent::{Edge, Node};
+use crate::interface::Message;
+
+// Draws an Agent's brain as a layered node-link diagram
+// Senses occupy the leftmost layer and actions the rightmost,
+// internal nodes are placed in between, one layer beyond their deepest input
+// When given the followed Agent's activations, each node is ringed by its latest output,
+// blue when positive and red when negative, growing thicker as the output grows stronger
+pub(crate) struct BrainView {
+    brain: graph::Graph<Node, Edge>,
+    // each node's output, indexed by NodeIndex
+    activations: Option<Vec<Option<f32>>>,
+    cache: canvas::Cache
+}
+
+impl BrainView {
+    const HEIGHT: u16 = 320;
+    const PADDING: f32 = 24f32;
+    const NODE_RADIUS: f32 = 6f32;
+    const LABEL_SIZE: f32 = 12f32;
+    // a ring for an output of 1 or more, in either direction
+    const MAX_RING_WIDTH: f32 = 4f32;
+
+    // the size of exported images
+    const SVG_SIZE: iced::Size = iced::Size::new(640f32, 320f32);
+
+    pub(crate) fn new(brain: graph::Graph<Node, Edge>) -> Self {
+        Self {
+            brain,
+            activations: None,
+            cache: canvas::Cache::new()
+        }
+    }
+
+    pub(crate) fn activations(mut self, activations: Option<Vec<Option<f32>>>) -> Self {
+        self.activations = activations;
+        self
+    }
+
+    pub(crate) fn view(self) -> iced::Element<'static, Message> {
+        use iced::Length;
+        iced::Canvas::new(self)
+            .width(Length::Fill)
+            .height(Length::Units(Self::HEIGHT))
+            .into()
+    }
+
+    // Renders the same layout as the canvas into a standalone SVG document
+    pub(crate) fn svg(&self) -> String {
+        let size = Self::SVG_SIZE;
+        let positions = self.positions(size);
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+            size.width,
+            size.height
+        );
+
+        // writing to a String can't fail
+        let _ = writeln!(svg, "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>", Self::hex(Self::COLOR_BACKGROUND));
+
+        for edge in self.brain.edge_references() {
+            let color = Self::hex(if edge.weight().additive { Self::COLOR_ADDITIVE } else { Self::COLOR_INHIBITORY });
+
+            let source = positions[edge.source().index()];
+            let target = positions[edge.target().index()];
+            let _ = if source == target {
+                writeln!(svg, "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"1.5\"/>",
+                    source.x,
+                    source.y - Self::NODE_RADIUS * 1.5f32,
+                    Self::NODE_RADIUS,
+                    color)
+            } else {
+                writeln!(svg, "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"1.5\"/>",
+                    source.x,
+                    source.y,
+                    target.x,
+                    target.y,
+                    color)
+            };
+        }
+
+        for index in self.brain.node_indices() {
+            let position = positions[index.index()];
+            let _ = writeln!(svg, "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>",
+                position.x,
+                position.y,
+                Self::NODE_RADIUS,
+                Self::hex(Self::node_rgb(&self.brain[index])));
+            let _ = writeln!(svg, "<text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"white\" text-anchor=\"middle\" dominant-baseline=\"hanging\">{}</text>",
+                position.x,
+                position.y + Self::NODE_RADIUS,
+                Self::LABEL_SIZE,
+                self.brain[index]);
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    // Returns the layer of each node, indexed by NodeIndex
+    fn layers(&self) -> Vec<usize> {
+        let internals = self.brain.node_weights()
+            .filter(|node| matches!(node, Node::Internal { .. }))
+            .count();
+
+        // relaxation is bounded by the number of internal nodes,
+        // so cycles can't push nodes arbitrarily deep
+        let mut layers = vec![0usize; self.brain.node_count()];
+        for _ in 0..internals {
+            let mut changed = false;
+            for edge in self.brain.edge_references() {
+                if let Node::Internal { .. } = self.brain[edge.target()] {
+                    let layer = usize::min(layers[edge.source().index()] + 1, internals);
+                    if layer > layers[edge.target().index()] {
+                        layers[edge.target().index()] = layer;
+                        changed = true;
+                    }
+                }
+            }
+
+            if !changed {
+                break;
+            }
+        }
+
+        let last = layers.iter().copied().max().unwrap_or(0) + 1;
+        for index in self.brain.node_indices() {
+            if let Node::Action(..) = self.brain[index] {
+                layers[index.index()] = last;
+            }
+        }
+
+        layers
+    }
+
+    // Spreads the nodes of each layer evenly along its column
+    fn positions(&self, size: iced::Size) -> Vec<iced::Point> {
+        let layers = self.layers();
+        let columns = layers.iter().copied().max().unwrap_or(0) + 1;
+
+        let mut rows = vec![0usize; columns];
+        for layer in layers.iter() {
+            rows[*layer] += 1;
+        }
+
+        let width = size.width - Self::PADDING * 2f32;
+        let height = size.height - Self::PADDING * 2f32;
+
+        let mut placed = vec![0usize; columns];
+        layers.iter().map(|layer| {
+            let row = placed[*layer];
+            placed[*layer] += 1;
+
+            iced::Point::new(
+                Self::PADDING + width * (*layer as f32 + 0.5f32) / columns as f32,
+                Self::PADDING + height * (row as f32 + 0.5f32) / rows[*layer] as f32
+            )
+        } ).collect()
+    }
+}
+
+// Colors
+impl BrainView {
+    const COLOR_BACKGROUND: [u8; 3] = [0x1A, 0x1A, 0x1A];
+    const COLOR_SENSE: [u8; 3] = [0x50, 0xFF, 0x78];
+    const COLOR_INTERNAL: [u8; 3] = [0xB4, 0xB4, 0xB4];
+    const COLOR_ACTION: [u8; 3] = [0x64, 0x64, 0xFF];
+    const COLOR_ADDITIVE: [u8; 3] = [0x3C, 0xE6, 0xFF];
+    const COLOR_INHIBITORY: [u8; 3] = [0xFF, 0x50, 0x50];
+
+    fn color(color: [u8; 3]) -> iced::Color {
+        iced::Color::from_rgb8(color[0], color[1], color[2])
+    }
+
+    fn hex(color: [u8; 3]) -> String {
+        format!("#{:02X}{:02X}{:02X}", color[0], color[1], color[2])
+    }
+
+    fn node_rgb(node: &Node) -> [u8; 3] {
+        use Node::*;
+        match node {
+            Sense(..) => Self::COLOR_SENSE,
+            Internal { .. } => Self::COLOR_INTERNAL,
+            Action(..) => Self::COLOR_ACTION
+        }
+    }
+}
+
+// this block contains helper methods
+impl BrainView {
+    fn draw_activation(&self, frame: &mut canvas::Frame, position: iced::Point, value: f32) {
+        let width = Self::MAX_RING_WIDTH * value.abs().min(1f32);
+        if width > 0f32 {
+            frame.stroke(
+                &canvas::Path::circle(position, Self::NODE_RADIUS + width / 2f32 + 1f32),
+                canvas::Stroke::default()
+                    .with_width(width)
+                    .with_color(Self::color(if value > 0f32 {
+                        Self::COLOR_ADDITIVE
+                    } else {
+                        Self::COLOR_INHIBITORY
+                    } )));
+        }
+
+        frame.fill_text(canvas::Text {
+            content: format!("{:.2}", value),
+            position: iced::Point::new(position.x, position.y - Self::NODE_RADIUS - Self::MAX_RING_WIDTH),
+            color: iced::Color::WHITE,
+            size: Self::LABEL_SIZE,
+            horizontal_alignment: iced::alignment::Horizontal::Center,
+            vertical_alignment: iced::alignment::Vertical::Bottom,
+            ..canvas::Text::default()
+        } );
+    }
+}
+
+impl canvas::Program<Message> for BrainView {
+    fn draw(&self, bounds: iced::Rectangle, _cursor: canvas::Cursor) -> Vec<canvas::Geometry> {
+        vec![
+            self.cache.draw(bounds.size(), |frame| {
+                frame.fill_rectangle(
+                    iced::Point::new(0f32, 0f32),
+                    bounds.size(),
+                    Self::color(Self::COLOR_BACKGROUND)
+                );
+
+                let positions = self.positions(bounds.size());
+
+                // edges are drawn first, so nodes sit on top of them
+                for edge in self.brain.edge_references() {
+                    let stroke = canvas::Stroke::default()
+                        .with_width(1.5f32)
+                        .with_color(Self::color(if edge.weight().additive {
+                            Self::COLOR_ADDITIVE
+                        } else {
+                            Self::COLOR_INHIBITORY
+                        } ));
+
+                    let source = positions[edge.source().index()];
+                    let target = positions[edge.target().index()];
+
+                    // self-connections are drawn as a small loop above the node
+                    let path = if source == target {
+                        canvas::Path::circle(
+                            iced::Point::new(source.x, source.y - Self::NODE_RADIUS * 1.5f32),
+                            Self::NODE_RADIUS
+                        )
+                    } else {
+                        canvas::Path::line(source, target)
+                    };
+
+                    frame.stroke(&path, stroke);
+                }
+
+                for index in self.brain.node_indices() {
+                    let position = positions[index.index()];
+                    frame.fill(
+                        &canvas::Path::circle(position, Self::NODE_RADIUS),
+                        Self::color(Self::node_rgb(&self.brain[index]))
+                    );
+
+                    // nodes that received no input this tick are left bare
+                    if let Some(value) = self.activations.as_ref().and_then(|activations| activations.get(index.index()).copied().flatten()) {
+                        self.draw_activation(frame, position, value);
+                    }
+
+                    frame.fill_text(canvas::Text {
+                        content: format!("{}", self.brain[index]),
+                        position: iced::Point::new(position.x, position.y + Self::NODE_RADIUS),
+                        color: iced::Color::WHITE,
+                        size: Self::LABEL_SIZE,
+                        horizontal_alignment: iced::alignment::Horizontal::Center,
+                        ..canvas::Text::default()
+                    } );
+                }
+            })
+        ]
+    }
+}
@@ -0,0 +1,280 @@
+use std::fmt;
+use std::str::FromStr;
+
+use strum::IntoEnumIterator;
+
+use crate::agent::Agent;
+use crate::agent::gene::ActionType;
+use crate::error::ColorRuleError;
+use crate::interface::Message;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Property {
+    Fitness,
+    Energy,
+    Thirst,
+    Lineage,
+    Species,
+    // the Agent's most recent action
+    Action
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Comparison {
+    Greater,
+    Less,
+    GreaterOrEqual,
+    LessOrEqual,
+    Equal,
+    NotEqual
+}
+
+impl Comparison {
+    // longer symbols come first, so `>=` isn't read as `>`
+    const SYMBOLS: [(&'static str, Comparison); 6] = [
+        (">=", Comparison::GreaterOrEqual),
+        ("<=", Comparison::LessOrEqual),
+        ("==", Comparison::Equal),
+        ("!=", Comparison::NotEqual),
+        (">", Comparison::Greater),
+        ("<", Comparison::Less)
+    ];
+
+    fn compare(&self, left: u8, right: u8) -> bool {
+        use Comparison::*;
+        match self {
+            Greater => left > right,
+            Less => left < right,
+            GreaterOrEqual => left >= right,
+            LessOrEqual => left <= right,
+            Equal => left == right,
+            NotEqual => left != right
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        Self::SYMBOLS.iter().find(|(_, comparison)| comparison == self).unwrap().0
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Value {
+    Number(u8),
+    Action(ActionType)
+}
+
+// Recolors every Agent that matches its condition, such as `fitness > 20 = gold`
+// Rules are kept as text in the session file, so they can also be edited by hand
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub(crate) struct ColorRule {
+    property: Property,
+    comparison: Comparison,
+    value: Value,
+    pub(crate) color: [u8; 3]
+}
+
+impl ColorRule {
+    const COLORS: [(&'static str, [u8; 3]); 12] = [
+        ("red", [0xFF, 0x32, 0x32]),
+        ("orange", [0xFF, 0x96, 0x28]),
+        ("gold", [0xFF, 0xD7, 0x00]),
+        ("yellow", [0xFF, 0xFF, 0x50]),
+        ("green", [0x50, 0xFF, 0x78]),
+        ("cyan", [0x3C, 0xE6, 0xFF]),
+        ("blue", [0x64, 0x64, 0xFF]),
+        ("purple", [0xDC, 0x64, 0xFF]),
+        ("pink", [0xFF, 0x8C, 0xC8]),
+        ("white", [0xFF, 0xFF, 0xFF]),
+        ("gray", [0x96, 0x96, 0x96]),
+        ("black", [0x00, 0x00, 0x00])
+    ];
+
+    pub(crate) fn matches(&self, agent: &Agent) -> bool {
+        let number = match self.property {
+            Property::Fitness => u8::from(agent.fitness),
+            Property::Energy => u8::from(agent.energy),
+            Property::Thirst => u8::from(agent.thirst),
+            Property::Species => agent.attributes.species(),
+            // unmarked Agents belong to no lineage
+            Property::Lineage => match agent.marker {
+                Some(marker) => marker,
+                None => return false
+            },
+            Property::Action => {
+                let action = match agent.last_action() {
+                    Some(action) => action,
+                    None => return false
+                };
+
+                return match (self.value, self.comparison) {
+                    (Value::Action(value), Comparison::Equal) => action == value,
+                    (Value::Action(value), Comparison::NotEqual) => action != value,
+                    _ => false
+                };
+            }
+        };
+
+        match self.value {
+            Value::Number(value) => self.comparison.compare(number, value),
+            Value::Action(..) => false
+        }
+    }
+
+    fn parse_color(color: &str) -> Result<[u8; 3], ColorRuleError> {
+        if let Some((_, color)) = Self::COLORS.iter().find(|(name, _)| name.eq_ignore_ascii_case(color)) {
+            return Ok(*color);
+        }
+
+        let hex = color.strip_prefix('#')
+            .filter(|hex| hex.len() == 6 && hex.is_ascii())
+            .ok_or_else(|| ColorRuleError::UnknownColor(String::from(color)))?;
+
+        let mut channels = [0u8; 3];
+        for (index, channel) in channels.iter_mut().enumerate() {
+            *channel = u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16)
+                .map_err(|_| ColorRuleError::UnknownColor(String::from(color)))?;
+        }
+
+        Ok(channels)
+    }
+}
+
+impl FromStr for ColorRule {
+    type Err = ColorRuleError;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let (condition, color) = rule.rsplit_once('=')
+            .filter(|(condition, _)| !condition.trim_end().ends_with(['=', '!', '<', '>']))
+            .ok_or_else(|| ColorRuleError::Malformed(String::from(rule)))?;
+
+        let (property, comparison, value) = Comparison::SYMBOLS.iter()
+            .find_map(|(symbol, comparison)| condition.split_once(symbol)
+                .map(|(property, value)| (property.trim(), *comparison, value.trim())))
+            .ok_or_else(|| ColorRuleError::Malformed(String::from(rule)))?;
+
+        let property = match property.to_ascii_lowercase().as_str() {
+            "fitness" => Property::Fitness,
+            "energy" => Property::Energy,
+            "thirst" => Property::Thirst,
+            "lineage" => Property::Lineage,
+            "species" => Property::Species,
+            "action" => Property::Action,
+            _ => return Err(ColorRuleError::UnknownProperty(String::from(property)))
+        };
+
+        let value = if property == Property::Action {
+            let action = ActionType::iter()
+                .find(|action| format!("{:?}", action).eq_ignore_ascii_case(value))
+                .ok_or_else(|| ColorRuleError::InvalidValue(String::from(value)))?;
+
+            // actions have no order
+            if !matches!(comparison, Comparison::Equal | Comparison::NotEqual) {
+                return Err(ColorRuleError::InvalidValue(String::from(value)));
+            }
+
+            Value::Action(action)
+        } else {
+            Value::Number(value.parse::<u8>().map_err(|_| ColorRuleError::InvalidValue(String::from(value)))?)
+        };
+
+        Ok(Self {
+            property,
+            comparison,
+            value,
+            color: Self::parse_color(color.trim())?
+        } )
+    }
+}
+
+impl fmt::Display for ColorRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {} = ",
+            format!("{:?}", self.property).to_ascii_lowercase(),
+            self.comparison.symbol(),
+            match self.value {
+                Value::Number(value) => value.to_string(),
+                Value::Action(action) => format!("{:?}", action)
+            })?;
+
+        match Self::COLORS.iter().find(|(_, color)| *color == self.color) {
+            Some((name, _)) => write!(f, "{}", name),
+            None => write!(f, "#{:02X}{:02X}{:02X}", self.color[0], self.color[1], self.color[2])
+        }
+    }
+}
+
+impl TryFrom<String> for ColorRule {
+    type Error = ColorRuleError;
+
+    fn try_from(rule: String) -> Result<Self, Self::Error> {
+        rule.parse()
+    }
+}
+
+impl From<ColorRule> for String {
+    fn from(rule: ColorRule) -> Self {
+        rule.to_string()
+    }
+}
+
+// Lists the current rules, each with a button to remove it, beneath a field for adding new ones
+#[derive(Default)]
+pub(crate) struct ColorRuleEditor {
+    pub(crate) input: String,
+    state_input: iced::text_input::State,
+    state_add: iced::button::State,
+    state_remove: Vec<iced::button::State>
+}
+
+impl ColorRuleEditor {
+    const PADDING: u16 = 10;
+
+    pub(crate) fn view(&mut self, rules: &[ColorRule]) -> iced::Element<'_, Message> {
+        use iced::Length;
+
+        use Message::*;
+        self.state_remove.resize_with(rules.len(), iced::button::State::default);
+
+        let mut editor = iced::Column::new()
+            .push(
+                iced::Text::new("Earlier rules take priority, e.g. fitness > 20 = gold or action == Kill = red")
+                    .width(Length::Fill))
+            .push(
+                iced::Row::new()
+                    .push(
+                        iced::TextInput::new(
+                            &mut self.state_input,
+                            "property comparison value = color",
+                            &self.input,
+                            ColorRuleInput)
+                            .width(Length::Fill)
+                            .on_submit(AddColorRule))
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_add,
+                            iced::Text::new("Add Rule"))
+                            .on_press(AddColorRule))
+                    .width(Length::Fill)
+                    .spacing(Self::PADDING))
+            .width(Length::Fill)
+            .spacing(Self::PADDING);
+
+        for (index, (rule, state)) in rules.iter().zip(self.state_remove.iter_mut()).enumerate() {
+            editor = editor.push(
+                iced::Row::new()
+                    .push(
+                        iced::Text::new(rule.to_string())
+                            .width(Length::Fill))
+                    .push(
+                        iced::Button::new(
+                            state,
+                            iced::Text::new("Remove"))
+                            .on_press(RemoveColorRule(index)))
+                    .width(Length::Fill)
+                    .spacing(Self::PADDING));
+        }
+
+        editor.into()
+    }
+}
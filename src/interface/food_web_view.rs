@@ -0,0 +1,97 @@
+use iced::canvas;
+
+use crate::interaction::{InteractionEdge, InteractionKind};
+use crate::interface::Message;
+
+// Draws the interaction network with agents evenly spaced around a circle
+// Edge thickness grows with the number of interactions between two agents
+pub(crate) struct FoodWebView {
+    agents: Vec<u64>,
+    edges: Vec<InteractionEdge>,
+    cache: canvas::Cache
+}
+
+impl FoodWebView {
+    const HEIGHT: u16 = 320;
+    const PADDING: f32 = 24f32;
+    const NODE_RADIUS: f32 = 4f32;
+    const MAXIMUM_EDGE_WIDTH: f32 = 4f32;
+
+    pub(crate) fn new(agents: Vec<u64>, edges: Vec<InteractionEdge>) -> Self {
+        Self {
+            agents,
+            edges,
+            cache: canvas::Cache::new()
+        }
+    }
+
+    pub(crate) fn view(self) -> iced::Element<'static, Message> {
+        use iced::Length;
+        iced::Canvas::new(self)
+            .width(Length::Fill)
+            .height(Length::Units(Self::HEIGHT))
+            .into()
+    }
+
+    fn position(&self, agent: u64, size: iced::Size) -> iced::Point {
+        let index = self.agents.binary_search(&agent).unwrap_or(0);
+        let angle = index as f32 / self.agents.len().max(1) as f32 * std::f32::consts::TAU;
+        let radius = f32::min(size.width, size.height) / 2f32 - Self::PADDING;
+
+        iced::Point::new(
+            size.width / 2f32 + radius * angle.cos(),
+            size.height / 2f32 + radius * angle.sin()
+        )
+    }
+}
+
+// Colors
+impl FoodWebView {
+    const COLOR_BACKGROUND: [u8; 3] = [0x1A, 0x1A, 0x1A];
+    const COLOR_AGENT: [u8; 3] = [0x64, 0x64, 0xFF];
+    const COLOR_KILLED: [u8; 3] = [0xFF, 0x50, 0x50];
+    const COLOR_FED: [u8; 3] = [0x50, 0xFF, 0x78];
+
+    fn color(color: [u8; 3]) -> iced::Color {
+        iced::Color::from_rgb8(color[0], color[1], color[2])
+    }
+}
+
+impl canvas::Program<Message> for FoodWebView {
+    fn draw(&self, bounds: iced::Rectangle, _cursor: canvas::Cursor) -> Vec<canvas::Geometry> {
+        vec![
+            self.cache.draw(bounds.size(), |frame| {
+                frame.fill_rectangle(
+                    iced::Point::new(0f32, 0f32),
+                    bounds.size(),
+                    Self::color(Self::COLOR_BACKGROUND)
+                );
+
+                let busiest = self.edges.iter().map(|edge| edge.count).max().unwrap_or(1);
+                for edge in self.edges.iter() {
+                    let stroke = canvas::Stroke::default()
+                        .with_width(1f32 + (Self::MAXIMUM_EDGE_WIDTH - 1f32) * edge.count as f32 / busiest as f32)
+                        .with_color(Self::color(match edge.kind {
+                            InteractionKind::Killed => Self::COLOR_KILLED,
+                            InteractionKind::Fed => Self::COLOR_FED
+                        } ));
+
+                    frame.stroke(
+                        &canvas::Path::line(
+                            self.position(edge.source, bounds.size()),
+                            self.position(edge.target, bounds.size())
+                        ),
+                        stroke
+                    );
+                }
+
+                for agent in self.agents.iter() {
+                    frame.fill(
+                        &canvas::Path::circle(self.position(*agent, bounds.size()), Self::NODE_RADIUS),
+                        Self::color(Self::COLOR_AGENT)
+                    );
+                }
+            })
+        ]
+    }
+}
@@ -0,0 +1,400 @@
+use std::fmt;
+use std::fmt::Write;
+
+use crate::interface::{CanvasTransform, InterfaceCanvas, Message};
+use crate::interface::viewport::Viewport;
+use crate::simulation::Simulation;
+use crate::tile::coord;
+
+// Annotations that can be burned into an exported frame
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Overlay {
+    // the current step, in the top left corner
+    Step,
+    // a bar spanning a round number of tiles, in the bottom left corner
+    ScaleBar,
+    // the colors used for food, terrain, species and color rules, in the top right corner
+    Legend,
+    // a ring around the targeted Agent
+    Highlight,
+    // the temperature scale, in the bottom right corner of worlds with a climate
+    Colormap
+}
+
+impl Overlay {
+    pub(crate) const ALL: [Overlay; 5] = [
+        Overlay::Step,
+        Overlay::ScaleBar,
+        Overlay::Legend,
+        Overlay::Highlight,
+        Overlay::Colormap
+    ];
+}
+
+impl fmt::Display for Overlay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Overlay::Step => "Step Counter",
+            Overlay::ScaleBar => "Scale Bar",
+            Overlay::Legend => "Legend",
+            Overlay::Highlight => "Highlight",
+            Overlay::Colormap => "Colormap"
+        } )
+    }
+}
+
+// Exports the world as it's drawn on the Canvas to an SVG document, with the chosen overlays burned in
+// The document is `width` pixels across, and its physical size is set by the DPI
+// Frames can also be rasterized to PNG images, with a square block of pixels per Tile
+pub(crate) struct FrameExport {
+    overlays: Vec<Overlay>,
+    pub(crate) width: String,
+    pub(crate) dpi: String,
+    state_overlays: [iced::button::State; Overlay::ALL.len()],
+    state_width: iced::text_input::State,
+    state_dpi: iced::text_input::State,
+    state_export: iced::button::State
+}
+
+impl Default for FrameExport {
+    fn default() -> Self {
+        Self {
+            overlays: Overlay::ALL.to_vec(),
+            width: String::from("1200"),
+            dpi: String::from("300"),
+            state_overlays: Default::default(),
+            state_width: iced::text_input::State::default(),
+            state_dpi: iced::text_input::State::default(),
+            state_export: iced::button::State::default()
+        }
+    }
+}
+
+// Everything needed to draw one frame
+pub(crate) struct Frame<'a> {
+    pub(crate) canvas: &'a InterfaceCanvas,
+    pub(crate) simulation: &'a Simulation,
+    pub(crate) target: Option<coord::Coord>
+}
+
+impl FrameExport {
+    const PADDING: u16 = 10;
+
+    // overlays are laid out for a frame this wide, and scaled with the frame
+    const BASE_WIDTH: f32 = 640f32;
+    const FONT_SIZE: f32 = 14f32;
+    const MARGIN: f32 = 8f32;
+    const SWATCH: f32 = 10f32;
+    const BAR_HEIGHT: f32 = 4f32;
+    const COLORMAP_SIZE: iced::Size = iced::Size::new(120f32, 10f32);
+    const HIGHLIGHT_WIDTH: f32 = 2f32;
+
+    // frames are at least this wide
+    const MIN_WIDTH: u32 = 64;
+    // the scale bar spans the largest of these that fits in a fifth of the world
+    const SCALE_STEPS: [usize; 7] = [1, 2, 5, 10, 20, 50, 100];
+
+    pub(crate) fn toggle(&mut self, overlay: Overlay) {
+        match self.overlays.iter().position(|enabled| *enabled == overlay) {
+            Some(index) => {
+                self.overlays.remove(index);
+            },
+            None => self.overlays.push(overlay)
+        }
+    }
+
+    pub(crate) fn view(&mut self) -> iced::Element<'_, Message> {
+        use iced::Length;
+
+        use Message::*;
+        let mut overlays = iced::Row::new()
+            .width(Length::Fill)
+            .spacing(Self::PADDING);
+
+        for (overlay, state) in Overlay::ALL.iter().zip(self.state_overlays.iter_mut()) {
+            let enabled = self.overlays.contains(overlay);
+            overlays = overlays.push(
+                iced::Button::new(
+                    state,
+                    iced::Text::new(format!("{}: {}", overlay, if enabled { "On" } else { "Off" })))
+                    .width(Length::Fill)
+                    .on_press(ToggleOverlay(*overlay)));
+        }
+
+        iced::Column::new()
+            .push(overlays)
+            .push(
+                iced::Row::new()
+                    .push(
+                        iced::TextInput::new(
+                            &mut self.state_width,
+                            "Width (px)",
+                            &self.width,
+                            FrameWidthChange)
+                            .width(Length::Fill))
+                    .push(
+                        iced::TextInput::new(
+                            &mut self.state_dpi,
+                            "DPI",
+                            &self.dpi,
+                            FrameDpiChange)
+                            .width(Length::Fill))
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_export,
+                            iced::Text::new("Export Frame"))
+                            .on_press(ExportFrame))
+                    .width(Length::Fill)
+                    .spacing(Self::PADDING))
+            .width(Length::Fill)
+            .spacing(Self::PADDING)
+            .into()
+    }
+
+    // Each Tile is a square block of pixels, so the image is about `width` pixels across
+    // Text can't be rasterized without a font, so the highlight is the only overlay drawn
+    // Returns None if the width isn't a whole number
+    pub(crate) fn png(&self, frame: Frame<'_>) -> Option<image::RgbImage> {
+        let width = self.width.trim().parse::<u32>().ok()?.max(Self::MIN_WIDTH);
+        let block = (width / frame.simulation.size().width.max(1) as u32).max(1);
+
+        Some(Self::rasterize(frame, block, self.overlays.contains(&Overlay::Highlight)))
+    }
+
+    // Draws each Tile as a `block` pixels wide square, outlining the target if `highlight` is set
+    pub(crate) fn rasterize(frame: Frame<'_>, block: u32, highlight: bool) -> image::RgbImage {
+        let dimensions = frame.simulation.size();
+
+        let mut pixels = vec![frame.canvas.theme.empty; dimensions.width * dimensions.height];
+        let index = |coord: coord::Coord| coord.y * dimensions.width + coord.x;
+
+        if frame.simulation.settings().climate().is_some() {
+            for (index, pixel) in pixels.iter_mut().enumerate() {
+                let coord = coord::Coord::new(index % dimensions.width, index / dimensions.width);
+                let deviation = frame.simulation.temperature(coord) - crate::climate::Temperature::MILD;
+                let color = if deviation < 0f32 { frame.canvas.theme.cold } else { frame.canvas.theme.hot };
+                *pixel = Self::blend(*pixel, iced::Color::from_rgba8(color[0], color[1], color[2], deviation.abs()));
+            }
+        }
+
+        for coord in frame.simulation.coords() {
+            let color = frame.canvas.color(Some(frame.simulation.get(coord)));
+            pixels[index(coord)] = Self::blend(pixels[index(coord)], color);
+        }
+
+        let target = frame.target.filter(|_| highlight);
+        image::RgbImage::from_fn(dimensions.width as u32 * block, dimensions.height as u32 * block, |x, y| {
+            let coord = coord::Coord::new((x / block) as usize, (y / block) as usize);
+
+            // the highlight outlines the targeted Agent's block
+            let edge = [x % block, y % block].iter().any(|offset| *offset == 0 || *offset == block - 1);
+            if target == Some(coord) && edge {
+                return image::Rgb(frame.canvas.theme.highlight);
+            }
+
+            image::Rgb(pixels[index(coord)])
+        } )
+    }
+
+    // Returns None if the width or DPI aren't whole numbers
+    pub(crate) fn svg(&self, frame: Frame<'_>) -> Option<String> {
+        let width = self.width.trim().parse::<u32>().ok()?.max(Self::MIN_WIDTH) as f32;
+        let dpi = self.dpi.trim().parse::<u32>().ok()?.max(1) as f32;
+
+        let dimensions = frame.simulation.size();
+        let size = iced::Size::new(width, (width * dimensions.height as f32 / dimensions.width.max(1) as f32).ceil());
+        let transform = CanvasTransform::new(size, dimensions, &Viewport::default());
+        let scale = width / Self::BASE_WIDTH;
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{2}in\" height=\"{3}in\" viewBox=\"0 0 {0} {1}\">\n",
+            size.width,
+            size.height,
+            size.width / dpi,
+            size.height / dpi
+        );
+
+        // writing to a String can't fail
+        let _ = writeln!(svg, "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>", Self::hex(frame.canvas.theme.empty));
+
+        if frame.simulation.settings().climate().is_some() {
+            for x in 0..dimensions.width {
+                for y in 0..dimensions.height {
+                    let coord = coord::Coord::new(x, y);
+                    let deviation = frame.simulation.temperature(coord) - crate::climate::Temperature::MILD;
+                    let corner = transform.corner(coord);
+                    let _ = writeln!(svg, "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" fill-opacity=\"{}\"/>",
+                        corner.x,
+                        corner.y,
+                        transform.tile.width,
+                        transform.tile.height,
+                        Self::hex(if deviation < 0f32 { frame.canvas.theme.cold } else { frame.canvas.theme.hot }),
+                        deviation.abs());
+                }
+            }
+        }
+
+        for coord in frame.simulation.coords() {
+            let center = transform.center(coord);
+            let color = frame.canvas.color(Some(frame.simulation.get(coord)));
+            let _ = writeln!(svg, "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" fill-opacity=\"{}\"/>",
+                center.x,
+                center.y,
+                transform.radius(),
+                Self::hex([(color.r * 255f32) as u8, (color.g * 255f32) as u8, (color.b * 255f32) as u8]),
+                color.a);
+        }
+
+        if frame.simulation.settings().barrier().is_some() {
+            let x = transform.corner(coord::Coord::new(dimensions.width / 2, 0)).x;
+            let _ = writeln!(svg, "<line x1=\"{0}\" y1=\"0\" x2=\"{0}\" y2=\"{1}\" stroke=\"{2}\" stroke-width=\"{3}\"/>",
+                x,
+                size.height,
+                Self::hex(frame.canvas.theme.barrier),
+                InterfaceCanvas::BARRIER_WIDTH * scale);
+        }
+
+        let font = Self::FONT_SIZE * scale;
+        let margin = Self::MARGIN * scale;
+        // the overlays are drawn in the theme's highlight color
+        let ink = Self::hex(frame.canvas.theme.highlight);
+
+        if let (true, Some(target)) = (self.overlays.contains(&Overlay::Highlight), frame.target) {
+            let center = transform.center(target);
+            let _ = writeln!(svg, "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>",
+                center.x,
+                center.y,
+                transform.radius() * 1.5f32,
+                ink,
+                Self::HIGHLIGHT_WIDTH * scale);
+        }
+
+        if self.overlays.contains(&Overlay::Step) {
+            let _ = writeln!(svg, "{}", Self::label(&ink, margin, margin, font, "start", "hanging",
+                &format!("Step {}", frame.simulation.tick())));
+        }
+
+        if self.overlays.contains(&Overlay::ScaleBar) {
+            let tiles = Self::SCALE_STEPS.iter()
+                .copied()
+                .rfind(|tiles| *tiles * 5 <= dimensions.width.max(5))
+                .unwrap_or(1);
+
+            let length = transform.tile.width * tiles as f32;
+            let bottom = size.height - margin;
+            let _ = writeln!(svg, "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                margin,
+                bottom - Self::BAR_HEIGHT * scale,
+                length,
+                Self::BAR_HEIGHT * scale,
+                ink);
+            let _ = writeln!(svg, "{}", Self::label(&ink, margin, bottom - (Self::BAR_HEIGHT + 2f32) * scale, font, "start", "text-after-edge",
+                &format!("{} {}", tiles, if tiles == 1 { "tile" } else { "tiles" })));
+        }
+
+        if self.overlays.contains(&Overlay::Legend) {
+            let entries = Self::legend(&frame);
+            for (index, (label, color)) in entries.iter().enumerate() {
+                let y = margin + index as f32 * font * 1.25f32;
+                let x = size.width - margin - Self::SWATCH * scale;
+                let _ = writeln!(svg, "<rect x=\"{}\" y=\"{}\" width=\"{2}\" height=\"{2}\" fill=\"{3}\"/>",
+                    x,
+                    y,
+                    Self::SWATCH * scale,
+                    Self::hex(*color));
+                let _ = writeln!(svg, "{}", Self::label(&ink, x - margin / 2f32, y, font, "end", "hanging", label));
+            }
+        }
+
+        if let (true, Some(..)) = (self.overlays.contains(&Overlay::Colormap), frame.simulation.settings().climate()) {
+            let bar = iced::Size::new(Self::COLORMAP_SIZE.width * scale, Self::COLORMAP_SIZE.height * scale);
+            let (x, y) = (size.width - margin - bar.width, size.height - margin - bar.height);
+            let _ = writeln!(svg, "<defs><linearGradient id=\"colormap\"><stop offset=\"0\" stop-color=\"{}\"/><stop offset=\"0.5\" stop-color=\"{}\"/><stop offset=\"1\" stop-color=\"{}\"/></linearGradient></defs>",
+                Self::hex(frame.canvas.theme.cold),
+                Self::hex(frame.canvas.theme.empty),
+                Self::hex(frame.canvas.theme.hot));
+            let _ = writeln!(svg, "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"url(#colormap)\"/>",
+                x,
+                y,
+                bar.width,
+                bar.height);
+            let _ = writeln!(svg, "{}", Self::label(&ink, x, y - 2f32 * scale, font, "start", "text-after-edge", "cold"));
+            let _ = writeln!(svg, "{}", Self::label(&ink, x + bar.width, y - 2f32 * scale, font, "end", "text-after-edge", "hot"));
+        }
+
+        svg.push_str("</svg>\n");
+        Some(svg)
+    }
+
+    // The colors of everything drawn in the frame, color rules first since they take precedence
+    fn legend(frame: &Frame<'_>) -> Vec<(String, [u8; 3])> {
+        let mut entries = frame.canvas.color_rules.iter()
+            .map(|rule| {
+                let text = rule.to_string();
+                let condition = text.rsplit_once(" = ").map_or(text.as_str(), |(condition, _)| condition);
+                (condition.to_string(), rule.color)
+            } )
+            .collect::<Vec<(String, [u8; 3])>>();
+
+        let mut species = frame.simulation.agents().iter()
+            .filter_map(|coord| frame.simulation.try_agent(*coord).ok().map(|agent| agent.attributes.species()))
+            .collect::<Vec<u8>>();
+        species.sort_unstable();
+        species.dedup();
+
+        for species in species {
+            entries.push((format!("species {}", species),
+                frame.canvas.theme.species[species as usize % frame.canvas.theme.species.len()]));
+        }
+
+        let mut markers = frame.simulation.markers().into_iter().collect::<Vec<u8>>();
+        markers.sort_unstable();
+
+        for marker in markers {
+            entries.push((format!("lineage {}", marker),
+                frame.canvas.theme.markers[marker as usize % frame.canvas.theme.markers.len()]));
+        }
+
+        entries.push((String::from("food"), frame.canvas.theme.food));
+        if frame.simulation.food().iter().any(|coord| frame.simulation.get(*coord).toxic()) {
+            entries.push((String::from("toxic food"), frame.canvas.theme.toxic_food));
+        }
+
+        let terrain = frame.simulation.coords().into_iter()
+            .map(|coord| frame.simulation.get(coord))
+            .fold((false, false), |(walls, water), tile| {
+                (walls || matches!(tile, crate::tile::Tile::Wall), water || matches!(tile, crate::tile::Tile::Water))
+            } );
+
+        if terrain.0 {
+            entries.push((String::from("wall"), frame.canvas.theme.wall));
+        }
+
+        if terrain.1 {
+            entries.push((String::from("water"), frame.canvas.theme.water));
+        }
+
+        entries
+    }
+
+    fn label(fill: &str, x: f32, y: f32, size: f32, anchor: &str, baseline: &str, text: &str) -> String {
+        format!("<text x=\"{}\" y=\"{}\" font-size=\"{}\" fill=\"{}\" text-anchor=\"{}\" dominant-baseline=\"{}\">{}</text>",
+            x,
+            y,
+            size,
+            fill,
+            anchor,
+            baseline,
+            text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;"))
+    }
+
+    // Paints a translucent color over an opaque one
+    fn blend(base: [u8; 3], color: iced::Color) -> [u8; 3] {
+        let channel = |base: u8, channel: f32| (base as f32 * (1f32 - color.a) + channel * 255f32 * color.a).round() as u8;
+        [channel(base[0], color.r), channel(base[1], color.g), channel(base[2], color.b)]
+    }
+
+    fn hex(color: [u8; 3]) -> String {
+        format!("#{:02X}{:02X}{:02X}", color[0], color[1], color[2])
+    }
+}
@@ -0,0 +1,99 @@
+use crate::agent::Agent;
+use crate::error::GenomeError;
+use crate::interface::brain_view::BrainView;
+use crate::interface::Message;
+
+// Edits a genome by hand, checking that it can become an Agent as it's typed and previewing its brain
+// While placing is on, clicking an empty Tile on the Canvas places the Agent there
+pub(crate) struct GenomeEditor {
+    genome: String,
+    // the Agent the genome becomes, or why it can't become one
+    parsed: Result<Agent, GenomeError>,
+    pub(crate) placing: bool,
+    state_genome: iced::text_input::State,
+    state_paste: iced::button::State,
+    state_placing: iced::button::State
+}
+
+impl Default for GenomeEditor {
+    fn default() -> Self {
+        Self {
+            genome: String::new(),
+            parsed: Err(GenomeError::EmptyGenome),
+            placing: false,
+            state_genome: iced::text_input::State::default(),
+            state_paste: iced::button::State::default(),
+            state_placing: iced::button::State::default()
+        }
+    }
+}
+
+impl GenomeEditor {
+    const PADDING: u16 = 10;
+
+    pub(crate) fn genome(&self) -> &str {
+        &self.genome
+    }
+
+    // The preview draws from its own prng, so editing never disturbs the run
+    pub(crate) fn set(&mut self, genome: String) {
+        use rand::SeedableRng;
+        let mut prng = crate::simulation::Prng::seed_from_u64(0);
+
+        self.parsed = Agent::from_string(genome.clone(), &mut prng);
+        self.genome = genome;
+
+        // a genome that can't become an Agent can't be placed
+        self.placing &= self.parsed.is_ok();
+    }
+
+    pub(crate) fn view(&mut self) -> iced::Element<'_, Message> {
+        use iced::Length;
+
+        let status = match &self.parsed {
+            Ok(agent) => format!("The genome has {} genes, its brain has {} nodes and {} connections",
+                agent.genome.len(),
+                agent.brain.node_count(),
+                agent.brain.edge_count()),
+            Err(e) => format!("The genome can't become an agent, {}", e)
+        };
+
+        let mut placing = iced::Button::new(
+            &mut self.state_placing,
+            iced::Text::new(format!("Place on Click: {}", if self.placing { "On" } else { "Off" })));
+
+        if self.parsed.is_ok() {
+            placing = placing.on_press(Message::TogglePlacing);
+        }
+
+        let mut editor = iced::Column::new()
+            .push(
+                iced::TextInput::new(
+                    &mut self.state_genome,
+                    "Genes, such as 10010110 01101001",
+                    &self.genome,
+                    Message::GenomeEditorInput)
+                    .width(Length::Fill))
+            .push(
+                iced::Row::new()
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_paste,
+                            iced::Text::new("Paste"))
+                            .on_press(Message::GenomeEditorPaste))
+                    .push(placing)
+                    .width(Length::Fill)
+                    .spacing(Self::PADDING))
+            .push(
+                iced::Text::new(status)
+                    .width(Length::Fill))
+            .width(Length::Fill)
+            .spacing(Self::PADDING);
+
+        if let Ok(agent) = &self.parsed {
+            editor = editor.push(BrainView::new(agent.brain.clone()).view());
+        }
+
+        editor.into()
+    }
+}
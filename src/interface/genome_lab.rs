@@ -0,0 +1,198 @@
+use std::fmt;
+
+use rand::SeedableRng;
+
+use crate::agent::gene::{Gene, Genome};
+use crate::error::GenomeEditError;
+use crate::interface::Message;
+use crate::simulation::Prng;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Operation {
+    // the donor is appended to the genome
+    Merge,
+    // the region of the genome is replaced by the donor's region
+    Splice,
+    // the region of the genome is filled with random genes
+    Randomize
+}
+
+impl Operation {
+    fn next(self) -> Self {
+        use Operation::*;
+        match self {
+            Merge => Splice,
+            Splice => Randomize,
+            Randomize => Merge
+        }
+    }
+
+    fn uses_donor(&self) -> bool {
+        matches!(self, Operation::Merge | Operation::Splice)
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Operation::Merge => "Merge",
+            Operation::Splice => "Splice",
+            Operation::Randomize => "Randomize Region"
+        } )
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Field {
+    Genome,
+    Region,
+    Donor,
+    DonorRegion,
+    Output
+}
+
+impl Field {
+    const ALL: [Field; 5] = [
+        Field::Genome,
+        Field::Region,
+        Field::Donor,
+        Field::DonorRegion,
+        Field::Output
+    ];
+
+    fn placeholder(&self) -> &'static str {
+        match self {
+            Field::Genome => "Genome file",
+            Field::Region => "Region, such as 4..12 (whole genome if empty)",
+            Field::Donor => "Donor genome file",
+            Field::DonorRegion => "Donor region (whole genome if empty)",
+            Field::Output => "Output file"
+        }
+    }
+}
+
+// Builds designed hybrids out of genome files, such as those exported from the genome pane
+// Nothing is imported, the new genome is written to its own file
+pub(crate) struct GenomeLab {
+    pub(crate) operation: Operation,
+    // the text of each field, in the order of Field::ALL
+    values: [String; Field::ALL.len()],
+    state_operation: iced::button::State,
+    state_values: [iced::text_input::State; Field::ALL.len()],
+    state_create: iced::button::State
+}
+
+impl Default for GenomeLab {
+    fn default() -> Self {
+        Self {
+            operation: Operation::Splice,
+            values: [
+                String::from("genome.txt"),
+                String::new(),
+                String::from("donor.txt"),
+                String::new(),
+                String::from("hybrid.txt")
+            ],
+            state_operation: iced::button::State::default(),
+            state_values: Default::default(),
+            state_create: iced::button::State::default()
+        }
+    }
+}
+
+impl GenomeLab {
+    const PADDING: u16 = 10;
+
+    pub(crate) fn cycle(&mut self) {
+        self.operation = self.operation.next();
+    }
+
+    pub(crate) fn set(&mut self, field: Field, value: String) {
+        if let Some(index) = Field::ALL.iter().position(|f| *f == field) {
+            self.values[index] = value;
+        }
+    }
+
+    // Writes the new genome to the output file, returning it along with the path
+    pub(crate) fn create(&self) -> Result<(String, String), GenomeEditError> {
+        let genome = Self::read(self.value(Field::Genome))?;
+        let region = Genome::region(self.value(Field::Region), genome.len())?;
+
+        let created = match self.operation {
+            Operation::Merge => Genome::merge(genome, Self::read(self.value(Field::Donor))?),
+            Operation::Splice => {
+                let donor = Self::read(self.value(Field::Donor))?;
+                let donor_region = Genome::region(self.value(Field::DonorRegion), donor.len())?;
+                Genome::splice(genome, region, &donor[donor_region])
+            },
+            Operation::Randomize => {
+                let mut prng = Prng::seed_from_u64(rand::random());
+                Genome::randomize(genome, region, &mut prng)
+            }
+        };
+
+        let created = Genome::get(created);
+        let output = self.value(Field::Output).trim().to_string();
+        crate::platform::write(&output, &created).map_err(|e| GenomeEditError::Io(output.clone(), e))?;
+
+        Ok((created, output))
+    }
+
+    pub(crate) fn view(&mut self) -> iced::Element<'_, Message> {
+        use iced::Length;
+
+        use Message::*;
+        let mut lab = iced::Column::new()
+            .push(
+                iced::Button::new(
+                    &mut self.state_operation,
+                    iced::Text::new(format!("Operation: {}", self.operation)))
+                    .width(Length::Fill)
+                    .on_press(CycleGenomeOperation))
+            .width(Length::Fill)
+            .spacing(Self::PADDING);
+
+        for ((field, value), state) in Field::ALL.iter().zip(self.values.iter()).zip(self.state_values.iter_mut()) {
+            let field = *field;
+
+            // merges take the whole donor, and randomizing has no donor
+            let hidden = match field {
+                Field::Donor => !self.operation.uses_donor(),
+                Field::DonorRegion => self.operation != Operation::Splice,
+                _ => false
+            };
+
+            if !hidden {
+                lab = lab.push(
+                    iced::TextInput::new(
+                        state,
+                        field.placeholder(),
+                        value,
+                        move |value| GenomeLabInput(field, value))
+                        .width(Length::Fill)
+                        .on_submit(CreateGenome));
+            }
+        }
+
+        lab
+            .push(
+                iced::Button::new(
+                    &mut self.state_create,
+                    iced::Text::new("Create Genome"))
+                    .width(Length::Fill)
+                    .on_press(CreateGenome))
+            .into()
+    }
+
+    fn value(&self, field: Field) -> &str {
+        Field::ALL.iter()
+            .position(|f| *f == field)
+            .map_or("", |index| self.values[index].as_str())
+    }
+
+    fn read(path: &str) -> Result<Vec<Gene>, GenomeEditError> {
+        let path = path.trim();
+        let contents = crate::platform::read_to_string(path).map_err(|e| GenomeEditError::Io(path.to_string(), e))?;
+        Genome::from_string(contents).map_err(|e| GenomeEditError::Genome(path.to_string(), e))
+    }
+}
@@ -0,0 +1,43 @@
+use crate::agent::gene::ActionType;
+use crate::interface::Message;
+
+// Lists an Agent's recent actions as a table, newest first,
+// with the step each was taken on and whether it had any effect
+pub(crate) struct HistoryTable {
+    history: Vec<(u64, ActionType, bool)>
+}
+
+impl HistoryTable {
+    const PADDING: u16 = 4;
+
+    pub(crate) fn new(history: Vec<(u64, ActionType, bool)>) -> Self {
+        Self { history }
+    }
+
+    pub(crate) fn view(self) -> iced::Element<'static, Message> {
+        use iced::Length;
+
+        let row = |step: String, action: String, effect: &str| {
+            iced::Row::new()
+                .push(iced::Text::new(step).width(Length::FillPortion(1)))
+                .push(iced::Text::new(action).width(Length::FillPortion(2)))
+                .push(iced::Text::new(effect).width(Length::FillPortion(2)))
+                .width(Length::Fill)
+                .spacing(Self::PADDING)
+        };
+
+        let mut table = iced::Column::new()
+            .push(row(String::from("Step"), String::from("Action"), "Effect"))
+            .width(Length::Fill)
+            .spacing(Self::PADDING);
+
+        for (tick, action, successful) in self.history {
+            table = table.push(row(
+                tick.to_string(),
+                format!("{:?}", action),
+                if successful { "Succeeded" } else { "No effect" }));
+        }
+
+        table.into()
+    }
+}
@@ -0,0 +1,37 @@
+use crate::agent::library::LIBRARY;
+use crate::interface::Message;
+
+// Lists the built-in genomes, with a button to import each one into the Simulation
+#[derive(Default)]
+pub(crate) struct LibraryBrowser {
+    state_import: [iced::button::State; LIBRARY.len()]
+}
+
+impl LibraryBrowser {
+    const PADDING: u16 = 10;
+
+    pub(crate) fn view(&mut self) -> iced::Element<'_, Message> {
+        use iced::Length;
+
+        let mut browser = iced::Column::new()
+            .width(Length::Fill)
+            .spacing(Self::PADDING);
+
+        for (index, (primitive, state)) in LIBRARY.iter().zip(self.state_import.iter_mut()).enumerate() {
+            browser = browser.push(
+                iced::Row::new()
+                    .push(
+                        iced::Text::new(format!("{}\n{}", primitive.name, primitive.description))
+                            .width(Length::Fill))
+                    .push(
+                        iced::Button::new(
+                            state,
+                            iced::Text::new("Import"))
+                            .on_press(Message::ImportPrimitive(index)))
+                    .width(Length::Fill)
+                    .spacing(Self::PADDING));
+        }
+
+        browser.into()
+    }
+}
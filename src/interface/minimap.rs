@@ -0,0 +1,125 @@
+use iced::canvas;
+
+use crate::interface::theme::Theme;
+use crate::interface::viewport::Viewport;
+use crate::simulation::Simulation;
+use crate::tile::Tile;
+
+// An overview of the whole world in the bottom right corner of the Canvas, shown while zoomed in
+// The part of the world on the Canvas is outlined, and clicking the overview moves the view there
+// Large worlds are drawn with a cell for several Tiles, each cell showing its most notable occupant
+pub(crate) struct Minimap {
+    // where the overview sits on the Canvas
+    bounds: iced::Rectangle,
+    canvas: iced::Size,
+    dimensions: iced::Size<usize>
+}
+
+impl Minimap {
+    // the longer side of the overview, in pixels
+    const SIZE: f32 = 160f32;
+    // the overview never takes up more than this fraction of the Canvas' width or height
+    const MAX_FRACTION: f32 = 0.3f32;
+    const MARGIN: f32 = 10f32;
+    const OUTLINE_WIDTH: f32 = 1.5f32;
+    const BACKDROP_ALPHA: f32 = 0.85f32;
+
+    // Returns None unless the Canvas is zoomed in
+    pub(crate) fn new(canvas: iced::Size, dimensions: iced::Size<usize>, viewport: &Viewport) -> Option<Self> {
+        if viewport.zoom() <= 1f32 || dimensions.width == 0 || dimensions.height == 0 {
+            return None;
+        }
+
+        // the overview keeps the world's proportions
+        let longest = Self::SIZE
+            .min(canvas.width * Self::MAX_FRACTION)
+            .min(canvas.height * Self::MAX_FRACTION);
+        let scale = longest / dimensions.width.max(dimensions.height) as f32;
+        let size = iced::Size::new(dimensions.width as f32 * scale, dimensions.height as f32 * scale);
+
+        Some(Self {
+            bounds: iced::Rectangle::new(
+                iced::Point::new(
+                    canvas.width - size.width - Self::MARGIN,
+                    canvas.height - size.height - Self::MARGIN),
+                size),
+            canvas,
+            dimensions
+        } )
+    }
+
+    pub(crate) fn contains(&self, point: iced::Point) -> bool {
+        self.bounds.contains(point)
+    }
+
+    // The point of the world beneath a point on the overview, as a fraction of the world
+    pub(crate) fn fraction(&self, point: iced::Point) -> iced::Point {
+        iced::Point::new(
+            ((point.x - self.bounds.x) / self.bounds.width).clamp(0f32, 1f32),
+            ((point.y - self.bounds.y) / self.bounds.height).clamp(0f32, 1f32)
+        )
+    }
+
+    pub(crate) fn draw(&self, frame: &mut canvas::Frame, simulation: &Simulation, viewport: &Viewport, theme: &Theme, color: impl Fn(&Tile) -> iced::Color) {
+        let backdrop = theme.empty;
+        frame.fill_rectangle(
+            self.bounds.position(),
+            self.bounds.size(),
+            iced::Color::from_rgba8(backdrop[0], backdrop[1], backdrop[2], Self::BACKDROP_ALPHA));
+
+        // a cell is never smaller than a pixel
+        let columns = self.dimensions.width.min(self.bounds.width.ceil() as usize).max(1);
+        let rows = self.dimensions.height.min(self.bounds.height.ceil() as usize).max(1);
+
+        let mut cells = vec![None; columns * rows];
+        for coord in simulation.coords() {
+            let index = coord.y * rows / self.dimensions.height * columns + coord.x * columns / self.dimensions.width;
+            let priority = Self::priority(simulation.get(coord));
+            if cells[index].is_none_or(|(highest, _)| priority > highest) {
+                cells[index] = Some((priority, coord));
+            }
+        }
+
+        let cell = iced::Size::new(self.bounds.width / columns as f32, self.bounds.height / rows as f32);
+        for (index, occupant) in cells.into_iter().enumerate() {
+            if let Some((_, coord)) = occupant {
+                frame.fill_rectangle(
+                    iced::Point::new(
+                        self.bounds.x + (index % columns) as f32 * cell.width,
+                        self.bounds.y + (index / columns) as f32 * cell.height),
+                    cell,
+                    color(simulation.get(coord)));
+            }
+        }
+
+        // the viewport spans 1 / zoom of the world in each direction
+        let origin = viewport.origin(self.canvas);
+        let zoom = viewport.zoom();
+        frame.stroke(
+            &canvas::Path::rectangle(
+                iced::Point::new(
+                    self.bounds.x + origin.x / (self.canvas.width * zoom) * self.bounds.width,
+                    self.bounds.y + origin.y / (self.canvas.height * zoom) * self.bounds.height),
+                iced::Size::new(self.bounds.width / zoom, self.bounds.height / zoom)),
+            canvas::Stroke::default()
+                .with_width(Self::OUTLINE_WIDTH)
+                .with_color(Theme::color(theme.highlight)));
+
+        frame.stroke(
+            &canvas::Path::rectangle(self.bounds.position(), self.bounds.size()),
+            canvas::Stroke::default()
+                .with_width(Self::OUTLINE_WIDTH)
+                .with_color(Theme::color(theme.text)));
+    }
+
+    // Agents are the easiest to lose sight of in a large world, so they win their cell
+    fn priority(tile: &Tile) -> u8 {
+        use Tile::*;
+        match tile {
+            Agent(..) => 3,
+            Food(..) => 2,
+            Wall => 1,
+            Water => 0
+        }
+    }
+}
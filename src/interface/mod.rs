@@ -0,0 +1,2391 @@
+mod animation;
+mod benchmark_view;
+mod brain_view;
+mod color_rules;
+mod food_web_view;
+mod frame_export;
+mod genome_editor;
+mod genome_lab;
+mod history_table;
+mod library_view;
+mod minimap;
+mod morgue_view;
+mod navigation;
+mod playback;
+mod pulse_controls;
+mod pyramid_chart;
+mod sandbox;
+mod session;
+mod settings_panel;
+mod slow_motion;
+mod stats_chart;
+mod tabs;
+mod theme;
+mod tutorial;
+mod viewport;
+mod wall_painter;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::fmt;
+
+use iced::canvas;
+use iced::canvas::event::Status;
+
+use crate::tile;
+use crate::tile::coord;
+use crate::simulation::{MemoryUsage, Rule, Simulation, SimulationSettings, StepAction, TileSummary, WorldStats};
+
+#[derive(Debug, Clone)]
+pub(crate) enum Message {
+    // an Agent is summarized by its id, so the inspector stays bound to it as it moves
+    InspectorTarget(coord::Coord, TileSummary),
+    Navigate(navigation::Navigation),
+    TileHistoryScrub(u16),
+    InspectorPaneChange(InspectorPane),
+    InspectorCopy,
+    InspectorExport,
+    ExportPathChange(String),
+    InspectorMark,
+    ToggleFollow,
+    ImportGenome,
+    Checkpoint,
+    LoadCheckpoint,
+    ReproduceRun,
+    RunSelfTest,
+    CycleTheme,
+    SaveWorld,
+    LoadWorld,
+    SaveRecording,
+    LoadRecording,
+    ReplayScrub(u32),
+    StartTutorial,
+    StartSplitWorld,
+    EndTutorial,
+    ForkSandbox,
+    PromoteSandbox,
+    DiscardSandbox,
+    SandboxKill,
+    SandboxFeed,
+    SandboxRule(Rule),
+    ColorRuleInput(String),
+    AddColorRule,
+    RemoveColorRule(usize),
+    MorgueQuery(String),
+    Resurrect(u64),
+    ImportPrimitive(usize),
+    CycleGenomeOperation,
+    GenomeLabInput(genome_lab::Field, String),
+    GenomeEditorInput(String),
+    GenomeEditorPaste,
+    TogglePlacing,
+    CreateGenome,
+    ToggleSlowMotion,
+    CycleBrush,
+    Paint(coord::Coord, wall_painter::Stroke),
+    EndPaint,
+    Modifiers(iced::keyboard::Modifiers),
+    Zoom(iced::mouse::ScrollDelta, iced::Point),
+    PanStart(iced::Point),
+    Pan(iced::Point),
+    EndPan,
+    Hover(Option<coord::Coord>),
+    LookAt(iced::Point),
+    ResetView,
+    CyclePulse,
+    PulseDurationChange(String),
+    ApplyPulse,
+    ToggleOverlay(frame_export::Overlay),
+    FrameWidthChange(String),
+    FrameDpiChange(String),
+    ExportFrame,
+    ToggleAnimation,
+    AnimationSkipChange(String),
+    ToggleCsv,
+    AnimationScaleChange(String),
+    SettingsInput(settings_panel::Field, String),
+    ApplySettings,
+    LoadConfig,
+    Play,
+    Pause,
+    TickRate(u16),
+    StepN(u32),
+    StepBack,
+    NewTab,
+    CloseTab,
+    SwitchTab(usize),
+    Reset,
+    Step,
+}
+
+impl Message {
+    // Messages that only edit text, files or the inspector leave the world looking the same
+    fn redraws(&self) -> bool {
+        use Message::*;
+        !matches!(self,
+            InspectorPaneChange(..) | InspectorCopy | InspectorExport | ExportPathChange(..) |
+            Checkpoint | LoadCheckpoint | RunSelfTest | SaveWorld | SaveRecording | ColorRuleInput(..) | MorgueQuery(..) |
+            CycleBrush | EndPaint | Modifiers(..) | PanStart(..) | EndPan | Hover(..) | CyclePulse | PulseDurationChange(..) |
+            ToggleOverlay(..) | FrameWidthChange(..) | FrameDpiChange(..) | ExportFrame |
+            ToggleAnimation | AnimationSkipChange(..) | AnimationScaleChange(..) | ToggleCsv |
+            SettingsInput(..) | CycleGenomeOperation | GenomeLabInput(..) | CreateGenome |
+            GenomeEditorInput(..) | GenomeEditorPaste | TogglePlacing | Play | Pause | TickRate(..))
+    }
+}
+
+pub(crate) struct Interface {
+    // the Simulation in the shown tab
+    simulation: Rc<RefCell<Simulation>>,
+    tabs: tabs::Tabs,
+    // the world's geometry is kept between frames, and only rebuilt after a message that could change it
+    // while paused and untouched, no geometry is rebuilt and no timer wakes the event loop
+    canvas_cache: Rc<RefCell<canvas::Cache>>,
+    // the id of the inspected Agent, and where it was last seen
+    target: Option<u64>,
+    target_coord: Option<coord::Coord>,
+    // keeps the viewport centered on the inspected Agent
+    follow: bool,
+    // the Tile beneath the cursor, described in a tooltip
+    hover: Option<coord::Coord>,
+    // the events involving the inspected Agent, kept after it dies
+    subscription: Option<crate::event::Subscription>,
+    selection: Option<InspectorPane>,
+    selection_text: String,
+    markers: u8,
+    extinctions: usize,
+    notification: Option<String>,
+    // each checkpoint is labelled with the saved world it was loaded from, None if it was taken from the shown run
+    checkpoints: Vec<(Option<String>, WorldStats)>,
+    memory: MemoryUsage,
+    // prevents the memory warning from being raised every step
+    memory_warned: bool,
+    // the tick shown by the tile history scrubber, follows the latest step when None
+    scrubber: Option<u64>,
+    // brains are exported as SVG when the path ends in .svg, as a Rust function when it ends in .rs,
+    // and otherwise as Graphviz dot
+    // runs are also reproduced from the manifest at this path,
+    // and worlds and recordings are saved to and loaded from it
+    // frames are exported to it as PNG images when it ends in .png, and otherwise as SVG documents
+    export_path: String,
+    // the recording being replayed, scrubbing rebuilds the Simulation at the chosen tick
+    replay: Option<crate::recording::Replay>,
+    // the guided tutorial, if one is in progress
+    tutorial: Option<tutorial::Tutorial>,
+    // the primary run, set aside while a sandbox forked from it is shown
+    primary: Option<Simulation>,
+    // the world before each recent step, older steps are spilled to disk
+    snapshots: crate::snapshot::SnapshotRing,
+    // marked lineages are periodically evaluated in the benchmark arenas
+    benchmarks: crate::benchmark::Benchmarks,
+    // recognizable emergent behaviors, looked for every few steps
+    motifs: crate::motif::MotifScanner,
+    // population, fitness, births, deaths and food after every step
+    stats: crate::stats::PopulationStats,
+    // preferences that persist between launches, such as the canvas color rules
+    session: session::Session,
+    color_rule_editor: color_rules::ColorRuleEditor,
+    morgue_browser: morgue_view::MorgueBrowser,
+    library_browser: library_view::LibraryBrowser,
+    genome_lab: genome_lab::GenomeLab,
+    genome_editor: genome_editor::GenomeEditor,
+    slow_motion: slow_motion::SlowMotion,
+    wall_painter: wall_painter::WallPainter,
+    viewport: viewport::Viewport,
+    pulse_controls: pulse_controls::PulseControls,
+    frame_export: frame_export::FrameExport,
+    animation: animation::AnimationRecorder,
+    // appends the statistics of every step to a CSV file, None unless turned on
+    csv: Option<crate::stats::CsvWriter>,
+    // opened for each run whose settings name an event stream
+    event_stream: Option<crate::event::EventStream>,
+    settings_panel: settings_panel::SettingsPanel,
+    playback: playback::Playback,
+    state_pick_list: iced::pick_list::State<InspectorPane>,
+    state_copy: iced::button::State,
+    state_export: iced::button::State,
+    state_export_path: iced::text_input::State,
+    state_mark: iced::button::State,
+    state_follow: iced::button::State,
+    state_import: iced::button::State,
+    state_checkpoint: iced::button::State,
+    state_load_checkpoint: iced::button::State,
+    state_reproduce: iced::button::State,
+    state_self_test: iced::button::State,
+    state_theme: iced::button::State,
+    state_save_world: iced::button::State,
+    state_load_world: iced::button::State,
+    state_save_recording: iced::button::State,
+    state_load_recording: iced::button::State,
+    state_csv: iced::button::State,
+    state_replay_scrubber: iced::slider::State,
+    state_start_tutorial: iced::button::State,
+    state_split_world: iced::button::State,
+    state_end_tutorial: iced::button::State,
+    state_reset_view: iced::button::State,
+    sandbox_controls: sandbox::SandboxControls,
+    state_scrubber: iced::slider::State,
+    state_scrollable: iced::scrollable::State
+}
+
+impl Default for Interface {
+    fn default() -> Self {
+        let simulation = Rc::new(RefCell::new(Simulation::default()));
+        let interface = Self {
+            tabs: tabs::Tabs::new(Rc::clone(&simulation)),
+            simulation,
+            canvas_cache: Rc::new(RefCell::new(canvas::Cache::new())),
+            target: None,
+            target_coord: None,
+            follow: false,
+            hover: None,
+            subscription: None,
+            selection: Some(InspectorPane::default()),
+            selection_text: String::default(),
+            markers: 0,
+            extinctions: 0,
+            notification: None,
+            checkpoints: Vec::new(),
+            memory: MemoryUsage::default(),
+            memory_warned: false,
+            scrubber: None,
+            export_path: String::from("brain.dot"),
+            replay: None,
+            tutorial: None,
+            primary: None,
+            snapshots: crate::snapshot::SnapshotRing::default(),
+            benchmarks: crate::benchmark::Benchmarks::default(),
+            motifs: crate::motif::MotifScanner::default(),
+            stats: crate::stats::PopulationStats::default(),
+            session: session::Session::load().unwrap_or_else(|e| {
+                tracing::info!("starting a new session, {} could not be loaded: {}", session::Session::PATH, e);
+                session::Session::default()
+            } ),
+            color_rule_editor: color_rules::ColorRuleEditor::default(),
+            morgue_browser: morgue_view::MorgueBrowser::default(),
+            library_browser: library_view::LibraryBrowser::default(),
+            genome_lab: genome_lab::GenomeLab::default(),
+            genome_editor: genome_editor::GenomeEditor::default(),
+            slow_motion: slow_motion::SlowMotion::default(),
+            wall_painter: wall_painter::WallPainter::default(),
+            viewport: viewport::Viewport::default(),
+            pulse_controls: pulse_controls::PulseControls::default(),
+            frame_export: frame_export::FrameExport::default(),
+            animation: animation::AnimationRecorder::default(),
+            csv: None,
+            event_stream: None,
+            settings_panel: settings_panel::SettingsPanel::default(),
+            playback: playback::Playback::default(),
+            state_pick_list: iced::pick_list::State::default(),
+            state_copy: iced::button::State::default(),
+            state_export: iced::button::State::default(),
+            state_export_path: iced::text_input::State::default(),
+            state_mark: iced::button::State::default(),
+            state_follow: iced::button::State::default(),
+            state_import: iced::button::State::default(),
+            state_checkpoint: iced::button::State::default(),
+            state_load_checkpoint: iced::button::State::default(),
+            state_reproduce: iced::button::State::default(),
+            state_self_test: iced::button::State::default(),
+            state_theme: iced::button::State::default(),
+            state_save_world: iced::button::State::default(),
+            state_load_world: iced::button::State::default(),
+            state_save_recording: iced::button::State::default(),
+            state_load_recording: iced::button::State::default(),
+            state_csv: iced::button::State::default(),
+            state_replay_scrubber: iced::slider::State::default(),
+            state_start_tutorial: iced::button::State::default(),
+            state_split_world: iced::button::State::default(),
+            state_end_tutorial: iced::button::State::default(),
+            state_reset_view: iced::button::State::default(),
+            sandbox_controls: sandbox::SandboxControls::default(),
+            state_scrubber: iced::slider::State::default(),
+            state_scrollable: iced::scrollable::State::default()
+        };
+
+        interface.record_manifest();
+        interface
+    }
+}
+
+impl iced::Application for Interface {
+    type Executor = iced::executor::Default;
+    type Message = Message;
+    type Flags = ();
+
+    fn new(_flags: Self::Flags) -> (Self, iced::Command<Self::Message>) {
+        (Self::default(), iced::Command::none())
+    }
+
+    fn title(&self) -> String {
+        String::from("Simulating Emergent Behavior")
+    }
+
+    fn update(&mut self, message: Self::Message) -> iced::Command<Self::Message> {
+        // the tutorial reacts to messages once they have been handled
+        let tutorial_message = self.tutorial.is_some().then(|| message.clone());
+
+        if message.redraws() {
+            self.canvas_cache.borrow_mut().clear();
+        }
+
+        use Message::*;
+        match message {
+            InspectorTarget(coord, TileSummary::Agent(id)) => self.set_target(coord, id),
+            Navigate(navigation) => {
+                let current = self.target_coord.zip(self.target);
+                let next = navigation.target(&self.simulation.borrow(), current);
+                match next {
+                    Some((coord, id)) => self.set_target(coord, id),
+                    None if navigation == navigation::Navigation::Nearest && current.is_none() =>
+                        self.notification = Some(String::from("Select an agent to find its nearest neighbor")),
+                    None => {  }
+                }
+            },
+            // while placing, clicking a Tile without an Agent places the edited genome there
+            InspectorTarget(coord, _) if self.genome_editor.placing => self.place_genome(coord),
+            InspectorTarget(coord, _) => self.inspect_tile(coord),
+            TileHistoryScrub(position) => self.scrub(position as usize),
+            InspectorPaneChange(pane) => self.set_selection(pane),
+            InspectorCopy => Self::copy(self.selection_text.clone()),
+            InspectorExport => self.export(),
+            ExportPathChange(path) => self.export_path = path,
+            InspectorMark => self.mark_target(),
+            ToggleFollow => {
+                self.follow = !self.follow;
+                if self.follow {
+                    if self.viewport.zoom() < viewport::Viewport::FOLLOW_ZOOM {
+                        self.viewport.zoom_to(viewport::Viewport::FOLLOW_ZOOM);
+                    }
+
+                    self.follow_target();
+                }
+            },
+            ImportGenome => self.import_genome(),
+            ReproduceRun => self.reproduce_run(),
+            CycleTheme => {
+                self.session.theme = self.session.theme.next();
+                self.save_session();
+            },
+            RunSelfTest => {
+                // the suite runs its own worlds, so the shown run is left untouched
+                let report = crate::selftest::run();
+                tracing::info!("{}", report);
+                self.notification = Some(report.to_string());
+            },
+            SaveWorld => {
+                let saved = self.simulation.borrow().save_to(&self.export_path);
+                self.notification = Some(match saved {
+                    Ok(..) => format!("Saved the world to {}", self.export_path),
+                    Err(e) => format!("Failed to save the world: {}", e)
+                } );
+            },
+            LoadWorld => match Simulation::load_from(&self.export_path) {
+                Ok(simulation) => {
+                    self.restart(simulation);
+                    self.replay = None;
+                    self.notification = Some(format!("Loaded the world from {}", self.export_path));
+                },
+                Err(e) => self.notification = Some(format!("Failed to load the world: {}", e))
+            },
+            SaveRecording => {
+                let recording = self.simulation.borrow().record();
+                self.notification = Some(match recording.save(&self.export_path) {
+                    Ok(..) => format!("Saved the recording to {}", self.export_path),
+                    Err(e) => format!("Failed to save the recording: {}", e)
+                } );
+            },
+            LoadRecording => self.load_recording(),
+            ReplayScrub(tick) => self.replay_to(tick as u64),
+            StartTutorial => self.start_tutorial(),
+            StartSplitWorld => self.start_split_world(),
+            EndTutorial => self.tutorial = None,
+            ForkSandbox => {
+                let fork = self.simulation.borrow().clone();
+                self.primary = Some(std::mem::replace(&mut *self.simulation.borrow_mut(), fork));
+                self.notification = Some(format!("Forked a sandbox at step {}", self.simulation.borrow().tick()));
+            },
+            PromoteSandbox => {
+                self.primary = None;
+                self.notification = Some(String::from("The sandbox replaced the primary run"));
+            },
+            DiscardSandbox => {
+                if let Some(primary) = self.primary.take() {
+                    self.restart(primary);
+                    self.notification = Some(String::from("Discarded the sandbox, the primary run was restored"));
+                }
+            },
+            SandboxKill => self.sandbox_kill(),
+            SandboxFeed => self.sandbox_feed(),
+            SandboxRule(rule) => self.simulation.borrow_mut().change_rule(rule),
+            ColorRuleInput(rule) => self.color_rule_editor.input = rule,
+            AddColorRule => match self.color_rule_editor.input.parse::<color_rules::ColorRule>() {
+                Ok(rule) => {
+                    self.session.color_rules.push(rule);
+                    self.color_rule_editor.input.clear();
+                    self.save_session();
+                },
+                Err(e) => self.notification = Some(format!("Failed to add the color rule: {}", e))
+            },
+            RemoveColorRule(index) => {
+                if index < self.session.color_rules.len() {
+                    self.session.color_rules.remove(index);
+                    self.save_session();
+                }
+            },
+            MorgueQuery(query) => self.morgue_browser.query = query,
+            Resurrect(id) => {
+                let resurrected = self.simulation.borrow_mut().resurrect(id);
+                self.notification = Some(match resurrected {
+                    Some(imported) => Self::describe_import(imported),
+                    None => format!("Agent {} is no longer in the morgue", id)
+                } );
+            },
+            ImportPrimitive(index) => {
+                let primitive = crate::agent::library::LIBRARY[index];
+                let imported = self.simulation.borrow_mut().import(primitive.genome.to_string());
+                self.notification = Some(format!("{}: {}", primitive.name, Self::describe_import(imported)));
+            },
+            Checkpoint => {
+                let checkpoint = self.simulation.borrow().statistics();
+                self.checkpoints.push((None, checkpoint));
+
+                if let Some(InspectorPane::Checkpoints) = self.selection {
+                    self.update_selection_text();
+                }
+            },
+            LoadCheckpoint => self.load_checkpoint(),
+            ToggleSlowMotion => self.slow_motion.toggle(),
+            Play => self.playback.playing = true,
+            Pause => self.playback.playing = false,
+            Reset => self.reset(),
+            TickRate(rate) => self.playback.rate = rate,
+            CycleBrush => self.wall_painter.cycle(),
+            Paint(coord, stroke) => self.paint(coord, stroke),
+            EndPaint => {
+                self.wall_painter.stroke = None;
+                self.wall_painter.last = None;
+            },
+            Modifiers(modifiers) => self.wall_painter.shift = modifiers.shift(),
+            Zoom(delta, cursor) => self.viewport.scroll(delta, cursor),
+            PanStart(cursor) => {
+                // taking the view by hand stops following
+                self.follow = false;
+                self.viewport.start_pan(cursor);
+            },
+            Pan(cursor) => self.viewport.pan(cursor),
+            EndPan => self.viewport.end_pan(),
+            Hover(coord) => self.hover = coord,
+            LookAt(point) => {
+                self.follow = false;
+                self.viewport.look_at(point);
+            },
+            ResetView => self.viewport = viewport::Viewport::default(),
+            CyclePulse => self.pulse_controls.cycle(),
+            PulseDurationChange(duration) => self.pulse_controls.duration = duration,
+            ApplyPulse => match self.pulse_controls.duration.trim().parse::<u64>() {
+                Ok(duration) => {
+                    let modifier = self.pulse_controls.modifier;
+                    self.simulation.borrow_mut().pulse(modifier, duration);
+                    self.notification = Some(format!("Applied a {} pulse for {} steps", modifier, duration));
+                },
+                Err(..) => self.notification = Some(String::from("A pulse lasts a whole number of steps"))
+            },
+            ToggleOverlay(overlay) => self.frame_export.toggle(overlay),
+            FrameWidthChange(width) => self.frame_export.width = width,
+            FrameDpiChange(dpi) => self.frame_export.dpi = dpi,
+            ExportFrame => self.export_frame(),
+            ToggleAnimation => self.toggle_animation(),
+            ToggleCsv => self.toggle_csv(),
+            AnimationSkipChange(skip) => self.animation.skip = skip,
+            AnimationScaleChange(scale) => self.animation.scale = scale,
+            SettingsInput(field, value) => self.settings_panel.set(field, value),
+            LoadConfig => self.load_config(),
+            CycleGenomeOperation => self.genome_lab.cycle(),
+            GenomeLabInput(field, value) => self.genome_lab.set(field, value),
+            GenomeEditorInput(genome) => self.genome_editor.set(genome),
+            GenomeEditorPaste => {
+                if let Some(genome) = Self::paste() {
+                    self.genome_editor.set(genome);
+                }
+            },
+            TogglePlacing => self.genome_editor.placing = !self.genome_editor.placing,
+            CreateGenome => self.create_genome(),
+            ApplySettings => self.apply_settings(),
+            Step => {
+                // in slow motion, the Simulation waits until every action of the latest step has been shown
+                if self.slow_motion.enabled && self.slow_motion.advance() {
+                    return iced::Command::none();
+                }
+
+                self.step();
+            },
+            // the actions of any step being played back in slow motion are skipped
+            StepN(steps) => for _ in 0..steps {
+                if !self.step() {
+                    break;
+                }
+            },
+            StepBack => self.step_back(),
+            // the sandbox's primary run would be lost along with the shown run
+            NewTab | CloseTab | SwitchTab(..) if self.primary.is_some() => {
+                self.notification = Some(String::from("Promote or discard the sandbox before changing tabs"));
+            },
+            NewTab => self.new_tab(),
+            CloseTab => {
+                if let Some(simulation) = self.tabs.close() {
+                    self.show_tab(simulation);
+                }
+            },
+            SwitchTab(index) => {
+                if let Some(simulation) = self.tabs.switch(index) {
+                    self.show_tab(simulation);
+                }
+            }
+        }
+
+        if let (Some(tutorial), Some(message)) = (&mut self.tutorial, tutorial_message) {
+            tutorial.advance(&message, self.selection, self.simulation.borrow().tick());
+        }
+
+        iced::Command::none()
+    }
+
+    // While playing, the Simulation steps on a timer
+    fn subscription(&self) -> iced::Subscription<Self::Message> {
+        if !self.playback.playing {
+            return iced::Subscription::none();
+        }
+
+        iced::time::every(self.playback.interval()).map(|_| Message::Step)
+    }
+
+    fn background_color(&self) -> iced::Color {
+        theme::Theme::color(self.session.theme.background)
+    }
+
+    fn view(&mut self) -> iced::Element<'_, Self::Message> {
+        use iced::Length;
+
+        let canvas = InterfaceCanvas::new(
+            Rc::clone(&self.simulation),
+            Rc::clone(&self.canvas_cache),
+            self.session.color_rules.clone(),
+            self.slow_motion.annotation(),
+            self.wall_painter.painting(),
+            self.viewport,
+            self.target_coord)
+            .hovering(self.hover)
+            .themed(self.session.theme.clone())
+            .view();
+
+        let theme = self.session.theme.clone();
+
+        // TODO: Move this into its own struct
+        let (toolbar, inspector) = self.controls();
+
+        let content = iced::Row::new()
+            .push(
+                iced::Column::new()
+                    .push(toolbar)
+                    .push(canvas)
+                    .width(Length::FillPortion(2u16))
+                    .height(Length::Fill)
+                    .spacing(Self::PADDING))
+            .push(inspector)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .padding(iced::Padding::new(Self::PADDING))
+            .spacing(Self::PADDING);
+
+        iced::Container::new(content)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .style(theme)
+            .into()
+
+    }
+}
+
+impl Interface {
+    const PADDING: u16 = 10;
+
+    // the event feed covers this many of the most recent steps
+    const EVENT_FEED_STEPS: u64 = 10;
+
+    // Builds the toolbar above the Canvas and the inspector beside it
+    // Both borrow the Interface's widget states, so they can't be built by separate methods
+    fn controls(&mut self) -> (iced::Element<'_, Message>, iced::Element<'_, Message>) {
+        use iced::Length;
+
+        // copied before the widget states are borrowed
+        let target = self.target_agent();
+
+        use Message::*;
+        let toolbar = iced::Column::new()
+            .push(self.tabs.view())
+            .push(
+                iced::Row::new()
+                    .push(self.playback.view())
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_reset_view,
+                            iced::Text::new(format!("Reset View ({:.1}x)", self.viewport.zoom())))
+                            .on_press(ResetView))
+                    .width(Length::Fill)
+                    .spacing(Self::PADDING))
+            .width(Length::Fill)
+            .spacing(Self::PADDING);
+
+        let mut inspector = iced::Column::new();
+        if let Some(tutorial) = &self.tutorial {
+            inspector = inspector
+                .push(
+                    iced::Text::new(tutorial.instructions(self.simulation.borrow().tick()))
+                        .width(Length::Fill))
+                .push(
+                    iced::Button::new(
+                        &mut self.state_end_tutorial,
+                        iced::Text::new(if tutorial.finished() { "Close Tutorial" } else { "Skip Tutorial" }))
+                        .width(Length::Fill)
+                        .on_press(EndTutorial));
+        }
+
+        if let Some(notification) = &self.notification {
+            inspector = inspector.push(
+                iced::Text::new(notification)
+                    .width(Length::Fill));
+        }
+
+        inspector = inspector.push(
+            iced::PickList::new(
+                &mut self.state_pick_list,
+                &InspectorPane::ALL[..],
+                self.selection,
+                InspectorPaneChange)
+                .width(Length::Fill));
+
+        if let Some(InspectorPane::TileHistory) = self.selection {
+            if let Some(watched) = self.simulation.borrow().watched() {
+                let last = watched.records.len().saturating_sub(1) as u16;
+                let position = match self.scrubber {
+                    Some(tick) => watched.records.iter()
+                        .position(|(t, _)| *t == tick)
+                        .unwrap_or(0) as u16,
+                    None => last
+                };
+
+                inspector = inspector.push(
+                    iced::Slider::new(
+                        &mut self.state_scrubber,
+                        0..=last,
+                        position,
+                        TileHistoryScrub)
+                        .width(Length::Fill));
+            }
+        }
+
+        if let Some(recording) = self.replay.as_ref().map(|replay| &replay.recording) {
+            let tick = self.simulation.borrow().tick().min(recording.ticks) as u32;
+            inspector = inspector.push(
+                iced::Slider::new(
+                    &mut self.state_replay_scrubber,
+                    0..=recording.ticks as u32,
+                    tick,
+                    ReplayScrub)
+                    .width(Length::Fill));
+        }
+
+        if let (Some(InspectorPane::History), Some(target)) = (self.selection, target.as_ref()) {
+            inspector = inspector.push(history_table::HistoryTable::new(target.history.clone()).view());
+        }
+
+        if let (Some(InspectorPane::Brain), Some(target)) = (self.selection, target) {
+            // the followed Agent records its brain's activity as it steps
+            let activations = if self.follow { target.activations() } else { None };
+            inspector = inspector.push(brain_view::BrainView::new(target.brain).activations(activations).view());
+        }
+
+        if let Some(InspectorPane::FoodWeb) = self.selection {
+            let simulation = self.simulation.borrow();
+            inspector = inspector.push(food_web_view::FoodWebView::new(
+                simulation.interactions().agents(),
+                simulation.interactions().edges()).view());
+        }
+
+        if let Some(InspectorPane::Benchmarks) = self.selection {
+            let bands = self.simulation.borrow().pulses().all().iter()
+                .map(|pulse| (pulse.start, pulse.end))
+                .collect();
+
+            inspector = inspector.push(benchmark_view::BenchmarkView::new(self.benchmarks.series(), bands, self.session.theme.species, self.session.theme.markers).view());
+        }
+
+        if let Some(InspectorPane::Chart) = self.selection {
+            let series = crate::stats::Series::ALL.iter()
+                .map(|series| (*series, self.stats.series(*series)))
+                .collect();
+            let bands = self.simulation.borrow().pulses().all().iter()
+                .map(|pulse| (pulse.start, pulse.end))
+                .collect();
+
+            inspector = inspector
+                .push(stats_chart::StatsChart::new(series, bands).view())
+                .push(pyramid_chart::PyramidChart::new(self.stats.pyramid().cloned()).view());
+        }
+
+        if let Some(InspectorPane::ColorRules) = self.selection {
+            inspector = inspector.push(self.color_rule_editor.view(&self.session.color_rules));
+        }
+
+        if let Some(InspectorPane::Morgue) = self.selection {
+            inspector = inspector.push(self.morgue_browser.view(self.simulation.borrow().morgue()));
+        }
+
+        if let Some(InspectorPane::Library) = self.selection {
+            inspector = inspector.push(self.library_browser.view());
+        }
+
+        if let Some(InspectorPane::GenomeLab) = self.selection {
+            inspector = inspector.push(self.genome_lab.view());
+        }
+
+        if let Some(InspectorPane::GenomeEditor) = self.selection {
+            inspector = inspector.push(self.genome_editor.view());
+        }
+
+        if let Some(InspectorPane::Settings) = self.selection {
+            inspector = inspector.push(self.settings_panel.view());
+        }
+
+        let season = {
+            let simulation = self.simulation.borrow();
+            let season = match simulation.season() {
+                Some(season) => format!("{}, food abundance {:.2}\n", season, simulation.abundance()),
+                None => String::new()
+            };
+
+            match simulation.weather() {
+                Some(severity) => format!("{}{} weather, severity {:.2}\n", season, crate::weather::Weather::name(severity), severity),
+                None => season
+            }
+        };
+
+        let inspector = inspector
+            .push(
+                iced::Scrollable::new(&mut self.state_scrollable)
+                    .push(
+                        iced::Text::new(&self.selection_text)
+                            .width(Length::Fill)
+                            .height(Length::Shrink))
+                    .push(
+                        iced::TextInput::new(
+                            &mut self.state_export_path,
+                            "Path",
+                            &self.export_path,
+                            ExportPathChange)
+                            .width(Length::Fill)
+                            .on_submit(InspectorExport))
+                    .push(
+                        iced::Row::new()
+                            .push(
+                                iced::Button::new(
+                                    &mut self.state_copy,
+                                    iced::Text::new("Copy"))
+                                    .width(Length::Fill)
+                                    .on_press(InspectorCopy))
+                            .push(
+                                iced::Button::new(
+                                    &mut self.state_export,
+                                    iced::Text::new("Export"))
+                                    .width(Length::Fill)
+                                    .on_press(InspectorExport))
+                            .width(Length::Fill)
+                            .spacing(Self::PADDING))
+                    .push(
+                        iced::Row::new()
+                            .push(
+                                iced::Button::new(
+                                    &mut self.state_mark,
+                                    iced::Text::new("Mark Lineage"))
+                                    .width(Length::Fill)
+                                    .on_press(InspectorMark))
+                            .push(
+                                iced::Button::new(
+                                    &mut self.state_follow,
+                                    iced::Text::new(if self.follow { "Follow: On" } else { "Follow: Off" }))
+                                    .width(Length::Fill)
+                                    .on_press(ToggleFollow))
+                            .width(Length::Fill)
+                            .spacing(Self::PADDING))
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_import,
+                            iced::Text::new("Import Genome"))
+                            .width(Length::Fill)
+                            .on_press(ImportGenome))
+                    .push(
+                        iced::Text::new(format!("{}{}", season, self.memory))
+                            .width(Length::Fill))
+                    .push(
+                        iced::Row::new()
+                            .push(
+                                iced::Button::new(
+                                    &mut self.state_checkpoint,
+                                    iced::Text::new("Checkpoint World Statistics"))
+                                    .width(Length::Fill)
+                                    .on_press(Checkpoint))
+                            .push(
+                                iced::Button::new(
+                                    &mut self.state_load_checkpoint,
+                                    iced::Text::new("Load Checkpoint"))
+                                    .width(Length::Fill)
+                                    .on_press(LoadCheckpoint))
+                            .width(Length::Fill)
+                            .spacing(Self::PADDING))
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_reproduce,
+                            iced::Text::new("Reproduce Run"))
+                            .width(Length::Fill)
+                            .on_press(ReproduceRun))
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_self_test,
+                            iced::Text::new("Run Self-Test"))
+                            .width(Length::Fill)
+                            .on_press(RunSelfTest))
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_theme,
+                            iced::Text::new(format!("Theme: {}", self.session.theme.name)))
+                            .width(Length::Fill)
+                            .on_press(CycleTheme))
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_split_world,
+                            iced::Text::new("Start Split World Experiment"))
+                            .width(Length::Fill)
+                            .on_press(StartSplitWorld))
+                    .push(
+                        iced::Row::new()
+                            .push(
+                                iced::Button::new(
+                                    &mut self.state_save_world,
+                                    iced::Text::new("Save World"))
+                                    .width(Length::Fill)
+                                    .on_press(SaveWorld))
+                            .push(
+                                iced::Button::new(
+                                    &mut self.state_load_world,
+                                    iced::Text::new("Load World"))
+                                    .width(Length::Fill)
+                                    .on_press(LoadWorld))
+                            .width(Length::Fill)
+                            .spacing(Self::PADDING))
+                    .push(
+                        iced::Row::new()
+                            .push(
+                                iced::Button::new(
+                                    &mut self.state_save_recording,
+                                    iced::Text::new("Save Recording"))
+                                    .width(Length::Fill)
+                                    .on_press(SaveRecording))
+                            .push(
+                                iced::Button::new(
+                                    &mut self.state_load_recording,
+                                    iced::Text::new("Load Recording"))
+                                    .width(Length::Fill)
+                                    .on_press(LoadRecording))
+                            .width(Length::Fill)
+                            .spacing(Self::PADDING))
+                    .push(self.slow_motion.view())
+                    .push(self.wall_painter.view())
+                    .push(self.pulse_controls.view(self.simulation.borrow().pulses(), self.simulation.borrow().tick()))
+                    .push(self.frame_export.view())
+                    .push(self.animation.view())
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_csv,
+                            iced::Text::new(if self.csv.is_some() { "CSV Log: On" } else { "CSV Log: Off" }))
+                            .width(Length::Fill)
+                            .on_press(ToggleCsv))
+                    .push(self.sandbox_controls.view(self.primary.is_some(), self.simulation.borrow().settings()))
+                    .push(
+                        // the button is shown at the top of the inspector while the tutorial runs
+                        match self.tutorial {
+                            Some(..) => iced::Column::new(),
+                            None => iced::Column::new().push(
+                                iced::Button::new(
+                                    &mut self.state_start_tutorial,
+                                    iced::Text::new("Start Tutorial"))
+                                    .width(Length::Fill)
+                                    .on_press(StartTutorial))
+                        } )
+                    .width(Length::Fill)
+                    .height(Length::Shrink)
+                    .spacing(Self::PADDING))
+            .width(Length::FillPortion(1u16))
+            .height(Length::Shrink)
+            .spacing(Self::PADDING);
+
+        (toolbar.into(), inspector.into())
+    }
+
+    // Steps the Simulation and everything that follows it
+    // Returns false if the Simulation was in use and couldn't step
+    fn step(&mut self) -> bool {
+        match self.simulation.try_borrow_mut() {
+            Ok(mut simulation) => {
+                // a failed spill only shortens how far back the run can be stepped
+                if let Err(e) = self.snapshots.push(&simulation, self.playback.playing) {
+                    tracing::warn!("failed to spill a snapshot to disk: {}", e);
+                }
+
+                simulation.step();
+            },
+            Err(e) => {
+                tracing::warn!("skipped a step, the simulation is in use: {}", e);
+                return false;
+            }
+        }
+
+        // the feed is read before the target is dropped, so it catches the Agent's death
+        if let Some(subscription) = self.subscription.as_mut() {
+            let simulation = self.simulation.borrow();
+            subscription.poll(simulation.events_since(subscription.next()), simulation.tick());
+        }
+
+        self.track_target();
+        self.check_extinctions();
+        self.check_memory();
+        self.benchmarks.poll(&self.simulation.borrow());
+        self.motifs.poll(&self.simulation.borrow());
+        self.stats.poll(&self.simulation.borrow());
+
+        let written = match (self.csv.as_mut(), self.stats.latest()) {
+            (Some(csv), Some(sample)) => csv.write(sample),
+            _ => Ok(())
+        };
+
+        if let Err(e) = written {
+            tracing::error!("failed to write statistics to {}: {}", self.export_path, e);
+            self.notification = Some(format!("Stopped writing statistics: {}", e));
+            self.csv = None;
+        }
+
+        let streamed = match self.event_stream.as_mut() {
+            Some(stream) => stream.write(&self.simulation.borrow()),
+            None => Ok(())
+        };
+
+        if let Err(e) = streamed {
+            tracing::error!("failed to write to the event stream: {}", e);
+            self.notification = Some(format!("Stopped streaming events: {}", e));
+            self.event_stream = None;
+        }
+
+        if self.animation.recording() {
+            let canvas = self.offscreen_canvas();
+            let room = self.animation.capture(frame_export::Frame {
+                canvas: &canvas,
+                simulation: &self.simulation.borrow(),
+                target: self.target_coord
+            } );
+
+            if !room {
+                self.finish_animation();
+            }
+        }
+
+        if self.slow_motion.enabled {
+            self.slow_motion.begin(self.simulation.borrow().actions());
+        }
+
+        // these panes change every step, regardless of the target
+        if let Some(InspectorPane::Archive | InspectorPane::Log | InspectorPane::TileHistory | InspectorPane::FoodWeb | InspectorPane::Events | InspectorPane::AgentEvents | InspectorPane::Energy | InspectorPane::Benchmarks | InspectorPane::Motifs | InspectorPane::Chart | InspectorPane::SplitWorld) = self.selection {
+            self.update_selection_text();
+        }
+
+        true
+    }
+
+    // Restores the world as it was before the latest step that still has a snapshot
+    // Once memory runs out, the snapshots spilled to disk are further apart
+    fn step_back(&mut self) {
+        match self.snapshots.pop() {
+            Ok(Some(simulation)) => {
+                let tick = simulation.tick();
+                *self.simulation.borrow_mut() = simulation;
+
+                // the scrubbed tick may be later than the restored world
+                self.scrubber = None;
+                self.track_target();
+                self.update_selection_text();
+
+                self.notification = Some(match self.snapshots.horizon() {
+                    Some(horizon) => format!("Stepped back to step {}, the run can be stepped back to step {}", tick, horizon),
+                    None => format!("Stepped back to step {}, no earlier steps are kept", tick)
+                } );
+            },
+            Ok(None) => self.notification = Some(String::from("No earlier steps are kept")),
+            Err(e) => self.notification = Some(format!("Failed to step back: {}", e))
+        }
+    }
+
+    // Rebuilds the Simulation from its current settings and seed
+    fn reset(&mut self) {
+        let settings = self.simulation.borrow().settings().clone();
+        self.restart(Simulation::new(settings));
+        self.replay = None;
+        self.record_manifest();
+
+        self.notification = Some(String::from("Reset the world"));
+    }
+
+    // Opens a tab with a new world, built from the settings pane like Apply does
+    fn new_tab(&mut self) {
+        let settings = self.settings_panel.settings(self.simulation.borrow().settings());
+        match settings {
+            Ok(settings) => {
+                let simulation = self.tabs.open(Simulation::new(settings));
+                self.show_tab(simulation);
+                self.record_manifest();
+
+                self.notification = Some(String::from("Opened the settings in a new tab"));
+            },
+            Err(e) => self.notification = Some(format!("Failed to open a tab: {}", e))
+        }
+    }
+
+    // Shows another tab's Simulation
+    // The inspector, charts and snapshots belong to the shown run, so they start over
+    fn show_tab(&mut self, simulation: Rc<RefCell<Simulation>>) {
+        self.simulation = simulation;
+        self.forget_run();
+        self.replay = None;
+        self.viewport = viewport::Viewport::default();
+
+        self.settings_panel.load(self.simulation.borrow().settings());
+    }
+
+    // Writes a designed genome to a file, warning if it couldn't become an Agent
+    fn create_genome(&mut self) {
+        self.notification = Some(match self.genome_lab.create() {
+            Ok((genome, path)) => {
+                // the check draws from its own prng, so the run is left untouched
+                use rand::SeedableRng;
+                let mut prng = crate::simulation::Prng::seed_from_u64(rand::random());
+                match crate::agent::Agent::from_string(genome, &mut prng) {
+                    Ok(..) => format!("Wrote the genome to {}", path),
+                    Err(e) => format!("Wrote the genome to {}, but it can't become an agent: {}", path, e)
+                }
+            },
+            Err(e) => format!("Failed to create the genome: {}", e)
+        } );
+    }
+
+    // Replaces the run with a fresh one built from the settings pane
+    fn apply_settings(&mut self) {
+        let settings = self.settings_panel.settings(self.simulation.borrow().settings());
+        match settings {
+            Ok(settings) => {
+                self.restart(Simulation::new(settings));
+                self.replay = None;
+                self.record_manifest();
+
+                // the pane shows the seed that was drawn, if it was left empty
+                self.settings_panel.load(self.simulation.borrow().settings());
+                self.notification = Some(String::from("Applied the settings to a new world"));
+            },
+            Err(e) => self.notification = Some(format!("Failed to apply the settings: {}", e))
+        }
+    }
+
+    // Adds the statistics of the world saved at the export path to the checkpoints, leaving the shown run as it is
+    fn load_checkpoint(&mut self) {
+        match Simulation::load_from(&self.export_path) {
+            Ok(simulation) => {
+                self.checkpoints.push((Some(self.export_path.clone()), simulation.statistics()));
+                self.notification = Some(format!("Loaded a checkpoint at step {} from {}", simulation.tick(), self.export_path));
+
+                if let Some(InspectorPane::Checkpoints) = self.selection {
+                    self.update_selection_text();
+                }
+            },
+            Err(e) => self.notification = Some(format!("Failed to load a checkpoint from {}: {}", self.export_path, e))
+        }
+    }
+
+    // Replaces the run with a fresh one built from the configuration file at the export path
+    fn load_config(&mut self) {
+        match SimulationSettings::from_path(&self.export_path) {
+            Ok(settings) => {
+                self.restart(Simulation::new(settings));
+                self.replay = None;
+                self.record_manifest();
+
+                self.settings_panel.load(self.simulation.borrow().settings());
+                self.notification = Some(format!("Started a new world from the settings in {}", self.export_path));
+            },
+            Err(e) => self.notification = Some(format!("Failed to load the settings in {}: {}", self.export_path, e))
+        }
+    }
+
+    // Kills the Agent on the most recently selected Tile
+    fn sandbox_kill(&mut self) {
+        let coord = self.simulation.borrow().watched().map(|watched| watched.coord);
+        let killed = match coord {
+            Some(coord) => self.simulation.borrow_mut().remove_agent(coord),
+            None => Err(crate::error::AccessError::NoAgent)
+        };
+
+        match killed {
+            Ok(..) => {
+                self.target = None;
+                self.target_coord = None;
+                self.follow = false;
+            },
+            Err(crate::error::AccessError::NoAgent) => self.notification = Some(String::from("Select an agent to kill it")),
+            Err(e) => self.notification = Some(format!("Failed to kill the agent: {}", e))
+        }
+    }
+
+    // Places a unit of food on the most recently selected Tile
+    fn sandbox_feed(&mut self) {
+        let coord = self.simulation.borrow().watched().map(|watched| watched.coord);
+        let placed = match coord {
+            Some(coord) => self.simulation.borrow_mut().place_food(coord),
+            None => false
+        };
+
+        if !placed {
+            self.notification = Some(String::from("Select an empty tile, or one with food, to add food to it"));
+        }
+    }
+
+    fn set_target(&mut self, coord: coord::Coord, id: u64) {
+        self.target = Some(id);
+        self.target_coord = Some(coord);
+
+        // re-selecting the same Agent keeps its feed
+        if self.subscription.as_ref().is_none_or(|subscription| subscription.agent != id) {
+            self.subscription = Some(crate::event::Subscription::new(id, self.simulation.borrow().tick()));
+        }
+        self.follow_target();
+
+        self.watch_tile(coord);
+    }
+
+    // Finds where the inspected Agent moved during the latest step
+    fn track_target(&mut self) {
+        let id = match self.target {
+            Some(id) => id,
+            None => return
+        };
+
+        let located = self.simulation.borrow().locate(id, self.target_coord);
+        match located {
+            Some(coord) => {
+                self.target_coord = Some(coord);
+                self.follow_target();
+
+                if let Some(InspectorPane::Genome | InspectorPane::Brain | InspectorPane::History | InspectorPane::Stats) = self.selection {
+                    self.update_selection_text();
+                }
+            },
+            None => {
+                self.target = None;
+                self.target_coord = None;
+                self.follow = false;
+                self.notification = Some(format!("Agent {} has died", id));
+            }
+        }
+    }
+
+    fn follow_target(&mut self) {
+        if let (true, Some(coord)) = (self.follow, self.target_coord) {
+            let dimensions = self.simulation.borrow().size();
+            self.viewport.center_on(coord, dimensions);
+
+            // recording starts with the next step, so the brain pane can show what drove each action
+            if let Ok(agent) = self.simulation.borrow().try_agent(coord) {
+                agent.record_activations();
+            }
+        }
+    }
+
+    // A copy of the inspected Agent as it is now
+    fn target_agent(&self) -> Option<crate::agent::Agent> {
+        let simulation = self.simulation.borrow();
+        let agent = simulation.try_agent(self.target_coord?).ok()?;
+        (Some(agent.id) == self.target).then(|| agent.clone())
+    }
+
+    // Begins recording the Tile's history, the scrubber resets to the latest step
+    // Inspects a Tile without an Agent, which unbinds the inspector from any Agent
+    fn inspect_tile(&mut self, coord: coord::Coord) {
+        self.target = None;
+        self.target_coord = None;
+        self.follow = false;
+
+        self.watch_tile(coord);
+    }
+
+    fn watch_tile(&mut self, coord: coord::Coord) {
+        self.simulation.borrow_mut().watch(coord);
+        self.scrubber = None;
+
+        self.update_selection_text();
+    }
+
+    fn scrub(&mut self, position: usize) {
+        // scrubbing to the end resumes following the latest step
+        self.scrubber = match self.simulation.borrow().watched() {
+            Some(watched) if position + 1 < watched.records.len() => {
+                watched.records.get(position).map(|(tick, _)| *tick)
+            },
+            _ => None
+        };
+
+        self.update_selection_text();
+    }
+
+    // Writes to the export path
+    // The food web pane exports the interaction network as GraphML,
+    // the genome pane exports the targeted Agent's genome,
+    // and every other pane exports the targeted Agent's brain
+    fn export(&mut self) {
+        let (subject, contents) = if let Some(InspectorPane::FoodWeb) = self.selection {
+            ("food web", self.simulation.borrow().interactions().graphml())
+        } else if let (Some(InspectorPane::Genome), Some(target)) = (self.selection, self.target_agent()) {
+            ("genome", crate::agent::gene::Genome::get(target.genome))
+        } else {
+            let target = match self.target_agent() {
+                Some(target) => target,
+                None => {
+                    self.notification = Some(String::from("Select an agent to export its brain"));
+                    return;
+                }
+            };
+
+            let extension = std::path::Path::new(&self.export_path).extension()
+                .and_then(|extension| extension.to_str())
+                .map(|extension| extension.to_ascii_lowercase());
+
+            match extension.as_deref() {
+                Some("svg") => ("brain", brain_view::BrainView::new(target.brain).svg()),
+                Some("rs") => ("controller", target.controller(self.simulation.borrow().settings().hysteresis())),
+                _ => ("brain", format!("{}", petgraph::dot::Dot::new(&target.brain)))
+            }
+        };
+
+        self.notification = Some(match crate::platform::write(&self.export_path, contents) {
+            Ok(..) => format!("Exported the {} to {}", subject, self.export_path),
+            Err(e) => {
+                tracing::error!("failed to export to {}: {}", self.export_path, e);
+                format!("Failed to export the {}: {}", subject, e)
+            }
+        } );
+    }
+
+    // Applies a stroke of the wall painter to a single Tile
+    // Tiles that can't take the stroke are skipped, so a stroke can cross them
+    fn paint(&mut self, coord: coord::Coord, stroke: wall_painter::Stroke) {
+        // each Tile is painted once per stroke, however often the cursor moves across it
+        if self.wall_painter.stroke.is_some() && self.wall_painter.last == Some(coord) {
+            return;
+        }
+
+        use wall_painter::Stroke::*;
+        if stroke != Spawn {
+            self.wall_painter.stroke = Some(stroke);
+            self.wall_painter.last = Some(coord);
+        }
+
+        let mut simulation = self.simulation.borrow_mut();
+        match stroke {
+            Build => {
+                simulation.build_wall(coord);
+            },
+            Erase => {
+                simulation.erase_wall(coord);
+            },
+            Feed => {
+                simulation.place_food(coord);
+            },
+            Clear => {
+                simulation.clear_tile(coord);
+            },
+            Spawn => match simulation.spawn_agent(coord) {
+                Ok(true) => self.notification = Some(format!("Spawned an agent at ({}, {})", coord.x, coord.y)),
+                Ok(false) => self.notification = Some(String::from("Agents can only be spawned on empty tiles")),
+                Err(e) => self.notification = Some(format!("Failed to spawn an agent: {}", e))
+            }
+        }
+    }
+
+    // Starts recording an animation to the export path, or stops and writes it
+    fn toggle_animation(&mut self) {
+        if self.animation.recording() {
+            self.finish_animation();
+            return;
+        }
+
+        if !self.export_path.ends_with(".gif") && !self.export_path.ends_with(".png") {
+            self.notification = Some(String::from("Animations are written to export paths ending in .gif or .png"));
+            return;
+        }
+
+        self.notification = Some(match self.animation.start() {
+            Some(..) => format!("Recording an animation to {}", self.export_path),
+            None => String::from("The steps per frame and pixels per tile must be positive whole numbers")
+        } );
+    }
+
+    // Streams the shown run's events if its settings ask for it
+    fn open_event_stream(&mut self) {
+        let target = self.simulation.borrow().settings().event_stream().map(String::from);
+        self.event_stream = match target.map(|target| (crate::event::EventStream::open(&target), target)) {
+            Some((Ok(stream), _)) => Some(stream),
+            Some((Err(e), target)) => {
+                tracing::error!("failed to open the event stream {}: {}", target, e);
+                self.notification = Some(format!("Failed to stream events to {}: {}", target, e));
+                None
+            },
+            None => None
+        };
+    }
+
+    // Starts appending the statistics of every step to the export path, or stops
+    fn toggle_csv(&mut self) {
+        if self.csv.take().is_some() {
+            self.notification = Some(String::from("Stopped writing statistics"));
+            return;
+        }
+
+        if !self.export_path.ends_with(".csv") {
+            self.notification = Some(String::from("Statistics are written to export paths ending in .csv"));
+            return;
+        }
+
+        self.notification = Some(match crate::stats::CsvWriter::create(&self.export_path) {
+            Ok(csv) => {
+                self.csv = Some(csv);
+                format!("Writing the statistics of every step to {}", self.export_path)
+            },
+            Err(e) => format!("Failed to open {}: {}", self.export_path, e)
+        } );
+    }
+
+    fn finish_animation(&mut self) {
+        self.notification = Some(match self.animation.finish(&self.export_path) {
+            Ok(0) => String::from("No frames were captured, so no animation was written"),
+            Ok(frames) => format!("Wrote an animation of {} frames to {}", frames, self.export_path),
+            Err(e) => {
+                tracing::error!("failed to write the animation to {}: {}", self.export_path, e);
+                format!("Failed to write the animation: {}", e)
+            }
+        } );
+    }
+
+    // Draws the whole world, ignoring the Canvas' zoom, for frames written to files
+    fn offscreen_canvas(&self) -> InterfaceCanvas {
+        InterfaceCanvas::new(
+            Rc::clone(&self.simulation),
+            Rc::new(RefCell::new(canvas::Cache::new())),
+            self.session.color_rules.clone(),
+            None,
+            wall_painter::Painting::default(),
+            viewport::Viewport::default(),
+            None)
+            .themed(self.session.theme.clone())
+    }
+
+    // Writes the whole world to the export path as an SVG document or PNG image, whatever the Canvas is zoomed in on
+    fn export_frame(&mut self) {
+        let canvas = self.offscreen_canvas();
+
+        let frame = frame_export::Frame {
+            canvas: &canvas,
+            simulation: &self.simulation.borrow(),
+            target: self.target_coord
+        };
+
+        // frames are rasterized when the path ends in .png, and written as SVG otherwise
+        let exported = if self.export_path.ends_with(".png") {
+            self.frame_export.png(frame).map(|image| image.save(&self.export_path).map_err(|e| e.to_string()))
+        } else {
+            self.frame_export.svg(frame).map(|svg| crate::platform::write(&self.export_path, svg).map_err(|e| e.to_string()))
+        };
+
+        self.notification = Some(match exported {
+            Some(Ok(..)) => format!("Exported the frame to {}", self.export_path),
+            Some(Err(e)) => {
+                tracing::error!("failed to export to {}: {}", self.export_path, e);
+                format!("Failed to export the frame: {}", e)
+            },
+            None => String::from("The frame width and DPI must be whole numbers")
+        } );
+    }
+
+    // Injects a new marker into the targeted Agent
+    // Its descendants inherit the marker and are drawn with the same tint
+    fn mark_target(&mut self) {
+        if let Some(coord) = self.target_coord {
+            let marker = self.markers;
+            let marked = self.simulation.borrow_mut().mark(coord, marker);
+            match marked {
+                Ok(..) => self.markers = self.markers.wrapping_add(1),
+                Err(crate::error::AccessError::NoAgent) => {  },
+                Err(e) => self.notification = Some(format!("Failed to mark the lineage: {}", e))
+            }
+        }
+    }
+
+    fn set_selection(&mut self, pane: InspectorPane) {
+        self.selection = Some(pane);
+
+        // the fields start out showing the current run's settings
+        if pane == InspectorPane::Settings {
+            self.settings_panel.load(self.simulation.borrow().settings());
+        }
+
+        self.update_selection_text();
+    }
+
+    // Reads a genome from the clipboard and attempts to add it to the Simulation
+    fn import_genome(&mut self) {
+        if let Some(genome) = Self::paste() {
+            let imported = self.simulation.borrow_mut().import(genome);
+            self.notification = Some(Self::describe_import(imported));
+        }
+    }
+
+    #[cfg(feature = "clipboard")]
+    fn copy(text: String) {
+        let copied = arboard::Clipboard::new()
+            .and_then(|mut clipboard| clipboard.set_text(text));
+
+        if let Err(e) = copied {
+            tracing::error!("failed to copy to the clipboard: {}", e);
+        }
+    }
+
+    #[cfg(feature = "clipboard")]
+    fn paste() -> Option<String> {
+        match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.get_text()) {
+            Ok(text) => Some(text),
+            Err(e) => {
+                tracing::error!("failed to read from the clipboard: {}", e);
+                None
+            }
+        }
+    }
+
+    // Builds without the clipboard feature can't reach the clipboard
+    #[cfg(not(feature = "clipboard"))]
+    fn copy(_text: String) {
+        tracing::error!("failed to copy to the clipboard: built without the clipboard feature");
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    fn paste() -> Option<String> {
+        tracing::error!("failed to read from the clipboard: built without the clipboard feature");
+        None
+    }
+
+    // Places the genome being edited on the Tile at the Coord
+    fn place_genome(&mut self, coord: coord::Coord) {
+        let placed = self.simulation.borrow_mut().place(coord, self.genome_editor.genome().to_string());
+        self.notification = Some(match placed {
+            Ok(crate::simulation::Import::NoSpace) => format!("The tile at ({}, {}) is occupied", coord.x, coord.y),
+            placed => Self::describe_import(placed)
+        } );
+    }
+
+    fn describe_import(imported: Result<crate::simulation::Import, crate::error::GenomeError>) -> String {
+        use crate::error::GenomeError;
+
+        use crate::simulation::Import::*;
+        match imported {
+            Ok(Inserted(coord, viability)) => format!("Imported an agent at ({}, {}){}",
+                coord.x,
+                coord.y,
+                match viability {
+                    Some(viability) => format!(", in quarantine it {}", viability),
+                    None => String::new()
+                }),
+            Ok(Rejected(viability)) => format!("Rejected the imported agent, it {}", viability),
+            Ok(NoSpace) => String::from("There is no room for the imported agent"),
+            Err(e @ GenomeError::MalformedGene { .. }) => format!("Failed to import the genome, {}. Genes are 8-digit binary numbers separated by spaces", e),
+            Err(e @ (GenomeError::EmptyBrain | GenomeError::NoActionNodes)) => format!("The genome can't become an agent, {}", e),
+            Err(e @ GenomeError::EmptyGenome) => format!("Failed to import the genome, {}. Copy a genome to the clipboard first", e)
+        }
+    }
+
+    // Writes the current run's manifest, so it can be reproduced later
+    fn record_manifest(&self) {
+        let manifest = crate::manifest::Manifest::new(self.simulation.borrow().settings().clone());
+        match manifest.write() {
+            Ok(path) => tracing::info!("wrote the run manifest to {}", path.display()),
+            Err(e) => tracing::error!("failed to write the run manifest: {}", e)
+        }
+    }
+
+    // Restarts the Simulation from the manifest at the export path
+    fn reproduce_run(&mut self) {
+        let manifest = match crate::manifest::Manifest::load(&self.export_path) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                self.notification = Some(format!("Failed to reproduce the run: {}", e));
+                return;
+            }
+        };
+
+        self.restart(Simulation::new(manifest.settings));
+        self.replay = None;
+
+        self.record_manifest();
+        self.notification = Some(format!("Reproduced the run from {}", self.export_path));
+    }
+
+    fn save_session(&mut self) {
+        if let Err(e) = self.session.save() {
+            tracing::error!("failed to save the session to {}: {}", session::Session::PATH, e);
+            self.notification = Some(format!("Failed to save the session: {}", e));
+        }
+    }
+
+    // Restarts in the tutorial scenario and shows the first instruction
+    fn start_tutorial(&mut self) {
+        self.restart(Simulation::new(SimulationSettings::tutorial()));
+        self.replay = None;
+        self.tutorial = Some(tutorial::Tutorial::new());
+
+        self.record_manifest();
+        self.notification = None;
+    }
+
+    // Restarts in the split world scenario, showing the statistics of each side
+    fn start_split_world(&mut self) {
+        self.restart(Simulation::new(SimulationSettings::split_world()));
+        self.replay = None;
+
+        self.record_manifest();
+        self.notification = Some(String::from("Started the split world experiment"));
+        self.set_selection(InspectorPane::SplitWorld);
+    }
+
+    // Loads the recording at the export path and replays it from the beginning
+    fn load_recording(&mut self) {
+        let recording = match crate::recording::Recording::load(&self.export_path) {
+            Ok(recording) => recording,
+            Err(e) => {
+                self.notification = Some(format!("Failed to load the recording: {}", e));
+                return;
+            }
+        };
+
+        self.restart(Simulation::replay(&recording, 0));
+        self.notification = Some(format!("Replaying {} steps from {}", recording.ticks, self.export_path));
+        self.replay = Some(crate::recording::Replay::new(recording));
+    }
+
+    // Replays forward from the nearest checkpoint, so scrubbing doesn't replay the whole run each time
+    fn replay_to(&mut self, tick: u64) {
+        if let Some(replay) = self.replay.as_mut() {
+            let simulation = replay.seek(tick);
+            self.restart(simulation);
+        }
+    }
+
+    // Swaps in a new Simulation, discarding everything gathered from the previous one
+    fn restart(&mut self, simulation: Simulation) {
+        *self.simulation.borrow_mut() = simulation;
+        self.forget_run();
+    }
+
+    // Discards everything tracked about the previously shown run
+    fn forget_run(&mut self) {
+        // the frames of an animation must all be the same size, so it ends with its run
+        if self.animation.recording() {
+            self.finish_animation();
+        }
+
+        // replayed runs may already contain marked lineages and extinctions
+        self.markers = self.simulation.borrow().markers().iter().max().map_or(0, |marker| marker.wrapping_add(1));
+        self.extinctions = self.simulation.borrow().extinctions().len();
+
+        // the log's steps would start over partway through the file
+        self.csv = None;
+        self.open_event_stream();
+
+        // a sandbox can't outlive the run it was forked from
+        self.primary = None;
+        self.benchmarks = crate::benchmark::Benchmarks::default();
+        self.motifs = crate::motif::MotifScanner::default();
+        self.stats = crate::stats::PopulationStats::default();
+        self.snapshots.clear();
+        self.slow_motion.clear();
+
+        self.target = None;
+        self.target_coord = None;
+        self.follow = false;
+        self.subscription = None;
+        self.checkpoints.clear();
+        self.scrubber = None;
+        self.selection_text.clear();
+    }
+
+    // Raises a notification for each lineage that died out during the last step
+    fn check_extinctions(&mut self) {
+        let simulation = self.simulation.borrow();
+        let extinctions = simulation.extinctions();
+        if extinctions.len() > self.extinctions {
+            self.notification = Some(extinctions[self.extinctions..].iter().fold(String::new(), |output, extinction| {
+                output + &*format!("Lineage {} went extinct, its genome was archived\n", extinction.marker)
+            } ).trim_end().to_string());
+
+            self.extinctions = extinctions.len();
+        }
+    }
+
+    // Refreshes the memory estimate, warning once each time usage nears the budget
+    fn check_memory(&mut self) {
+        let mut memory = self.simulation.borrow().memory();
+        memory.statistics = self.checkpoints.capacity() * std::mem::size_of::<(Option<String>, WorldStats)>();
+        memory.snapshots = self.snapshots.memory();
+
+        if memory.near_budget() && !self.memory_warned {
+            tracing::warn!("estimated memory usage is nearing the budget: {} bytes", memory.total());
+            self.notification = Some(format!("Memory usage is nearing the configured budget\n{}", memory));
+        }
+
+        self.memory_warned = memory.near_budget();
+        self.memory = memory;
+    }
+
+    fn update_selection_text(&mut self) {
+        use InspectorPane::*;
+
+        match self.selection {
+            // the archive does not depend on the targeted Agent
+            Some(Archive) => {
+                self.selection_text = self.simulation.borrow().extinctions().iter().fold(String::new(), |output, extinction| {
+                    output + &*format!("Lineage {}\n{}\n\n",
+                        extinction.marker,
+                        crate::agent::gene::Genome::get(extinction.genome.clone()))
+                } ).trim_end().to_string();
+            },
+            Some(Log) => self.selection_text = crate::logging::recent().join("\n"),
+            Some(FoodWeb) => {
+                let simulation = self.simulation.borrow();
+                let interactions = simulation.interactions();
+                self.selection_text = interactions.edges().iter().fold(
+                    format!("{} interactions between {} agents", interactions.len(), interactions.agents().len()),
+                    |output, edge| {
+                        output + &format!("\n{} {} {} ({}x)", edge.source, edge.kind, edge.target, edge.count)
+                    } );
+            },
+            Some(Events) => {
+                let simulation = self.simulation.borrow();
+                let since = simulation.tick().saturating_sub(Self::EVENT_FEED_STEPS);
+
+                // newest first
+                let mut events = simulation.events_since(since)
+                    .map(|event| event.to_string())
+                    .collect::<Vec<String>>();
+                events.reverse();
+
+                self.selection_text = if events.is_empty() {
+                    format!("No events in the last {} steps", Self::EVENT_FEED_STEPS)
+                } else {
+                    events.join("\n")
+                };
+            },
+            Some(AgentEvents) => {
+                self.selection_text = match &self.subscription {
+                    Some(subscription) => subscription.feed().fold(
+                        format!("Events involving agent {} since it was selected", subscription.agent),
+                        |output, event| output + "\n" + &event.to_string()),
+                    None => String::from("Select an agent to follow its events")
+                };
+            },
+            Some(Energy) => self.selection_text = self.simulation.borrow().ledger().audit(),
+            Some(Benchmarks) => self.selection_text = self.benchmarks.report(),
+            Some(Motifs) => self.selection_text = self.motifs.report(),
+            Some(Chart) => self.selection_text = self.stats.report(),
+            Some(SplitWorld) => {
+                self.selection_text = match self.simulation.borrow().sides() {
+                    Some([left, right]) => format!("Left side\n{}\n\nRight side\n{}", left, right),
+                    None => String::from("The world isn't split, start the split world experiment to divide it")
+                };
+            },
+            // the rules, the dead, the library, the genome lab, the genome editor and the settings are shown by their own widgets
+            Some(ColorRules | Morgue | Library | GenomeLab | GenomeEditor | Settings) | None => self.selection_text.clear(),
+            Some(Checkpoints) => self.selection_text = Self::compare_checkpoints(&self.checkpoints),
+            Some(TileHistory) => {
+                self.selection_text = match self.simulation.borrow().watched() {
+                    Some(watched) => {
+                        // a scrubbed tick past every record, as after stepping back, shows the latest one
+                        let latest = watched.records.back();
+                        let (tick, record) = match self.scrubber {
+                            Some(tick) => watched.records.iter().find(|(t, _)| *t >= tick).or(latest),
+                            None => latest
+                        }.copied().expect("a watched Tile is recorded as soon as it is watched");
+
+                        format!("Tile ({}, {})\n\nStep {}: {}\n\nOver the last {} steps\n  agent visits: {}\n  mean food: {:.2}",
+                            watched.coord.x,
+                            watched.coord.y,
+                            tick,
+                            record,
+                            watched.records.len(),
+                            watched.visits(),
+                            watched.mean_food())
+                    },
+                    None => String::from("Select a tile to record its history")
+                };
+            },
+            Some(Genome) => self.describe_agent(|agent| crate::agent::gene::Genome::get(agent.genome)),
+            Some(Brain) => self.describe_agent(|agent| format!("{}", petgraph::dot::Dot::new(&agent.brain))),
+            // the actions themselves are listed in a table beneath
+            Some(History) => self.describe_agent(|agent| {
+                let successful = agent.history.iter().filter(|(_, _, successful)| *successful).count();
+                let effect = format!("{} of the last {} actions had an effect", successful, agent.history.len());
+
+                match agent.stability() {
+                    Some(stability) => format!("Stability: {:.2}\n{}", stability, effect),
+                    None => effect
+                }
+            } ),
+            Some(Stats) => self.describe_agent(|agent| format!("{}", agent.brain_stats()))
+        }
+    }
+
+    // Fills one of the Agent's panes, which describe the inspected Tile when there is no Agent on it
+    fn describe_agent(&mut self, describe: impl FnOnce(crate::agent::Agent) -> String) {
+        match self.target_agent() {
+            Some(agent) => self.selection_text = describe(agent),
+            None => {
+                let simulation = self.simulation.borrow();
+                if let Some(coord) = simulation.watched().map(|watched| watched.coord) {
+                    if let Ok(summary) = simulation.summarize(coord) {
+                        self.selection_text = format!("Tile ({}, {})\n\n{}", coord.x, coord.y, summary);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// this block contains helper methods
+impl Interface {
+    // Lists each metric across every checkpoint, along with its change since the previous checkpoint
+    // Checkpoints loaded from saved worlds are labelled with their file, so several runs can be compared
+    fn compare_checkpoints(checkpoints: &[(Option<String>, WorldStats)]) -> String {
+        if checkpoints.is_empty() {
+            return String::from("No checkpoints have been recorded");
+        }
+
+        let label = |(source, checkpoint): &(Option<String>, WorldStats)| match source {
+            Some(source) => format!("{} step {}", source, checkpoint.tick),
+            None => format!("step {}", checkpoint.tick)
+        };
+
+        let steps = checkpoints.iter().fold(String::from("checkpoints:"), |output, checkpoint| {
+            output + &format!("\n  {}", label(checkpoint))
+        } );
+
+        let metrics = checkpoints[0].1.metrics().len();
+        (0..metrics).fold(steps, |mut output, metric| {
+            output.push_str(&format!("\n\n{}", checkpoints[0].1.metrics()[metric].0));
+
+            let mut previous: Option<f32> = None;
+            for checkpoint in checkpoints.iter() {
+                let value = checkpoint.1.metrics()[metric].1;
+                output.push_str(&format!("\n  {}: {:.2}", label(checkpoint), value));
+                if let Some(previous) = previous {
+                    output.push_str(&format!(" ({:+.2})", value - previous));
+                }
+
+                previous = Some(value);
+            }
+
+            output
+        } )
+    }
+}
+
+struct InterfaceCanvas {
+    simulation: Rc<RefCell<Simulation>>,
+    // checked in order, the first matching rule colors an Agent
+    color_rules: Vec<color_rules::ColorRule>,
+    // the action being played back in slow motion, labelled above the Agent that took it
+    annotation: Option<(StepAction, String)>,
+    // the selected brush, and the stroke in progress
+    painting: wall_painter::Painting,
+    viewport: viewport::Viewport,
+    // the inspected Agent's Tile, outlined every frame
+    target: Option<coord::Coord>,
+    // the Tile beneath the cursor, described in a tooltip drawn over the cached world
+    hover: Option<coord::Coord>,
+    theme: theme::Theme,
+    // shared with the Interface, which clears it whenever the world might look different
+    cache: Rc<RefCell<canvas::Cache>>
+}
+
+impl InterfaceCanvas {
+    const ANNOTATION_WIDTH: f32 = 2f32;
+    const ANNOTATION_SIZE: f32 = 16f32;
+    const BARRIER_WIDTH: f32 = 2f32;
+    const TARGET_WIDTH: f32 = 2f32;
+    const TOOLTIP_SIZE: f32 = 14f32;
+    const TOOLTIP_PADDING: f32 = 4f32;
+    // the tooltip sits this far below and to the right of the cursor
+    const TOOLTIP_OFFSET: f32 = 16f32;
+
+    const MODIFIER_KEYS: [iced::keyboard::KeyCode; 6] = [
+        iced::keyboard::KeyCode::LShift,
+        iced::keyboard::KeyCode::RShift,
+        iced::keyboard::KeyCode::LControl,
+        iced::keyboard::KeyCode::RControl,
+        iced::keyboard::KeyCode::LAlt,
+        iced::keyboard::KeyCode::RAlt
+    ];
+
+    fn new(simulation: Rc<RefCell<Simulation>>, cache: Rc<RefCell<canvas::Cache>>, color_rules: Vec<color_rules::ColorRule>, annotation: Option<(StepAction, String)>, painting: wall_painter::Painting, viewport: viewport::Viewport, target: Option<coord::Coord>) -> Self {
+        Self {
+            simulation,
+            color_rules,
+            annotation,
+            painting,
+            viewport,
+            target,
+            hover: None,
+            theme: theme::Theme::default(),
+            cache
+        }
+    }
+
+    // Describes the Tile at the Coord in a tooltip beside the cursor
+    fn hovering(mut self, hover: Option<coord::Coord>) -> Self {
+        self.hover = hover;
+        self
+    }
+
+    fn themed(mut self, theme: theme::Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    fn view(self) -> iced::Element<'static, Message> {
+        use iced::Length;
+        iced::Canvas::new(self)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .into()
+    }
+}
+
+// Colors
+impl InterfaceCanvas {
+    fn color(&self, tile: Option<&tile::Tile>) -> iced::Color {
+        let to_color = |color: [u8; 3]| {
+            [color[0] as f32 / 255f32, color[1] as f32 / 255f32, color[2] as f32 / 255f32]
+        };
+
+        if tile.is_none() {
+            return iced::Color::from(to_color(self.theme.empty));
+        }
+
+        use tile::Tile::*;
+        match tile.unwrap() {
+            // Agents that can't be borrowed are drawn without their marker, species or color rules
+            Agent(..) => {
+                let agent = tile.unwrap().try_agent().ok();
+                let rule = agent.as_ref().and_then(|agent| self.color_rules.iter().find(|rule| rule.matches(agent)));
+                let species = agent.as_ref().map_or(0, |agent| agent.attributes.species());
+
+                match (rule, agent.and_then(|agent| agent.marker)) {
+                    (Some(rule), _) => iced::Color::from(to_color(rule.color)),
+                    (None, Some(marker)) => iced::Color::from(to_color(
+                        self.theme.markers[marker as usize % self.theme.markers.len()])),
+                    (None, None) => iced::Color::from(to_color(
+                        self.theme.species[species as usize % self.theme.species.len()]))
+                }
+            },
+            Food(_, toxic) => {
+                let color = if *toxic { self.theme.toxic_food } else { self.theme.food };
+                iced::Color::from_rgba8(
+                    color[0],
+                    color[1],
+                    color[2],
+                    tile.unwrap().food() as f32 / tile::Tile::DIFFUSION_THRESHOLD as f32)
+            },
+            Wall => iced::Color::from(to_color(self.theme.wall)),
+            Water => iced::Color::from(to_color(self.theme.water))
+        }
+    }
+}
+
+impl canvas::Program<Message> for InterfaceCanvas {
+    fn update(&mut self, event: canvas::Event, bounds: iced::Rectangle, cursor: canvas::Cursor) -> (Status, Option<Message>) {
+        use canvas::event::Event::{Mouse, Keyboard};
+
+        use iced::mouse::Event::*;
+        use iced::keyboard::Event::*;
+
+        use Message::*;
+
+        let mut message: Option<Message> = None;
+        match event {
+            Mouse(WheelScrolled { delta }) if cursor.position_in(&bounds).is_some() => {
+                message = Self::fraction(cursor, bounds).map(|fraction| Zoom(delta, fraction));
+            },
+            // the minimap sits above the world, so clicks on it never reach the Tiles beneath
+            Mouse(ButtonPressed(iced::mouse::Button::Left)) if self.minimap_at(cursor, bounds).is_some() => {
+                message = self.minimap_at(cursor, bounds).map(LookAt);
+            },
+            // while a brush is selected, clicks paint rather than inspect
+            Mouse(ButtonPressed(button)) if self.painting.brush != wall_painter::Brush::Off => {
+                let stroke = wall_painter::Stroke::new(self.painting.brush, button, self.painting.shift);
+                message = match (stroke, self.coord_at(cursor, bounds)) {
+                    (Some(stroke), Some(coord)) => Some(Paint(coord, stroke)),
+                    // buttons the brush doesn't use still pan
+                    (None, Some(..)) if button != iced::mouse::Button::Left => Self::fraction(cursor, bounds).map(PanStart),
+                    _ => None
+                };
+            },
+            Mouse(ButtonPressed(iced::mouse::Button::Right | iced::mouse::Button::Middle)) if cursor.position_in(&bounds).is_some() => {
+                message = Self::fraction(cursor, bounds).map(PanStart);
+            },
+            Mouse(CursorMoved { .. }) if self.viewport.panning() => {
+                message = Self::fraction(cursor, bounds).map(Pan);
+            },
+            Mouse(ButtonReleased(iced::mouse::Button::Right | iced::mouse::Button::Middle)) if self.viewport.panning() => {
+                message = Some(EndPan);
+            },
+            Mouse(CursorMoved { .. }) if self.painting.stroke.is_some() => {
+                message = self.coord_at(cursor, bounds).zip(self.painting.stroke).map(|(coord, stroke)| Paint(coord, stroke));
+            },
+            Mouse(ButtonReleased(..)) if self.painting.stroke.is_some() => message = Some(EndPaint),
+            // only moving onto a different Tile is reported, so the tooltip isn't rebuilt for every pixel
+            Mouse(CursorMoved { .. }) => {
+                let coord = self.coord_at(cursor, bounds);
+                if coord != self.hover {
+                    message = Some(Hover(coord));
+                }
+            },
+            Mouse(CursorLeft) if self.hover.is_some() => message = Some(Hover(None)),
+            Mouse(ButtonPressed(..)) => {
+                if let Some(coord) = self.coord_at(cursor, bounds) {
+                    message = match self.simulation.try_borrow().as_deref().map(|simulation| simulation.summarize(coord)) {
+                        Ok(Ok(summary)) => Some(InspectorTarget(coord, summary)),
+                        Ok(Err(e)) => {
+                            tracing::warn!("could not inspect the agent at {:?}: {}", coord, e);
+                            None
+                        },
+                        Err(e) => {
+                            tracing::warn!("could not inspect the simulation: {}", e);
+                            None
+                        }
+                    };
+                }
+            },
+            Keyboard(ModifiersChanged(modifiers)) => message = Some(Modifiers(modifiers)),
+            // holding a modifier, such as shift to spawn Agents, doesn't take a step
+            // the navigation keys move the inspector between Agents, any other key takes a step
+            Keyboard(KeyPressed { key_code, .. }) if !Self::MODIFIER_KEYS.contains(&key_code) => {
+                message = Some(navigation::Navigation::from_key(key_code).map_or(Step, Navigate));
+            },
+            _ => {  }
+        }
+
+        (Status::Ignored, message)
+    }
+
+    fn draw(&self, bounds: iced::Rectangle, cursor: canvas::Cursor) -> Vec<canvas::Geometry> {
+        // the frame is skipped rather than panicking if the simulation is being modified
+        let simulation = match self.simulation.try_borrow() {
+            Ok(simulation) => simulation,
+            Err(..) => return Vec::new()
+        };
+
+        let transform = CanvasTransform::new(bounds.size(), simulation.size(), &self.viewport);
+
+        // the tooltip follows the cursor, so it is drawn over the cached world every frame
+        let mut overlay = canvas::Frame::new(bounds.size());
+        if let Some((coord, position)) = self.hover.zip(cursor.position_in(&bounds)) {
+            self.draw_tooltip(&mut overlay, &simulation, coord, position);
+        }
+
+        vec![
+            self.cache.borrow().draw(bounds.size(), |frame| {
+                frame.fill_rectangle(
+                    iced::Point::new(0f32, 0f32),
+                    bounds.size(),
+                    self.color(None)
+                );
+
+                // the further a Tile is from mild, the more strongly it is tinted
+                if simulation.settings().climate().is_some() {
+                    for x in 0..simulation.size().width {
+                        for y in 0..simulation.size().height {
+                            let coord = coord::Coord::new(x, y);
+                            let deviation = simulation.temperature(coord) - crate::climate::Temperature::MILD;
+                            let color = if deviation < 0f32 { self.theme.cold } else { self.theme.hot };
+
+                            frame.fill_rectangle(
+                                transform.corner(coord),
+                                transform.tile,
+                                iced::Color::from_rgba8(color[0], color[1], color[2], deviation.abs()));
+                        }
+                    }
+                }
+
+                // the ground is drawn before the Agents, so Agents are always composited on top
+                // a Tile holds a single occupant for now, so nothing is hidden beneath an Agent yet
+                let (agents, ground): (Vec<_>, Vec<_>) = simulation.coords().into_iter()
+                    .partition(|coord| matches!(simulation.get(*coord), tile::Tile::Agent(..)));
+
+                for coord in ground.into_iter().chain(agents) {
+                    let path = canvas::Path::circle(transform.center(coord), transform.radius());
+
+                    frame.fill(
+                        &path,
+                        self.color(Some(simulation.get(coord)))
+                    );
+                }
+
+                // the barrier runs between the two middle columns
+                if simulation.settings().barrier().is_some() {
+                    let x = transform.corner(coord::Coord::new(simulation.size().width / 2, 0)).x;
+                    frame.stroke(
+                        &canvas::Path::line(iced::Point::new(x, 0f32), iced::Point::new(x, bounds.height)),
+                        canvas::Stroke::default()
+                            .with_width(Self::BARRIER_WIDTH)
+                            .with_color(theme::Theme::color(self.theme.barrier)));
+                }
+
+                if let Some(coord) = self.target {
+                    frame.stroke(
+                        &canvas::Path::rectangle(transform.corner(coord), transform.tile),
+                        canvas::Stroke::default()
+                            .with_width(Self::TARGET_WIDTH)
+                            .with_color(theme::Theme::color(self.theme.highlight)));
+                }
+
+                if let Some((step, label)) = &self.annotation {
+                    let center = transform.center(step.coord);
+                    frame.stroke(
+                        &canvas::Path::circle(center, transform.radius() * 1.5f32),
+                        canvas::Stroke::default()
+                            .with_width(Self::ANNOTATION_WIDTH)
+                            .with_color(theme::Theme::color(self.theme.highlight)));
+
+                    frame.fill_text(canvas::Text {
+                        content: label.clone(),
+                        position: iced::Point::new(center.x, center.y - transform.radius() * 1.5f32),
+                        color: theme::Theme::color(self.theme.highlight),
+                        size: Self::ANNOTATION_SIZE,
+                        horizontal_alignment: iced::alignment::Horizontal::Center,
+                        vertical_alignment: iced::alignment::Vertical::Bottom,
+                        ..canvas::Text::default()
+                    } );
+                }
+
+                if let Some(minimap) = minimap::Minimap::new(bounds.size(), simulation.size(), &self.viewport) {
+                    minimap.draw(frame, &simulation, &self.viewport, &self.theme, |tile| self.color(Some(tile)));
+                }
+            }),
+            overlay.into_geometry()
+        ]
+    }
+}
+
+// this block contains helper methods
+impl InterfaceCanvas {
+    // Describes the Tile beneath the cursor in a small label beside it,
+    // kept inside the Canvas when the cursor is near its right or bottom edge
+    fn draw_tooltip(&self, frame: &mut canvas::Frame, simulation: &Simulation, coord: coord::Coord, position: iced::Point) {
+        use crate::error::AccessError;
+
+        let location = format!("({}, {})", coord.x, coord.y);
+        let content = match simulation.try_agent(coord) {
+            Ok(agent) => format!("{}\n{:?}\nid {}, fitness {}, energy {}",
+                location, agent, agent.id, u8::from(agent.fitness), u8::from(agent.energy)),
+            Err(AccessError::NoAgent) if simulation.exists(coord) => format!("{}\n{:?}", location, simulation.get(coord)),
+            Err(AccessError::NoAgent) => format!("{}\nEmpty", location),
+            Err(AccessError::Borrowed) => location
+        };
+
+        // text isn't measured on the Canvas, so its size is estimated from the longest line
+        let columns = content.lines().map(|line| line.chars().count()).max().unwrap_or(0);
+        let size = iced::Size::new(
+            columns as f32 * Self::TOOLTIP_SIZE * 0.6f32 + Self::TOOLTIP_PADDING * 2f32,
+            content.lines().count() as f32 * Self::TOOLTIP_SIZE * 1.25f32 + Self::TOOLTIP_PADDING * 2f32);
+
+        let corner = iced::Point::new(
+            (position.x + Self::TOOLTIP_OFFSET).min(frame.width() - size.width).max(0f32),
+            (position.y + Self::TOOLTIP_OFFSET).min(frame.height() - size.height).max(0f32));
+
+        let backdrop = self.theme.empty;
+        frame.fill_rectangle(corner, size, iced::Color::from_rgba8(backdrop[0], backdrop[1], backdrop[2], 0.85f32));
+        frame.fill_text(canvas::Text {
+            content,
+            position: iced::Point::new(corner.x + Self::TOOLTIP_PADDING, corner.y + Self::TOOLTIP_PADDING),
+            color: theme::Theme::color(self.theme.highlight),
+            size: Self::TOOLTIP_SIZE,
+            ..canvas::Text::default()
+        } );
+    }
+
+    // Returns the Coord of the Tile beneath the cursor, whether or not it is occupied
+    // Returns None if the cursor isn't over the Canvas, or is over the minimap
+    fn coord_at(&self, cursor: canvas::Cursor, bounds: iced::Rectangle) -> Option<coord::Coord> {
+        // the cursor is made relative to the Canvas, matching the Frame used in draw
+        let point = cursor.position_in(&bounds)?;
+
+        let simulation = self.simulation.try_borrow().ok()?;
+        if minimap::Minimap::new(bounds.size(), simulation.size(), &self.viewport).is_some_and(|minimap| minimap.contains(point)) {
+            return None;
+        }
+
+        CanvasTransform::new(bounds.size(), simulation.size(), &self.viewport).coord(point)
+    }
+
+    // Returns the point of the world beneath the cursor on the minimap, as a fraction of the world
+    // Returns None if the minimap isn't shown or the cursor isn't over it
+    fn minimap_at(&self, cursor: canvas::Cursor, bounds: iced::Rectangle) -> Option<iced::Point> {
+        let point = cursor.position_in(&bounds)?;
+
+        let simulation = self.simulation.try_borrow().ok()?;
+        minimap::Minimap::new(bounds.size(), simulation.size(), &self.viewport)
+            .filter(|minimap| minimap.contains(point))
+            .map(|minimap| minimap.fraction(point))
+    }
+
+    // Returns the cursor's position as a fraction of the Canvas' width and height
+    // Panning follows the cursor even after it leaves the Canvas
+    fn fraction(cursor: canvas::Cursor, bounds: iced::Rectangle) -> Option<iced::Point> {
+        let position = cursor.position()?;
+        Some(iced::Point::new(
+            (position.x - bounds.x) / bounds.width,
+            (position.y - bounds.y) / bounds.height
+        ))
+    }
+}
+
+// Maps between Coords and points on the Canvas, through the Viewport
+// Shared by drawing and picking, so a click always lands on the Tile drawn beneath it
+struct CanvasTransform {
+    tile: iced::Size,
+    // the size of the whole world on the Canvas, zoom included
+    world: iced::Size,
+    // the offset of the visible region from the top left corner of the world
+    origin: iced::Vector,
+    dimensions: iced::Size<usize>
+}
+
+impl CanvasTransform {
+    fn new(bounds: iced::Size, dimensions: iced::Size<usize>, viewport: &viewport::Viewport) -> Self {
+        Self {
+            tile: iced::Size::new(
+                bounds.width / dimensions.width as f32 * viewport.zoom(),
+                bounds.height / dimensions.height as f32 * viewport.zoom()
+            ),
+            world: iced::Size::new(bounds.width * viewport.zoom(), bounds.height * viewport.zoom()),
+            origin: viewport.origin(bounds),
+            dimensions
+        }
+    }
+
+    fn center(&self, coord: coord::Coord) -> iced::Point {
+        iced::Point::new(
+            self.tile.width * (coord.x as f32 + 0.5f32) - self.origin.x,
+            self.tile.height * (coord.y as f32 + 0.5f32) - self.origin.y
+        )
+    }
+
+    fn corner(&self, coord: coord::Coord) -> iced::Point {
+        iced::Point::new(
+            self.tile.width * coord.x as f32 - self.origin.x,
+            self.tile.height * coord.y as f32 - self.origin.y
+        )
+    }
+
+    fn radius(&self) -> f32 {
+        (self.tile.width + self.tile.height) / 4f32
+    }
+
+    // Points on the far edge of the world belong to the last row or column, and points beyond it to no Tile
+    fn coord(&self, point: iced::Point) -> Option<coord::Coord> {
+        let point = iced::Point::new(point.x + self.origin.x, point.y + self.origin.y);
+        if point.x < 0f32 || point.y < 0f32 || point.x > self.world.width || point.y > self.world.height {
+            return None;
+        }
+
+        let x = (point.x / self.tile.width).floor() as usize;
+        let y = (point.y / self.tile.height).floor() as usize;
+
+        Some(coord::Coord::new(
+            x.min(self.dimensions.width.checked_sub(1)?),
+            y.min(self.dimensions.height.checked_sub(1)?)
+        ))
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum InspectorPane {
+    #[default]
+    Genome,
+    Brain,
+    Stats,
+    History,
+    TileHistory,
+    Checkpoints,
+    FoodWeb,
+    Events,
+    AgentEvents,
+    Energy,
+    Benchmarks,
+    Motifs,
+    Chart,
+    SplitWorld,
+    ColorRules,
+    Morgue,
+    Library,
+    GenomeLab,
+    GenomeEditor,
+    Settings,
+    Archive,
+    Log
+}
+
+impl InspectorPane {
+    const ALL: [InspectorPane; 22] = [
+        InspectorPane::Genome,
+        InspectorPane::Brain,
+        InspectorPane::Stats,
+        InspectorPane::History,
+        InspectorPane::TileHistory,
+        InspectorPane::Checkpoints,
+        InspectorPane::FoodWeb,
+        InspectorPane::Events,
+        InspectorPane::AgentEvents,
+        InspectorPane::Energy,
+        InspectorPane::Benchmarks,
+        InspectorPane::Motifs,
+        InspectorPane::Chart,
+        InspectorPane::SplitWorld,
+        InspectorPane::ColorRules,
+        InspectorPane::Morgue,
+        InspectorPane::Library,
+        InspectorPane::GenomeLab,
+        InspectorPane::GenomeEditor,
+        InspectorPane::Settings,
+        InspectorPane::Archive,
+        InspectorPane::Log
+    ];
+}
+
+impl fmt::Display for InspectorPane {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}",
+               match self {
+                   InspectorPane::Genome => "Genome",
+                   InspectorPane::Brain => "Brain",
+                   InspectorPane::Stats => "Brain Statistics",
+                   InspectorPane::History => "Action History",
+                   InspectorPane::TileHistory => "Tile History",
+                   InspectorPane::Checkpoints => "Checkpoint Comparison",
+                   InspectorPane::FoodWeb => "Food Web",
+                   InspectorPane::Events => "Event Feed",
+                   InspectorPane::AgentEvents => "Agent Feed",
+                   InspectorPane::Energy => "Energy Audit",
+                   InspectorPane::Benchmarks => "Lineage Benchmarks",
+                   InspectorPane::Motifs => "Emergent Motifs",
+                   InspectorPane::Chart => "Population Chart",
+                   InspectorPane::SplitWorld => "Split World",
+                   InspectorPane::ColorRules => "Color Rules",
+                   InspectorPane::Morgue => "Morgue",
+                   InspectorPane::Library => "Gene Library",
+                   InspectorPane::GenomeLab => "Genome Arithmetic",
+                   InspectorPane::GenomeEditor => "Genome Editor",
+                   InspectorPane::Settings => "Settings",
+                   InspectorPane::Archive => "Extinct Lineages",
+                   InspectorPane::Log => "Log"
+               }
+        )
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // every Canvas is 100 pixels square
+    fn transform(width: usize, height: usize, viewport: &viewport::Viewport) -> CanvasTransform {
+        CanvasTransform::new(iced::Size::new(100f32, 100f32), iced::Size::new(width, height), viewport)
+    }
+
+    fn coord(transform: &CanvasTransform, x: f32, y: f32) -> Option<coord::Coord> {
+        transform.coord(iced::Point::new(x, y))
+    }
+
+    #[test]
+    fn coord_within_the_world() {
+        let transform = transform(10, 10, &viewport::Viewport::default());
+
+        assert_eq!(coord(&transform, 0f32, 0f32), Some(coord::Coord::new(0, 0)));
+        assert_eq!(coord(&transform, 15f32, 42f32), Some(coord::Coord::new(1, 4)));
+        assert_eq!(coord(&transform, 99.9f32, 99.9f32), Some(coord::Coord::new(9, 9)));
+    }
+
+    #[test]
+    fn coord_on_the_far_edge() {
+        let transform = transform(10, 10, &viewport::Viewport::default());
+
+        assert_eq!(coord(&transform, 100f32, 100f32), Some(coord::Coord::new(9, 9)));
+        assert_eq!(coord(&transform, 100f32, 50f32), Some(coord::Coord::new(9, 5)));
+    }
+
+    #[test]
+    fn coord_outside_the_world() {
+        let transform = transform(10, 10, &viewport::Viewport::default());
+
+        assert_eq!(coord(&transform, -0.1f32, 50f32), None);
+        assert_eq!(coord(&transform, 50f32, -20f32), None);
+        assert_eq!(coord(&transform, 100.5f32, 50f32), None);
+        assert_eq!(coord(&transform, 50f32, 250f32), None);
+    }
+
+    #[test]
+    fn coord_zoomed_and_panned() {
+        // zoomed in on the center, the Canvas shows the middle 5 columns and rows
+        let mut viewport = viewport::Viewport::default();
+        viewport.zoom_to(2f32);
+        let zoomed = transform(10, 10, &viewport);
+
+        assert_eq!(coord(&zoomed, 0f32, 0f32), Some(coord::Coord::new(2, 2)));
+        assert_eq!(coord(&zoomed, 99.9f32, 99.9f32), Some(coord::Coord::new(7, 7)));
+
+        // panned to the top left corner
+        viewport.look_at(iced::Point::new(0f32, 0f32));
+        let panned = transform(10, 10, &viewport);
+
+        assert_eq!(coord(&panned, 0f32, 0f32), Some(coord::Coord::new(0, 0)));
+        assert_eq!(coord(&panned, 99.9f32, 45f32), Some(coord::Coord::new(4, 2)));
+        assert_eq!(coord(&panned, -1f32, 0f32), None);
+    }
+
+    #[test]
+    fn coord_in_a_single_tile_world() {
+        let transform = transform(1, 1, &viewport::Viewport::default());
+
+        assert_eq!(coord(&transform, 0f32, 0f32), Some(coord::Coord::new(0, 0)));
+        assert_eq!(coord(&transform, 50f32, 50f32), Some(coord::Coord::new(0, 0)));
+        assert_eq!(coord(&transform, 100f32, 100f32), Some(coord::Coord::new(0, 0)));
+        assert_eq!(coord(&transform, 101f32, 100f32), None);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::interface::Message;
+use crate::morgue::Morgue;
+
+// Searches the morgue, listing the newest matches with a button to bring each one back
+#[derive(Default)]
+pub(crate) struct MorgueBrowser {
+    pub(crate) query: String,
+    state_query: iced::text_input::State,
+    state_resurrect: Vec<iced::button::State>
+}
+
+impl MorgueBrowser {
+    const PADDING: u16 = 10;
+    // only the newest matches are listed, the rest can be found by narrowing the search
+    const RESULTS: usize = 20;
+
+    pub(crate) fn view(&mut self, morgue: &Morgue) -> iced::Element<'_, Message> {
+        use iced::Length;
+
+        use Message::*;
+        let results = morgue.search(&self.query);
+        self.state_resurrect.resize_with(results.len().min(Self::RESULTS), iced::button::State::default);
+
+        let mut browser = iced::Column::new()
+            .push(
+                iced::TextInput::new(
+                    &mut self.state_query,
+                    "Search by cause, lineage, id or genes",
+                    &self.query,
+                    MorgueQuery)
+                    .width(Length::Fill))
+            .push(
+                iced::Text::new(format!("{} of the last {} deaths match", results.len(), morgue.len()))
+                    .width(Length::Fill))
+            .width(Length::Fill)
+            .spacing(Self::PADDING);
+
+        for (obituary, state) in results.iter().zip(self.state_resurrect.iter_mut()) {
+            browser = browser.push(
+                iced::Row::new()
+                    .push(
+                        iced::Text::new(obituary.to_string())
+                            .width(Length::Fill))
+                    .push(
+                        iced::Button::new(
+                            state,
+                            iced::Text::new("Resurrect"))
+                            .on_press(Resurrect(obituary.id)))
+                    .width(Length::Fill)
+                    .spacing(Self::PADDING));
+        }
+
+        browser.into()
+    }
+}
@@ -0,0 +1,89 @@
+use iced::keyboard::KeyCode;
+
+use crate::simulation::Simulation;
+use crate::tile::coord;
+
+// How the population is ordered when stepping through it
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Order {
+    // fittest first
+    Fitness,
+    // oldest first
+    Age
+}
+
+// Moves the inspector from one Agent to another without clicking on their Tiles
+// The left and right arrows step through the population by fitness, the up and down arrows by age,
+// and N jumps to the Agent closest to the inspected one
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Navigation {
+    Next(Order),
+    Previous(Order),
+    Nearest
+}
+
+impl Navigation {
+    pub(crate) fn from_key(key_code: KeyCode) -> Option<Self> {
+        use Navigation::*;
+        match key_code {
+            KeyCode::Right => Some(Next(Order::Fitness)),
+            KeyCode::Left => Some(Previous(Order::Fitness)),
+            KeyCode::Down => Some(Next(Order::Age)),
+            KeyCode::Up => Some(Previous(Order::Age)),
+            KeyCode::N => Some(Nearest),
+            _ => None
+        }
+    }
+
+    // The Coord and id of the Agent to inspect next, given the one inspected now
+    // Without an inspected Agent, stepping starts at either end of the order,
+    // and there is nothing to be nearest to
+    pub(crate) fn target(&self, simulation: &Simulation, current: Option<(coord::Coord, u64)>) -> Option<(coord::Coord, u64)> {
+        // Agents that can't be borrowed are skipped
+        let agents = simulation.agents().into_iter()
+            .filter_map(|coord| simulation.try_agent(coord).ok().map(|agent| Candidate {
+                coord,
+                id: agent.id,
+                fitness: u8::from(agent.fitness),
+                born: agent.born
+            } ))
+            .collect::<Vec<_>>();
+
+        use Navigation::*;
+        match self {
+            Next(order) | Previous(order) => {
+                let mut ordered = agents;
+                ordered.sort_by(|a, b| match order {
+                    Order::Fitness => b.fitness.cmp(&a.fitness),
+                    Order::Age => a.born.cmp(&b.born)
+                }.then(a.id.cmp(&b.id)));
+
+                let count = ordered.len();
+                let position = current.and_then(|(_, id)| ordered.iter().position(|agent| agent.id == id));
+                let index = match (self, position) {
+                    (Next(..), Some(position)) => (position + 1) % count,
+                    (Previous(..), Some(position)) => (position + count - 1) % count,
+                    (Next(..), None) => 0,
+                    _ => count.checked_sub(1)?
+                };
+
+                ordered.get(index).map(|agent| (agent.coord, agent.id))
+            },
+            Nearest => {
+                let (origin, id) = current?;
+                agents.iter()
+                    .filter(|agent| agent.id != id)
+                    .min_by_key(|agent| (agent.coord.x.abs_diff(origin.x) + agent.coord.y.abs_diff(origin.y), agent.id))
+                    .map(|agent| (agent.coord, agent.id))
+            }
+        }
+    }
+}
+
+// The parts of an Agent that navigation orders by, copied so the whole Agent isn't cloned
+struct Candidate {
+    coord: coord::Coord,
+    id: u64,
+    fitness: u8,
+    born: u64
+}
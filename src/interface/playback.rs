@@ -0,0 +1,96 @@
+use crate::interface::Message;
+
+// The control row above the Canvas
+// Steps the Simulation continuously at an adjustable number of ticks per second, or a few steps at a time
+// Key presses still take a single step, whether or not the run is playing
+// Stepping back restores the world from before the latest step, see SnapshotRing
+pub(crate) struct Playback {
+    pub(crate) playing: bool,
+    pub(crate) rate: u16,
+    state_toggle: iced::button::State,
+    state_step: iced::button::State,
+    state_step_back: iced::button::State,
+    state_step_n: [iced::button::State; Self::STEPS.len()],
+    state_reset: iced::button::State,
+    state_rate: iced::slider::State
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Self {
+            playing: false,
+            rate: Self::DEFAULT_RATE,
+            state_toggle: iced::button::State::default(),
+            state_step: iced::button::State::default(),
+            state_step_back: iced::button::State::default(),
+            state_step_n: Default::default(),
+            state_reset: iced::button::State::default(),
+            state_rate: iced::slider::State::default()
+        }
+    }
+}
+
+impl Playback {
+    const PADDING: u16 = 10;
+
+    const DEFAULT_RATE: u16 = 10;
+    pub(crate) const MAX_RATE: u16 = 60;
+
+    // each gets a button that takes that many steps at once
+    const STEPS: [u32; 2] = [10, 100];
+
+    // The time between steps while playing
+    pub(crate) fn interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs_f32(1f32 / self.rate.clamp(1, Self::MAX_RATE) as f32)
+    }
+
+    pub(crate) fn view(&mut self) -> iced::Element<'_, Message> {
+        use iced::Length;
+
+        use Message::*;
+        let mut toolbar = iced::Row::new()
+            .push(
+                iced::Button::new(
+                    &mut self.state_step_back,
+                    iced::Text::new("Step Back"))
+                    .on_press(StepBack))
+            .push(
+                iced::Button::new(
+                    &mut self.state_toggle,
+                    iced::Text::new(if self.playing { "Pause" } else { "Play" }))
+                    .on_press(if self.playing { Pause } else { Play }))
+            .push(
+                iced::Button::new(
+                    &mut self.state_step,
+                    iced::Text::new("Step"))
+                    .on_press(Step));
+
+        for (steps, state) in Self::STEPS.iter().zip(self.state_step_n.iter_mut()) {
+            toolbar = toolbar.push(
+                iced::Button::new(
+                    state,
+                    iced::Text::new(format!("Step {}", steps)))
+                    .on_press(StepN(*steps)));
+        }
+
+        toolbar
+            .push(
+                iced::Button::new(
+                    &mut self.state_reset,
+                    iced::Text::new("Reset"))
+                    .on_press(Reset))
+            .push(
+                iced::Slider::new(
+                    &mut self.state_rate,
+                    1..=Self::MAX_RATE,
+                    self.rate,
+                    TickRate)
+                    .width(Length::Fill))
+            .push(
+                iced::Text::new(format!("{} ticks/s", self.rate))
+                    .width(Length::Fill))
+            .width(Length::Fill)
+            .spacing(Self::PADDING)
+            .into()
+    }
+}
@@ -0,0 +1,78 @@
+use crate::interface::Message;
+use crate::pulse::{Modifier, Pulses};
+
+// Applies temporary global modifiers to the Simulation, and lists those in effect
+pub(crate) struct PulseControls {
+    pub(crate) modifier: Modifier,
+    pub(crate) duration: String,
+    state_modifier: iced::button::State,
+    state_duration: iced::text_input::State,
+    state_apply: iced::button::State
+}
+
+impl Default for PulseControls {
+    fn default() -> Self {
+        Self {
+            modifier: Self::MODIFIERS[0],
+            duration: String::from("500"),
+            state_modifier: iced::button::State::default(),
+            state_duration: iced::text_input::State::default(),
+            state_apply: iced::button::State::default()
+        }
+    }
+}
+
+impl PulseControls {
+    const PADDING: u16 = 10;
+
+    // the modifiers offered by the interface, in the order the button cycles through them
+    const MODIFIERS: [Modifier; 2] = [
+        Modifier::Mutation(2f32),
+        Modifier::Truce
+    ];
+
+    pub(crate) fn cycle(&mut self) {
+        let index = Self::MODIFIERS.iter().position(|modifier| *modifier == self.modifier).unwrap_or(0);
+        self.modifier = Self::MODIFIERS[(index + 1) % Self::MODIFIERS.len()];
+    }
+
+    pub(crate) fn view(&mut self, pulses: &Pulses, tick: u64) -> iced::Element<'_, Message> {
+        use iced::Length;
+
+        use Message::*;
+        let active = pulses.active(tick + 1).fold(String::new(), |output, pulse| {
+            output + &format!("{}\n", pulse)
+        } );
+
+        iced::Column::new()
+            .push(
+                iced::Row::new()
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_modifier,
+                            iced::Text::new(format!("Pulse: {}", self.modifier)))
+                            .width(Length::Fill)
+                            .on_press(CyclePulse))
+                    .push(
+                        iced::TextInput::new(
+                            &mut self.state_duration,
+                            "Steps",
+                            &self.duration,
+                            PulseDurationChange)
+                            .width(Length::Fill)
+                            .on_submit(ApplyPulse))
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_apply,
+                            iced::Text::new("Apply"))
+                            .on_press(ApplyPulse))
+                    .width(Length::Fill)
+                    .spacing(Self::PADDING))
+            .push(
+                iced::Text::new(active.trim_end())
+                    .width(Length::Fill))
+            .width(Length::Fill)
+            .spacing(Self::PADDING)
+            .into()
+    }
+}
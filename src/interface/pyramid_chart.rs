@@ -0,0 +1,90 @@
+use iced::canvas;
+
+use crate::interface::Message;
+use crate::stats::Pyramid;
+
+// Draws the age structure as a population pyramid, the youngest band at the bottom
+// Each band's bar is centered and as wide as its share of the largest band,
+// so boom cohorts show as bulges that climb the pyramid as they age
+pub(crate) struct PyramidChart {
+    pyramid: Option<Pyramid>,
+    cache: canvas::Cache
+}
+
+impl PyramidChart {
+    const HEIGHT: u16 = 200;
+    const PADDING: f32 = 16f32;
+    // room on the left for each band's ages
+    const LABEL_WIDTH: f32 = 64f32;
+    const LABEL_SIZE: f32 = 12f32;
+    // the fraction of each band's height left between bars
+    const GAP: f32 = 0.15f32;
+
+    const COLOR_BACKGROUND: [u8; 3] = [0x1A, 0x1A, 0x1A];
+    const COLOR_BAR: [u8; 3] = [0x50, 0x8C, 0xFF];
+    const COLOR_LABEL: [u8; 3] = [0xC8, 0xC8, 0xC8];
+
+    pub(crate) fn new(pyramid: Option<Pyramid>) -> Self {
+        Self {
+            pyramid,
+            cache: canvas::Cache::new()
+        }
+    }
+
+    pub(crate) fn view(self) -> iced::Element<'static, Message> {
+        use iced::Length;
+        iced::Canvas::new(self)
+            .width(Length::Fill)
+            .height(Length::Units(Self::HEIGHT))
+            .into()
+    }
+
+    fn color(color: [u8; 3]) -> iced::Color {
+        iced::Color::from_rgb8(color[0], color[1], color[2])
+    }
+}
+
+impl canvas::Program<Message> for PyramidChart {
+    fn draw(&self, bounds: iced::Rectangle, _cursor: canvas::Cursor) -> Vec<canvas::Geometry> {
+        vec![
+            self.cache.draw(bounds.size(), |frame| {
+                frame.fill_rectangle(
+                    iced::Point::new(0f32, 0f32),
+                    bounds.size(),
+                    Self::color(Self::COLOR_BACKGROUND)
+                );
+
+                let pyramid = match &self.pyramid {
+                    Some(pyramid) => pyramid,
+                    None => return
+                };
+
+                let width = bounds.width - Self::PADDING * 2f32 - Self::LABEL_WIDTH;
+                let height = (bounds.height - Self::PADDING * 2f32) / pyramid.counts.len().max(1) as f32;
+                let center = Self::PADDING + Self::LABEL_WIDTH + width / 2f32;
+                let largest = pyramid.counts.iter().copied().max().unwrap_or(0).max(1);
+
+                for (index, count) in pyramid.counts.iter().enumerate() {
+                    // the youngest band sits at the bottom
+                    let top = Self::PADDING + height * (pyramid.counts.len() - index - 1) as f32;
+                    let bar = width * *count as f32 / largest as f32;
+
+                    frame.fill_rectangle(
+                        iced::Point::new(center - bar / 2f32, top + height * Self::GAP / 2f32),
+                        iced::Size::new(bar, height * (1f32 - Self::GAP)),
+                        Self::color(Self::COLOR_BAR));
+
+                    let (youngest, oldest) = pyramid.ages(index);
+                    frame.fill_text(canvas::Text {
+                        content: format!("{}-{}", youngest, oldest),
+                        position: iced::Point::new(Self::PADDING, top + height / 2f32),
+                        color: Self::color(Self::COLOR_LABEL),
+                        size: Self::LABEL_SIZE,
+                        vertical_alignment: iced::alignment::Vertical::Center,
+                        ..canvas::Text::default()
+                    } );
+                }
+            })
+        ]
+    }
+}
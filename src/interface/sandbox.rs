@@ -0,0 +1,117 @@
+use crate::interface::Message;
+use crate::simulation::{Rule, SimulationSettings, UpdateMode};
+use crate::tile::coord;
+
+// Forks a sandbox from the primary run, or perturbs and resolves the current sandbox
+#[derive(Default)]
+pub(crate) struct SandboxControls {
+    state_fork: iced::button::State,
+    state_promote: iced::button::State,
+    state_discard: iced::button::State,
+    state_kill: iced::button::State,
+    state_feed: iced::button::State,
+    state_boundary: iced::button::State,
+    state_update: iced::button::State,
+    state_neighborhood: iced::button::State
+}
+
+impl SandboxControls {
+    const PADDING: u16 = 10;
+
+    // `forked` is true while a sandbox is shown in place of the primary run
+    pub(crate) fn view(&mut self, forked: bool, settings: &SimulationSettings) -> iced::Element<'_, Message> {
+        use iced::Length;
+
+        use Message::*;
+        if !forked {
+            return iced::Button::new(
+                &mut self.state_fork,
+                iced::Text::new("Fork Sandbox"))
+                .width(Length::Fill)
+                .on_press(ForkSandbox)
+                .into();
+        }
+
+        // each rule button switches to the next option
+        let boundary = {
+            use coord::BoundaryMode::*;
+            match settings.boundary() {
+                Wrap => (Wrap, Wall),
+                Wall => (Wall, Kill),
+                Kill => (Kill, Wrap)
+            }
+        };
+
+        let update = match settings.update() {
+            UpdateMode::Sequential => (UpdateMode::Sequential, UpdateMode::Synchronous),
+            UpdateMode::Synchronous => (UpdateMode::Synchronous, UpdateMode::Sequential)
+        };
+
+        let neighborhood = match settings.neighborhood() {
+            coord::Neighborhood::VonNeumann => (coord::Neighborhood::VonNeumann, coord::Neighborhood::Moore),
+            coord::Neighborhood::Moore => (coord::Neighborhood::Moore, coord::Neighborhood::VonNeumann)
+        };
+
+        iced::Column::new()
+            .push(
+                iced::Text::new("Sandbox: changes made here don't affect the primary run")
+                    .width(Length::Fill))
+            .push(
+                iced::Row::new()
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_kill,
+                            iced::Text::new("Kill Agent"))
+                            .width(Length::Fill)
+                            .on_press(SandboxKill))
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_feed,
+                            iced::Text::new("Add Food"))
+                            .width(Length::Fill)
+                            .on_press(SandboxFeed))
+                    .width(Length::Fill)
+                    .spacing(Self::PADDING))
+            .push(
+                iced::Row::new()
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_boundary,
+                            iced::Text::new(format!("Boundary: {:?}", boundary.0)))
+                            .width(Length::Fill)
+                            .on_press(SandboxRule(Rule::Boundary(boundary.1))))
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_update,
+                            iced::Text::new(format!("Update: {:?}", update.0)))
+                            .width(Length::Fill)
+                            .on_press(SandboxRule(Rule::Update(update.1))))
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_neighborhood,
+                            iced::Text::new(format!("Neighborhood: {:?}", neighborhood.0)))
+                            .width(Length::Fill)
+                            .on_press(SandboxRule(Rule::Neighborhood(neighborhood.1))))
+                    .width(Length::Fill)
+                    .spacing(Self::PADDING))
+            .push(
+                iced::Row::new()
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_promote,
+                            iced::Text::new("Promote Sandbox"))
+                            .width(Length::Fill)
+                            .on_press(PromoteSandbox))
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_discard,
+                            iced::Text::new("Discard Sandbox"))
+                            .width(Length::Fill)
+                            .on_press(DiscardSandbox))
+                    .width(Length::Fill)
+                    .spacing(Self::PADDING))
+            .width(Length::Fill)
+            .spacing(Self::PADDING)
+            .into()
+    }
+}
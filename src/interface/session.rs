@@ -0,0 +1,28 @@
+use crate::error::LoadError;
+use crate::interface::color_rules::ColorRule;
+use crate::interface::theme::Theme;
+
+// Interface preferences that outlive a single run
+// Saved whenever they change, and restored when the interface starts
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Session {
+    #[serde(default)]
+    pub(crate) color_rules: Vec<ColorRule>,
+    #[serde(default)]
+    pub(crate) theme: Theme
+}
+
+impl Session {
+    // relative to the working directory
+    pub(crate) const PATH: &'static str = "session.json";
+
+    pub(crate) fn load() -> Result<Self, LoadError> {
+        let contents = crate::platform::read_to_string(Self::PATH).map_err(LoadError::Io)?;
+        serde_json::from_str(&contents).map_err(LoadError::Parse)
+    }
+
+    pub(crate) fn save(&self) -> std::io::Result<()> {
+        // a session only holds plain values, so serializing it can't fail
+        crate::platform::write(Self::PATH, serde_json::to_string_pretty(self).unwrap())
+    }
+}
@@ -0,0 +1,188 @@
+use crate::error::SettingsError;
+use crate::interface::Message;
+use crate::simulation::{Basics, SimulationSettings};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Field {
+    Width,
+    Height,
+    Agents,
+    Complexity,
+    Seed,
+    MutationRate,
+    ReproductionThreshold,
+    // in megabytes
+    MemoryBudget
+}
+
+impl Field {
+    const ALL: [Field; 8] = [
+        Field::Width,
+        Field::Height,
+        Field::Agents,
+        Field::Complexity,
+        Field::Seed,
+        Field::MutationRate,
+        Field::ReproductionThreshold,
+        Field::MemoryBudget
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Field::Width => "width",
+            Field::Height => "height",
+            Field::Agents => "agent count",
+            Field::Complexity => "complexity",
+            Field::Seed => "seed",
+            Field::MutationRate => "mutation rate",
+            Field::ReproductionThreshold => "reproduction threshold",
+            Field::MemoryBudget => "memory budget (MB)"
+        }
+    }
+}
+
+// Edits the settings of the current run
+// Applying them replaces the run with a fresh Simulation, any setting without a field is kept
+// Loading a config replaces every setting with those in the TOML file at the export path
+#[derive(Default)]
+pub(crate) struct SettingsPanel {
+    // the text of each field, in the order of Field::ALL
+    values: [String; Field::ALL.len()],
+    state_values: [iced::text_input::State; Field::ALL.len()],
+    state_apply: iced::button::State,
+    state_load_config: iced::button::State
+}
+
+impl SettingsPanel {
+    const PADDING: u16 = 10;
+
+    const MEGABYTE: usize = 1024 * 1024;
+
+    // Fills every field from the given settings
+    pub(crate) fn load(&mut self, settings: &SimulationSettings) {
+        let basics = settings.basics();
+        for (field, value) in Field::ALL.iter().zip(self.values.iter_mut()) {
+            *value = match field {
+                Field::Width => basics.dimensions.width.to_string(),
+                Field::Height => basics.dimensions.height.to_string(),
+                Field::Agents => basics.agents.to_string(),
+                Field::Complexity => basics.complexity.to_string(),
+                Field::Seed => basics.seed.map_or(String::new(), |seed| seed.to_string()),
+                Field::MutationRate => basics.mutation_rate.to_string(),
+                Field::ReproductionThreshold => basics.reproduction_threshold.to_string(),
+                Field::MemoryBudget => (basics.memory_budget / Self::MEGABYTE).to_string()
+            };
+        }
+    }
+
+    pub(crate) fn set(&mut self, field: Field, value: String) {
+        if let Some(index) = Field::ALL.iter().position(|f| *f == field) {
+            self.values[index] = value;
+        }
+    }
+
+    // Builds on the given settings, an empty seed draws a new one
+    pub(crate) fn settings(&self, settings: &SimulationSettings) -> Result<SimulationSettings, SettingsError> {
+        let width = self.parse::<usize>(Field::Width)?;
+        let height = self.parse::<usize>(Field::Height)?;
+        if width == 0 || height == 0 {
+            return Err(SettingsError::EmptyWorld);
+        }
+
+        let seed = match self.value(Field::Seed).trim() {
+            "" => None,
+            _ => Some(self.parse::<u64>(Field::Seed)?)
+        };
+
+        let mutation_rate = self.parse::<f32>(Field::MutationRate)?;
+        if !mutation_rate.is_finite() || mutation_rate < 0f32 {
+            return Err(self.invalid(Field::MutationRate));
+        }
+
+        let reproduction_threshold = self.parse::<u8>(Field::ReproductionThreshold)?;
+        if !(1..=Basics::MAX_REPRODUCTION_THRESHOLD).contains(&reproduction_threshold) {
+            return Err(self.invalid(Field::ReproductionThreshold));
+        }
+
+        let memory_budget = self.parse::<usize>(Field::MemoryBudget)?
+            .checked_mul(Self::MEGABYTE)
+            .ok_or_else(|| self.invalid(Field::MemoryBudget))?;
+
+        let settings = settings.with_basics(Basics {
+            dimensions: iced::Size::new(width, height),
+            agents: self.parse::<usize>(Field::Agents)?,
+            complexity: self.parse::<usize>(Field::Complexity)?,
+            seed,
+            mutation_rate,
+            reproduction_threshold,
+            memory_budget
+        } );
+
+        settings.validate().map_err(|e| SettingsError::Invalid(e.to_string()))?;
+        Ok(settings)
+    }
+
+    pub(crate) fn view(&mut self) -> iced::Element<'_, Message> {
+        use iced::Length;
+
+        use Message::*;
+        let mut panel = iced::Column::new()
+            .width(Length::Fill)
+            .spacing(Self::PADDING);
+
+        for ((field, value), state) in Field::ALL.iter().zip(self.values.iter()).zip(self.state_values.iter_mut()) {
+            let field = *field;
+            panel = panel.push(
+                iced::Row::new()
+                    .push(
+                        iced::Text::new(field.name())
+                            .width(Length::Fill))
+                    .push(
+                        iced::TextInput::new(
+                            state,
+                            if field == Field::Seed { "Random" } else { "" },
+                            value,
+                            move |value| SettingsInput(field, value))
+                            .width(Length::Fill)
+                            .on_submit(ApplySettings))
+                    .width(Length::Fill)
+                    .spacing(Self::PADDING));
+        }
+
+        panel
+            .push(
+                iced::Row::new()
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_apply,
+                            iced::Text::new("Apply"))
+                            .width(Length::Fill)
+                            .on_press(ApplySettings))
+                    .push(
+                        iced::Button::new(
+                            &mut self.state_load_config,
+                            iced::Text::new("Load Config"))
+                            .width(Length::Fill)
+                            .on_press(LoadConfig))
+                    .width(Length::Fill)
+                    .spacing(Self::PADDING))
+            .into()
+    }
+
+    fn value(&self, field: Field) -> &str {
+        Field::ALL.iter()
+            .position(|f| *f == field)
+            .map_or("", |index| self.values[index].as_str())
+    }
+
+    fn parse<T: std::str::FromStr>(&self, field: Field) -> Result<T, SettingsError> {
+        self.value(field).trim().parse::<T>().map_err(|_| self.invalid(field))
+    }
+
+    fn invalid(&self, field: Field) -> SettingsError {
+        SettingsError::InvalidValue {
+            field: field.name(),
+            value: self.value(field).to_string()
+        }
+    }
+}
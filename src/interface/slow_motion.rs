@@ -0,0 +1,75 @@
+use std::collections::VecDeque;
+
+use crate::interface::Message;
+use crate::simulation::StepAction;
+
+// Plays back each step one action at a time, so a presenter can talk through what every agent did
+// While enabled, each Step message reveals the next action, and the Simulation only steps once all have been shown
+#[derive(Default)]
+pub(crate) struct SlowMotion {
+    pub(crate) enabled: bool,
+    // the actions of the latest step that haven't been shown yet
+    queue: VecDeque<StepAction>,
+    current: Option<StepAction>,
+    // the number of actions taken during the latest step
+    total: usize,
+    state_toggle: iced::button::State
+}
+
+impl SlowMotion {
+    const PADDING: u16 = 10;
+
+    pub(crate) fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+        self.clear();
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.queue.clear();
+        self.current = None;
+        self.total = 0;
+    }
+
+    // Queues the actions of a step that was just taken, and shows the first of them
+    pub(crate) fn begin(&mut self, actions: &[StepAction]) {
+        self.queue = actions.iter().copied().collect();
+        self.total = actions.len();
+        self.advance();
+    }
+
+    // Shows the next action of the current step
+    // Returns false once every action has been shown, when it's time to step again
+    pub(crate) fn advance(&mut self) -> bool {
+        self.current = self.queue.pop_front();
+        self.current.is_some()
+    }
+
+    // The action being shown, and the label drawn above the Agent that took it
+    pub(crate) fn annotation(&self) -> Option<(StepAction, String)> {
+        self.current.map(|step| (step, format!("#{} {:?}", step.agent, step.action)))
+    }
+
+    pub(crate) fn view(&mut self) -> iced::Element<'_, Message> {
+        use iced::Length;
+
+        let progress = match self.current {
+            Some(..) => format!("Action {} of {}", self.total - self.queue.len(), self.total),
+            None if self.enabled => String::from("Step to play back the next step"),
+            None => String::new()
+        };
+
+        iced::Row::new()
+            .push(
+                iced::Button::new(
+                    &mut self.state_toggle,
+                    iced::Text::new(if self.enabled { "Slow Motion: On" } else { "Slow Motion: Off" }))
+                    .width(Length::Fill)
+                    .on_press(Message::ToggleSlowMotion))
+            .push(
+                iced::Text::new(progress)
+                    .width(Length::Fill))
+            .width(Length::Fill)
+            .spacing(Self::PADDING)
+            .into()
+    }
+}
@@ -0,0 +1,145 @@
+use iced::canvas;
+
+use crate::interface::Message;
+use crate::stats::Series;
+
+// Plots each recorded metric over time as a line, with its own vertical scale
+// Population and food dwarf the other metrics, so each line is scaled to its own peak
+// Steps covered by a rule pulse are shaded, as in the benchmark chart
+pub(crate) struct StatsChart {
+    series: Vec<(Series, Vec<(u64, f32)>)>,
+    // the first and last tick of each pulse
+    bands: Vec<(u64, u64)>,
+    cache: canvas::Cache
+}
+
+impl StatsChart {
+    const HEIGHT: u16 = 240;
+    const PADDING: f32 = 16f32;
+    const LINE_WIDTH: f32 = 2f32;
+    const LABEL_SIZE: f32 = 12f32;
+
+    const COLOR_BACKGROUND: [u8; 3] = [0x1A, 0x1A, 0x1A];
+    const COLOR_AXES: [u8; 3] = [0x5A, 0x5A, 0x5A];
+    const COLOR_BAND: [u8; 3] = [0xFF, 0xFF, 0xFF];
+    const BAND_ALPHA: f32 = 0.08f32;
+    // indexed like Series::ALL
+    const COLOR_SERIES: [[u8; 3]; 7] = [
+        [0x50, 0x8C, 0xFF],
+        [0xFF, 0xC8, 0x32],
+        [0x50, 0xDC, 0x78],
+        [0xB4, 0x50, 0xDC],
+        [0xFF, 0x50, 0x50],
+        [0xC8, 0xC8, 0xC8],
+        [0x32, 0xDC, 0xDC]
+    ];
+
+    // Series without any points, such as the weather of a world without Weather, are left out
+    pub(crate) fn new(series: Vec<(Series, Vec<(u64, f32)>)>, bands: Vec<(u64, u64)>) -> Self {
+        Self {
+            series: series.into_iter().filter(|(_, points)| !points.is_empty()).collect(),
+            bands,
+            cache: canvas::Cache::new()
+        }
+    }
+
+    pub(crate) fn view(self) -> iced::Element<'static, Message> {
+        use iced::Length;
+        iced::Canvas::new(self)
+            .width(Length::Fill)
+            .height(Length::Units(Self::HEIGHT))
+            .into()
+    }
+
+    fn color(series: Series) -> iced::Color {
+        let index = Series::ALL.iter().position(|s| *s == series).unwrap_or(0);
+        let color = Self::COLOR_SERIES[index % Self::COLOR_SERIES.len()];
+        iced::Color::from_rgb8(color[0], color[1], color[2])
+    }
+}
+
+impl canvas::Program<Message> for StatsChart {
+    fn draw(&self, bounds: iced::Rectangle, _cursor: canvas::Cursor) -> Vec<canvas::Geometry> {
+        vec![
+            self.cache.draw(bounds.size(), |frame| {
+                frame.fill_rectangle(
+                    iced::Point::new(0f32, 0f32),
+                    bounds.size(),
+                    iced::Color::from_rgb8(Self::COLOR_BACKGROUND[0], Self::COLOR_BACKGROUND[1], Self::COLOR_BACKGROUND[2])
+                );
+
+                let width = bounds.width - Self::PADDING * 2f32;
+                let height = bounds.height - Self::PADDING * 2f32;
+
+                frame.stroke(
+                    &canvas::Path::new(|path| {
+                        path.move_to(iced::Point::new(Self::PADDING, Self::PADDING));
+                        path.line_to(iced::Point::new(Self::PADDING, Self::PADDING + height));
+                        path.line_to(iced::Point::new(Self::PADDING + width, Self::PADDING + height));
+                    } ),
+                    canvas::Stroke::default().with_color(iced::Color::from_rgb8(Self::COLOR_AXES[0], Self::COLOR_AXES[1], Self::COLOR_AXES[2]))
+                );
+
+                // every series shares the same steps
+                let ticks = self.series.iter().flat_map(|(_, points)| points.iter().map(|(tick, _)| *tick));
+                let first = ticks.clone().min().unwrap_or(0);
+                let last = ticks.max().unwrap_or(0);
+
+                // bands are clipped to the charted steps
+                if last > first {
+                    let x = |tick: u64| Self::PADDING + width * (tick - first) as f32 / (last - first) as f32;
+                    for (start, end) in self.bands.iter().map(|(start, end)| ((*start).max(first), (*end).min(last))) {
+                        if start >= end {
+                            continue;
+                        }
+
+                        frame.fill_rectangle(
+                            iced::Point::new(x(start), Self::PADDING),
+                            iced::Size::new(x(end) - x(start), height),
+                            iced::Color::from_rgba8(Self::COLOR_BAND[0], Self::COLOR_BAND[1], Self::COLOR_BAND[2], Self::BAND_ALPHA));
+                    }
+                }
+
+                for (index, (series, points)) in self.series.iter().enumerate() {
+                    let color = Self::color(*series);
+
+                    // a series that never rises above zero lies along the axis
+                    let peak = points.iter().map(|(_, value)| *value).fold(0f32, f32::max).max(f32::EPSILON);
+                    let position = |tick: u64, value: f32| iced::Point::new(
+                        Self::PADDING + if last > first {
+                            width * (tick - first) as f32 / (last - first) as f32
+                        } else {
+                            width / 2f32
+                        },
+                        Self::PADDING + height * (1f32 - value / peak)
+                    );
+
+                    frame.stroke(
+                        &canvas::Path::new(|path| {
+                            for (index, (tick, value)) in points.iter().enumerate() {
+                                if index == 0 {
+                                    path.move_to(position(*tick, *value));
+                                } else {
+                                    path.line_to(position(*tick, *value));
+                                }
+                            }
+                        } ),
+                        canvas::Stroke::default().with_width(Self::LINE_WIDTH).with_color(color)
+                    );
+
+                    // the legend lists each series beside its peak, down the right edge
+                    frame.fill_text(canvas::Text {
+                        content: format!("{} (peak {:.1})", series, peak),
+                        position: iced::Point::new(
+                            Self::PADDING + width,
+                            Self::PADDING + index as f32 * Self::LABEL_SIZE * 1.25f32),
+                        color,
+                        size: Self::LABEL_SIZE,
+                        horizontal_alignment: iced::alignment::Horizontal::Right,
+                        ..canvas::Text::default()
+                    } );
+                }
+            })
+        ]
+    }
+}
@@ -0,0 +1,103 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::interface::Message;
+use crate::simulation::Simulation;
+
+// The open Simulations, each with its own settings and seed, so different parameterizations can be compared
+// Only the shown Simulation steps, the others wait where they were left
+pub(crate) struct Tabs {
+    simulations: Vec<Rc<RefCell<Simulation>>>,
+    active: usize,
+    state_tabs: Vec<iced::button::State>,
+    state_new: iced::button::State,
+    state_close: iced::button::State
+}
+
+impl Tabs {
+    const PADDING: u16 = 10;
+
+    pub(crate) fn new(simulation: Rc<RefCell<Simulation>>) -> Self {
+        Self {
+            simulations: vec![simulation],
+            active: 0,
+            state_tabs: vec![iced::button::State::default()],
+            state_new: iced::button::State::default(),
+            state_close: iced::button::State::default()
+        }
+    }
+
+    // The Simulation shown on the Canvas
+    pub(crate) fn active(&self) -> Rc<RefCell<Simulation>> {
+        Rc::clone(&self.simulations[self.active])
+    }
+
+    // Adds a tab after the others and shows it
+    pub(crate) fn open(&mut self, simulation: Simulation) -> Rc<RefCell<Simulation>> {
+        self.simulations.push(Rc::new(RefCell::new(simulation)));
+        self.state_tabs.push(iced::button::State::default());
+        self.active = self.simulations.len() - 1;
+
+        self.active()
+    }
+
+    // Closes the shown tab and shows the one before it
+    // Returns None if it is the only tab, as there would be nothing left to show
+    pub(crate) fn close(&mut self) -> Option<Rc<RefCell<Simulation>>> {
+        if self.simulations.len() == 1 {
+            return None;
+        }
+
+        self.simulations.remove(self.active);
+        self.state_tabs.remove(self.active);
+        self.active = self.active.saturating_sub(1);
+
+        Some(self.active())
+    }
+
+    // Returns None if the tab doesn't exist or is already shown
+    pub(crate) fn switch(&mut self, index: usize) -> Option<Rc<RefCell<Simulation>>> {
+        if index == self.active || index >= self.simulations.len() {
+            return None;
+        }
+
+        self.active = index;
+        Some(self.active())
+    }
+
+    pub(crate) fn view(&mut self) -> iced::Element<'_, Message> {
+        use Message::*;
+        let mut tabs = iced::Row::new().spacing(Self::PADDING);
+
+        for (index, (simulation, state)) in self.simulations.iter().zip(self.state_tabs.iter_mut()).enumerate() {
+            // the shown tab is marked, rather than disabled, so it still looks like a tab
+            let label = match simulation.try_borrow().ok().and_then(|simulation| simulation.settings().seed()) {
+                Some(seed) => format!("{} (seed {})", index + 1, seed),
+                None => format!("{}", index + 1)
+            };
+
+            tabs = tabs.push(
+                iced::Button::new(
+                    state,
+                    iced::Text::new(if index == self.active { format!("[{}]", label) } else { label }))
+                    .on_press(SwitchTab(index)));
+        }
+
+        let mut close = iced::Button::new(
+            &mut self.state_close,
+            iced::Text::new("Close Tab"));
+
+        if self.simulations.len() > 1 {
+            close = close.on_press(CloseTab);
+        }
+
+        tabs
+            .push(
+                iced::Button::new(
+                    &mut self.state_new,
+                    iced::Text::new("New Tab"))
+                    .on_press(NewTab))
+            .push(close)
+            .into()
+    }
+}
@@ -0,0 +1,143 @@
+// The colors the world is drawn in, and the colors of the widgets around it
+// The chosen Theme is saved with the session, so a custom palette can be made by editing session.json
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub(crate) struct Theme {
+    pub(crate) name: String,
+    // the ground, which the tooltip's backdrop also uses
+    pub(crate) empty: [u8; 3],
+    pub(crate) food: [u8; 3],
+    pub(crate) toxic_food: [u8; 3],
+    // unmarked Agents are colored by species, species 0 keeps the original agent color
+    pub(crate) species: [[u8; 3]; 4],
+    pub(crate) markers: [[u8; 3]; 4],
+    pub(crate) wall: [u8; 3],
+    pub(crate) water: [u8; 3],
+    pub(crate) barrier: [u8; 3],
+    pub(crate) cold: [u8; 3],
+    pub(crate) hot: [u8; 3],
+    // outlines and labels drawn over the world
+    pub(crate) highlight: [u8; 3],
+    // behind and on the widgets
+    pub(crate) background: [u8; 3],
+    pub(crate) text: [u8; 3]
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
+
+impl Theme {
+    pub(crate) fn dark() -> Self {
+        Self {
+            name: String::from("Dark"),
+            empty: [0x1A, 0x1A, 0x1A],
+            food: [0xFF, 0x50, 0x50],
+            toxic_food: [0xA0, 0xE0, 0x30],
+            species: [
+                [0x64, 0x64, 0xFF],
+                [0xFF, 0x8C, 0x3C],
+                [0xE6, 0xE6, 0xE6],
+                [0x96, 0x5A, 0x32]
+            ],
+            markers: [
+                [0x50, 0xFF, 0x78],
+                [0xFF, 0xD2, 0x3C],
+                [0xDC, 0x64, 0xFF],
+                [0x3C, 0xE6, 0xFF]
+            ],
+            wall: [0x5A, 0x5A, 0x5A],
+            water: [0x3C, 0x78, 0xDC],
+            barrier: [0xC8, 0xC8, 0x50],
+            cold: [0x50, 0x8C, 0xFF],
+            hot: [0xFF, 0x78, 0x32],
+            highlight: [0xFF, 0xFF, 0xFF],
+            background: [0x26, 0x26, 0x26],
+            text: [0xE6, 0xE6, 0xE6]
+        }
+    }
+
+    pub(crate) fn light() -> Self {
+        Self {
+            name: String::from("Light"),
+            empty: [0xF0, 0xF0, 0xF0],
+            food: [0xE0, 0x3C, 0x3C],
+            toxic_food: [0x6E, 0x9E, 0x14],
+            species: [
+                [0x3C, 0x3C, 0xC8],
+                [0xD2, 0x69, 0x1E],
+                [0x50, 0x50, 0x50],
+                [0x8B, 0x45, 0x13]
+            ],
+            markers: [
+                [0x14, 0xA0, 0x3C],
+                [0xC8, 0x96, 0x00],
+                [0xA0, 0x3C, 0xC8],
+                [0x00, 0x96, 0xB4]
+            ],
+            wall: [0x8C, 0x8C, 0x8C],
+            water: [0x28, 0x64, 0xC8],
+            barrier: [0xA0, 0x8C, 0x00],
+            cold: [0x1E, 0x64, 0xDC],
+            hot: [0xDC, 0x50, 0x14],
+            highlight: [0x00, 0x00, 0x00],
+            background: [0xFF, 0xFF, 0xFF],
+            text: [0x1A, 0x1A, 0x1A]
+        }
+    }
+
+    // Saturated colors on black, so that no two kinds of Tile are told apart by shade alone
+    pub(crate) fn high_contrast() -> Self {
+        Self {
+            name: String::from("High Contrast"),
+            empty: [0x00, 0x00, 0x00],
+            food: [0xFF, 0x00, 0x00],
+            toxic_food: [0x00, 0xFF, 0x00],
+            species: [
+                [0x40, 0x80, 0xFF],
+                [0xFF, 0x80, 0x00],
+                [0xFF, 0xFF, 0xFF],
+                [0xFF, 0x00, 0xFF]
+            ],
+            markers: [
+                [0xFF, 0xFF, 0x00],
+                [0x00, 0xFF, 0xFF],
+                [0xFF, 0x80, 0xFF],
+                [0x80, 0xFF, 0x80]
+            ],
+            wall: [0x9A, 0x9A, 0x9A],
+            water: [0x00, 0x50, 0xC0],
+            barrier: [0xFF, 0xFF, 0x00],
+            cold: [0x00, 0xC0, 0xFF],
+            hot: [0xFF, 0x60, 0x00],
+            highlight: [0xFF, 0xFF, 0xFF],
+            background: [0x00, 0x00, 0x00],
+            text: [0xFF, 0xFF, 0xFF]
+        }
+    }
+
+    // The preset after this one, a custom palette is followed by the first preset
+    pub(crate) fn next(&self) -> Self {
+        let presets = [Self::dark(), Self::light(), Self::high_contrast()];
+        match presets.iter().position(|preset| preset.name == self.name) {
+            Some(index) => presets[(index + 1) % presets.len()].clone(),
+            None => presets[0].clone()
+        }
+    }
+
+    pub(crate) fn color(color: [u8; 3]) -> iced::Color {
+        iced::Color::from_rgb8(color[0], color[1], color[2])
+    }
+}
+
+// Colors the area behind the widgets, and the text on it
+impl iced::container::StyleSheet for Theme {
+    fn style(&self) -> iced::container::Style {
+        iced::container::Style {
+            text_color: Some(Self::color(self.text)),
+            background: Some(Self::color(self.background).into()),
+            ..iced::container::Style::default()
+        }
+    }
+}
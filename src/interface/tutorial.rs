@@ -0,0 +1,65 @@
+use crate::interface::{InspectorPane, Message};
+use crate::simulation::TileSummary;
+
+// Walks a new user through the interface, one instruction at a time
+// Each stage is completed by the user performing the action it describes
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Tutorial {
+    stage: Stage
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Stage {
+    SelectAgent,
+    InspectBrain,
+    // the tick at which fast-forwarding began
+    FastForward(u64),
+    SaveGenome,
+    Finished
+}
+
+impl Tutorial {
+    // the number of steps the user is asked to advance the world
+    const FAST_FORWARD_STEPS: u64 = 100;
+
+    pub(crate) fn new() -> Self {
+        Self { stage: Stage::SelectAgent }
+    }
+
+    pub(crate) fn finished(&self) -> bool {
+        self.stage == Stage::Finished
+    }
+
+    // Moves to the next stage if the message completes the current one
+    // `selection` and `tick` describe the Interface after the message was handled
+    pub(crate) fn advance(&mut self, message: &Message, selection: Option<InspectorPane>, tick: u64) {
+        use Stage::*;
+        self.stage = match (self.stage, message) {
+            (SelectAgent, Message::InspectorTarget(_, TileSummary::Agent(..))) => InspectBrain,
+            (InspectBrain, Message::InspectorPaneChange(InspectorPane::Brain)) => FastForward(tick),
+            (FastForward(start), Message::Step | Message::StepN(..)) if tick >= start + Self::FAST_FORWARD_STEPS => SaveGenome,
+            (SaveGenome, Message::InspectorCopy | Message::InspectorExport)
+                if selection == Some(InspectorPane::Genome) => Finished,
+            (stage, _) => stage
+        };
+    }
+
+    pub(crate) fn instructions(&self, tick: u64) -> String {
+        use Stage::*;
+        let (number, instruction) = match self.stage {
+            SelectAgent => (1, String::from(
+                "Click on one of the blue circles in the world to select an agent")),
+            InspectBrain => (2, String::from(
+                "Choose Brain from the list above to see the network that decides the agent's actions")),
+            FastForward(start) => (3, format!(
+                "Press Step, or a key other than the arrows or N while the world is focused, to advance it, {} steps to go",
+                (start + Self::FAST_FORWARD_STEPS).saturating_sub(tick))),
+            SaveGenome => (4, String::from(
+                "Choose Genome from the list, then Copy it to the clipboard or Export it to the path below")),
+            Finished => return String::from(
+                "Tutorial complete! Import Genome adds a copied genome back into the world")
+        };
+
+        format!("Tutorial {}/4: {}", number, instruction)
+    }
+}
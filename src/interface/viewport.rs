@@ -0,0 +1,120 @@
+// The region of the world shown on the Canvas
+// Scrolling zooms in on the Tile beneath the cursor, and dragging with the right or middle button pans
+// Positions are fractions of the world or the Canvas, so the view survives the window being resized
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Viewport {
+    zoom: f32,
+    // the point of the world at the center of the Canvas
+    center: iced::Point,
+    // where the cursor was on the Canvas when the view was last panned, None unless panning
+    anchor: Option<iced::Point>
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self {
+            zoom: 1f32,
+            center: iced::Point::new(0.5f32, 0.5f32),
+            anchor: None
+        }
+    }
+}
+
+impl Viewport {
+    const MAX_ZOOM: f32 = 16f32;
+    // following an Agent zooms in at least this far, so the view has room to move
+    pub(crate) const FOLLOW_ZOOM: f32 = 4f32;
+    // each line scrolled zooms by this factor
+    const ZOOM_STEP: f32 = 1.2f32;
+    // scrolling by pixels is converted to lines
+    const PIXELS_PER_LINE: f32 = 40f32;
+
+    pub(crate) fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    pub(crate) fn panning(&self) -> bool {
+        self.anchor.is_some()
+    }
+
+    // The distance between the top left corner of the world and that of the Canvas, in pixels
+    pub(crate) fn origin(&self, bounds: iced::Size) -> iced::Vector {
+        iced::Vector::new(
+            self.center.x * bounds.width * self.zoom - bounds.width / 2f32,
+            self.center.y * bounds.height * self.zoom - bounds.height / 2f32
+        )
+    }
+
+    // Zooms by the given number of lines, keeping the point of the world beneath the cursor in place
+    pub(crate) fn zoom_at(&mut self, lines: f32, cursor: iced::Point) {
+        let x = self.center.x + (cursor.x - 0.5f32) / self.zoom;
+        let y = self.center.y + (cursor.y - 0.5f32) / self.zoom;
+
+        self.zoom = (self.zoom * Self::ZOOM_STEP.powf(lines)).clamp(1f32, Self::MAX_ZOOM);
+        self.center = iced::Point::new(
+            x - (cursor.x - 0.5f32) / self.zoom,
+            y - (cursor.y - 0.5f32) / self.zoom
+        );
+
+        self.clamp();
+    }
+
+    pub(crate) fn zoom_to(&mut self, zoom: f32) {
+        self.zoom = zoom.clamp(1f32, Self::MAX_ZOOM);
+        self.clamp();
+    }
+
+    // Moves the view so the Tile is as close to the center of the Canvas as the edges allow
+    pub(crate) fn center_on(&mut self, coord: crate::tile::coord::Coord, dimensions: iced::Size<usize>) {
+        self.center = iced::Point::new(
+            (coord.x as f32 + 0.5f32) / dimensions.width.max(1) as f32,
+            (coord.y as f32 + 0.5f32) / dimensions.height.max(1) as f32
+        );
+
+        self.clamp();
+    }
+
+    // Moves the view so the point of the world, given as a fraction of it, is as close to the center as the edges allow
+    pub(crate) fn look_at(&mut self, point: iced::Point) {
+        self.center = point;
+        self.clamp();
+    }
+
+    pub(crate) fn scroll(&mut self, delta: iced::mouse::ScrollDelta, cursor: iced::Point) {
+        use iced::mouse::ScrollDelta::*;
+        match delta {
+            Lines { y, .. } => self.zoom_at(y, cursor),
+            Pixels { y, .. } => self.zoom_at(y / Self::PIXELS_PER_LINE, cursor)
+        }
+    }
+
+    pub(crate) fn start_pan(&mut self, cursor: iced::Point) {
+        self.anchor = Some(cursor);
+    }
+
+    // Drags the world along with the cursor
+    pub(crate) fn pan(&mut self, cursor: iced::Point) {
+        if let Some(anchor) = self.anchor {
+            self.center = iced::Point::new(
+                self.center.x - (cursor.x - anchor.x) / self.zoom,
+                self.center.y - (cursor.y - anchor.y) / self.zoom
+            );
+
+            self.anchor = Some(cursor);
+            self.clamp();
+        }
+    }
+
+    pub(crate) fn end_pan(&mut self) {
+        self.anchor = None;
+    }
+
+    // The view never strays past the edges of the world
+    fn clamp(&mut self) {
+        let margin = 0.5f32 / self.zoom;
+        self.center = iced::Point::new(
+            self.center.x.clamp(margin, 1f32 - margin),
+            self.center.y.clamp(margin, 1f32 - margin)
+        );
+    }
+}
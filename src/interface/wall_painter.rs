@@ -0,0 +1,111 @@
+use crate::interface::Message;
+
+// What a click on the Canvas does to the Tile beneath it
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub(crate) enum Brush {
+    // clicks inspect the Tile, as they do without the painter
+    #[default]
+    Off,
+    // builds walls on empty Tiles
+    Wall,
+    // tears down walls, leaving every other Tile alone
+    Erase,
+    // the left button adds food and the right button clears Tiles,
+    // shift-clicking spawns a random Agent
+    Edit
+}
+
+impl Brush {
+    fn next(self) -> Self {
+        use Brush::*;
+        match self {
+            Off => Wall,
+            Wall => Erase,
+            Erase => Edit,
+            Edit => Off
+        }
+    }
+}
+
+// The change made to each Tile a stroke passes over
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Stroke {
+    Build,
+    Erase,
+    // adds a unit of food
+    Feed,
+    // removes food, walls and water, but never Agents
+    Clear,
+    // only ever applied to the clicked Tile
+    Spawn
+}
+
+impl Stroke {
+    // Returns None if the button does nothing with the given brush
+    pub(crate) fn new(brush: Brush, button: iced::mouse::Button, shift: bool) -> Option<Self> {
+        use iced::mouse::Button::*;
+        match (brush, button) {
+            (Brush::Wall, Left) => Some(Stroke::Build),
+            (Brush::Erase, Left) => Some(Stroke::Erase),
+            (Brush::Edit, Left) if shift => Some(Stroke::Spawn),
+            (Brush::Edit, Left) => Some(Stroke::Feed),
+            (Brush::Edit, Right) => Some(Stroke::Clear),
+            _ => None
+        }
+    }
+}
+
+// Everything the Canvas needs to know to turn mouse events into strokes
+#[derive(Debug, Copy, Clone, Default)]
+pub(crate) struct Painting {
+    pub(crate) brush: Brush,
+    // the stroke in progress, between pressing and releasing the button
+    pub(crate) stroke: Option<Stroke>,
+    pub(crate) shift: bool
+}
+
+// Paints walls, food and Agents onto the world at runtime, so users can build mazes and corridors
+// While a brush is selected, dragging across the Canvas paints every Tile the cursor passes over
+#[derive(Default)]
+pub(crate) struct WallPainter {
+    pub(crate) brush: Brush,
+    pub(crate) stroke: Option<Stroke>,
+    // the latest Tile painted by the stroke in progress
+    pub(crate) last: Option<crate::tile::coord::Coord>,
+    // whether either shift key is held
+    pub(crate) shift: bool,
+    state_toggle: iced::button::State
+}
+
+impl WallPainter {
+    pub(crate) fn cycle(&mut self) {
+        self.brush = self.brush.next();
+        self.stroke = None;
+        self.last = None;
+    }
+
+    pub(crate) fn painting(&self) -> Painting {
+        Painting {
+            brush: self.brush,
+            stroke: self.stroke,
+            shift: self.shift
+        }
+    }
+
+    pub(crate) fn view(&mut self) -> iced::Element<'_, Message> {
+        use iced::Length;
+
+        use Brush::*;
+        iced::Button::new(
+            &mut self.state_toggle,
+            iced::Text::new(match self.brush {
+                Off => "Paint: Off",
+                Wall => "Paint: Build Walls",
+                Erase => "Paint: Erase Walls",
+                Edit => "Paint: Food and Agents"
+            } ))
+            .width(Length::Fill)
+            .on_press(Message::CycleBrush)
+            .into()
+    }
+}
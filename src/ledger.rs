@@ -0,0 +1,170 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+
+// Energy is held in two pools: food on the map, and the energy of living agents
+// Every change to either pool is booked as one of these flows
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+pub enum Flow {
+    // food placed by agents
+    Produced,
+    // food left behind by the dead, in proportion to their fitness
+    Remains,
+    // energy restored to agents by eating
+    Sated,
+    // the starting energy of newborn and imported agents
+    Born,
+    // food placed by hand
+    Placed,
+    // food removed by agents eating it
+    Eaten,
+    // food that decayed away
+    Decayed,
+    // food that diffused into a tile held by an agent or a wall
+    Spilled,
+    // energy drained by actions and by running the brain
+    Spent,
+    // energy held by agents when they died
+    Died,
+    // food erased by hand
+    Cleared
+}
+
+impl Flow {
+    // sources add energy to the world, every other flow removes it
+    pub fn source(&self) -> bool {
+        use Flow::*;
+        matches!(self, Produced | Remains | Sated | Born | Placed)
+    }
+}
+
+impl fmt::Display for Flow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Flow::*;
+        write!(f, "{}", match self {
+            Produced => "food produced",
+            Remains => "food from remains",
+            Sated => "energy from eating",
+            Born => "energy of new agents",
+            Placed => "food placed by hand",
+            Eaten => "food eaten",
+            Decayed => "food decayed",
+            Spilled => "food spilled onto agents and walls",
+            Spent => "energy spent",
+            Died => "energy lost at death",
+            Cleared => "food cleared by hand"
+        } )
+    }
+}
+
+// The flows booked during a single step, along with the world's energy either side of it
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Entry {
+    pub tick: u64,
+    pub opening: u64,
+    pub closing: u64,
+    pub flows: BTreeMap<Flow, u64>
+}
+
+impl Entry {
+    // the change in energy predicted by the booked flows
+    pub fn net(&self) -> i64 {
+        self.flows.iter().fold(0i64, |net, (flow, amount)| {
+            if flow.source() { net + *amount as i64 } else { net - *amount as i64 }
+        } )
+    }
+
+    // energy that appeared or vanished without being booked, zero when the step balances
+    pub fn discrepancy(&self) -> i64 {
+        self.closing as i64 - self.opening as i64 - self.net()
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Step {}\n  opening energy: {}", self.tick, self.opening)?;
+        for (flow, amount) in self.flows.iter() {
+            writeln!(f, "  {} {}: {}", if flow.source() { '+' } else { '-' }, flow, amount)?;
+        }
+
+        write!(f, "  closing energy: {}", self.closing)?;
+        match self.discrepancy() {
+            0 => write!(f, "\n  balanced"),
+            discrepancy => write!(f, "\n  UNBALANCED by {:+}", discrepancy)
+        }
+    }
+}
+
+// Books every energy flow as it happens, closing an Entry at the end of each step
+// Flows booked between steps (such as imports) belong to the following step
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Ledger {
+    // the world's energy when the pending flows started being booked
+    opening: u64,
+    pending: BTreeMap<Flow, u64>,
+    entries: VecDeque<Entry>
+}
+
+impl Ledger {
+    pub const CAPACITY: usize = 1024;
+
+    pub fn new(opening: u64) -> Self {
+        Self {
+            opening,
+            ..Self::default()
+        }
+    }
+
+    pub fn book(&mut self, flow: Flow, amount: u64) {
+        if amount > 0 {
+            *self.pending.entry(flow).or_insert(0) += amount;
+        }
+    }
+
+    // `closing` is the world's energy at the end of the step
+    pub fn close(&mut self, tick: u64, closing: u64) {
+        if self.entries.len() == Self::CAPACITY {
+            self.entries.pop_front();
+        }
+
+        self.entries.push_back(Entry {
+            tick,
+            opening: self.opening,
+            closing,
+            flows: std::mem::take(&mut self.pending)
+        } );
+
+        self.opening = closing;
+    }
+
+    // Nothing has been booked or closed, as in a new Ledger
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty() && self.entries.is_empty()
+    }
+
+    pub fn latest(&self) -> Option<&Entry> {
+        self.entries.back()
+    }
+
+    // Entries that failed the audit, oldest first
+    pub fn unbalanced(&self) -> impl Iterator<Item = &Entry> {
+        self.entries.iter().filter(|entry| entry.discrepancy() != 0)
+    }
+
+    // Checks that every change in energy over the retained steps was booked
+    pub fn audit(&self) -> String {
+        let latest = match self.latest() {
+            Some(latest) => latest,
+            None => return String::from("No steps have been audited")
+        };
+
+        let unbalanced = self.unbalanced().collect::<Vec<&Entry>>();
+        let mut audit = format!("{}\n\n{} of the last {} steps balanced", latest, self.entries.len() - unbalanced.len(), self.entries.len());
+
+        // the most recent failures are the most useful
+        for entry in unbalanced.iter().rev().take(5) {
+            audit.push_str(&format!("\n  step {} is unbalanced by {:+}", entry.tick, entry.discrepancy()));
+        }
+
+        audit
+    }
+}
@@ -0,0 +1,44 @@
+//! The engine behind the evolution simulation, usable without its interface.
+//!
+//! A [`Simulation`] is a grid of [`tile::Tile`]s holding creatures, food, walls and water.
+//! It is built from [`SimulationSettings`] and advanced one step at a time with [`Simulation::step`].
+//! Each creature is an [`Agent`], whose brain is built from a genome of 8-bit genes, see [`Genome`].
+//!
+//! ```no_run
+//! use evolution_simulation::{Simulation, SimulationSettings};
+//!
+//! let mut simulation = Simulation::new(SimulationSettings::default());
+//! while simulation.tick() < 1000 && !simulation.agents().is_empty() {
+//!     simulation.step();
+//! }
+//!
+//! println!("{} agents survived", simulation.agents().len());
+//! ```
+
+pub mod agent;
+pub mod error;
+pub mod tile;
+pub mod simulation;
+pub mod logging;
+pub mod interaction;
+pub mod event;
+pub mod recording;
+pub mod manifest;
+pub mod ledger;
+pub mod benchmark;
+pub mod morgue;
+pub mod climate;
+pub mod weather;
+pub mod pulse;
+pub mod motif;
+pub mod snapshot;
+pub mod stats;
+pub mod platform;
+pub mod selftest;
+pub mod experiments;
+#[cfg(feature = "pyo3")]
+pub mod python;
+
+pub use agent::Agent;
+pub use agent::gene::Genome;
+pub use simulation::{Simulation, SimulationSettings};
@@ -0,0 +1,73 @@
+use std::fmt;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use tracing::field::{Field, Visit};
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Where per-run log files are written.
+const LOG_DIRECTORY: &str = "logs";
+
+/// The number of recent log lines kept in memory for the interface.
+const BUFFER_SIZE: usize = 256;
+
+static BUFFER: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Installs the global subscriber.
+/// Events are written to a new file in LOG_DIRECTORY and kept in an in-memory buffer.
+/// If the log file can't be created, only the buffer is used.
+pub fn init() {
+    // creating the file fails without the fs feature, as in the browser
+    let file = crate::platform::create_dir_all(LOG_DIRECTORY).ok()
+        .and_then(|_| crate::platform::create(format!("{}/run-{}.log", LOG_DIRECTORY, crate::platform::unix_time())).ok())
+        .map(|file| {
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(Mutex::new(file))
+        } );
+
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::filter::LevelFilter::INFO)
+        .with(file)
+        .with(BufferLayer)
+        .init();
+}
+
+/// Returns the most recent log lines, oldest first.
+pub fn recent() -> Vec<String> {
+    match BUFFER.lock() {
+        Ok(buffer) => buffer.iter().cloned().collect(),
+        Err(..) => Vec::new()
+    }
+}
+
+// Formats each event onto a single line and stores it in BUFFER
+struct BufferLayer;
+
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for BufferLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let mut line = LineVisitor(format!("{} ", event.metadata().level()));
+        event.record(&mut line);
+
+        if let Ok(mut buffer) = BUFFER.lock() {
+            if buffer.len() == BUFFER_SIZE {
+                buffer.pop_front();
+            }
+
+            buffer.push_back(line.0.trim_end().to_string());
+        }
+    }
+}
+
+struct LineVisitor(String);
+
+impl Visit for LineVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.0.push_str(&format!("{:?} ", value));
+        } else {
+            self.0.push_str(&format!("{}={:?} ", field.name(), value));
+        }
+    }
+}
@@ -1,11 +1,70 @@
-mod agent;
-mod tile;
-mod simulation;
+// the interface is a thin layer over the engine, which lives in the library
+// its modules are imported here so that the interface can keep naming them through `crate::`
+use evolution_simulation::{agent, error, tile, simulation, logging, interaction, event, recording, manifest};
+use evolution_simulation::{benchmark, morgue, climate, weather, pulse, motif, snapshot, stats, platform, selftest, experiments};
+
+#[cfg(not(target_arch = "wasm32"))]
 mod interface;
+#[cfg(target_arch = "wasm32")]
+mod web;
+#[cfg(not(target_arch = "wasm32"))]
+mod headless;
 
-use iced::Sandbox;
+#[cfg(not(target_arch = "wasm32"))]
+use iced::Application;
 
+#[cfg(not(target_arch = "wasm32"))]
 pub fn main() -> iced::Result {
+    logging::init();
+
+    // `selftest` runs the self-test suite instead of opening the interface
+    if std::env::args().nth(1).as_deref() == Some("selftest") {
+        let report = selftest::run();
+        println!("{}", report);
+        std::process::exit(if report.passed() { 0 } else { 1 });
+    }
+
+    // `experiment` runs a batch of worlds without the interface, see headless::ExperimentOptions
+    if std::env::args().nth(1).as_deref() == Some(headless::ExperimentOptions::COMMAND) {
+        let finished = headless::ExperimentOptions::parse(std::env::args().skip(1))
+            .and_then(|options| headless::run_experiments(&options));
+
+        match finished {
+            Ok(results) => println!("{}", results),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+
+        return Ok(());
+    }
+
+    // `--headless` steps a world without the interface, see headless::Options
+    if std::env::args().any(|arg| arg == headless::Options::FLAG) {
+        let finished = headless::Options::parse(std::env::args().skip(1))
+            .and_then(|options| headless::run(&options));
+
+        match finished {
+            Ok(summary) => println!("{}", summary),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+
+        return Ok(());
+    }
+
     interface::Interface::run(iced::Settings::default())
 
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn main() {
+    logging::init();
+
+    if let Err(e) = web::start() {
+        tracing::error!("failed to start the web front-end: {:?}", e);
+    }
 }
\ No newline at end of file
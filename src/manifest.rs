@@ -0,0 +1,49 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::LoadError;
+use crate::simulation::SimulationSettings;
+
+// Everything needed to reconstruct a run's starting configuration
+// Settings are recorded after the seed is resolved, so unseeded runs can be reproduced too
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Manifest {
+    pub version: String,
+    // absent when the engine was built outside of a git checkout
+    pub commit: Option<String>,
+    pub settings: SimulationSettings
+}
+
+impl Manifest {
+    // manifests are written here, relative to the working directory
+    pub const DIRECTORY: &'static str = "runs";
+
+    pub fn new(settings: SimulationSettings) -> Self {
+        Self {
+            version: String::from(env!("CARGO_PKG_VERSION")),
+            commit: option_env!("GIT_HASH").map(String::from),
+            settings
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+        let contents = crate::platform::read_to_string(path).map_err(LoadError::Io)?;
+        serde_json::from_str(&contents).map_err(LoadError::Parse)
+    }
+
+    // Writes the manifest into DIRECTORY, returning the path of the new file
+    pub fn write(&self) -> std::io::Result<PathBuf> {
+        crate::platform::create_dir_all(Self::DIRECTORY)?;
+
+        let started = crate::platform::unix_time();
+
+        let path = Path::new(Self::DIRECTORY).join(format!("run-{}-{}.json",
+            started,
+            self.settings.seed().unwrap_or(0)));
+
+        // serializing plain settings can't fail
+        let contents = serde_json::to_string_pretty(self).unwrap();
+        crate::platform::write(&path, contents)?;
+
+        Ok(path)
+    }
+}
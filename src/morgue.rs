@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::agent::gene;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Cause {
+    Killed { killer: u64 },
+    Starved,
+    Dehydrated,
+    // walked off a deadly edge of the world
+    FellOffEdge,
+    // removed by hand
+    Removed
+}
+
+impl fmt::Display for Cause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Cause::*;
+        match self {
+            Killed { killer } => write!(f, "killed by agent {}", killer),
+            Starved => write!(f, "starved"),
+            Dehydrated => write!(f, "died of thirst"),
+            FellOffEdge => write!(f, "fell off the edge of the world"),
+            Removed => write!(f, "removed by hand")
+        }
+    }
+}
+
+// The genome and life summary of a dead Agent
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Obituary {
+    pub id: u64,
+    pub marker: Option<u8>,
+    // the tick of the Agent's death
+    pub tick: u64,
+    pub age: u64,
+    pub offspring: u32,
+    pub fitness: u8,
+    pub cause: Cause,
+    pub genome: Vec<gene::Gene>
+}
+
+impl fmt::Display for Obituary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Agent {}{} {} at step {}, aged {} with {} offspring and fitness {}\n{}",
+            self.id,
+            match self.marker {
+                Some(marker) => format!(" (lineage {})", marker),
+                None => String::new()
+            },
+            self.cause,
+            self.tick,
+            self.age,
+            self.offspring,
+            self.fitness,
+            gene::Genome::get(self.genome.clone()))
+    }
+}
+
+// A ring buffer of the most recently deceased, oldest first
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Morgue {
+    obituaries: VecDeque<Obituary>
+}
+
+impl Morgue {
+    pub const CAPACITY: usize = 512;
+
+    pub fn record(&mut self, obituary: Obituary) {
+        if self.obituaries.len() == Self::CAPACITY {
+            self.obituaries.pop_front();
+        }
+
+        self.obituaries.push_back(obituary);
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Obituary> {
+        self.obituaries.iter().find(|obituary| obituary.id == id)
+    }
+
+    // Obituaries containing every word of the query, newest first
+    // Words are matched against the summary and the genome, so `killed lineage 2` or a run of genes both work
+    pub fn search(&self, query: &str) -> Vec<&Obituary> {
+        let words = query.split_whitespace()
+            .map(|word| word.to_lowercase())
+            .collect::<Vec<String>>();
+
+        self.obituaries.iter().rev().filter(|obituary| {
+            let text = obituary.to_string().to_lowercase();
+            words.iter().all(|word| text.contains(word.as_str()))
+        } ).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.obituaries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.obituaries.is_empty()
+    }
+
+    // An estimate of the bytes held by the morgue, including each genome
+    pub fn memory(&self) -> usize {
+        use std::mem::size_of;
+
+        self.obituaries.capacity() * size_of::<Obituary>()
+            + self.obituaries.iter().map(|obituary| obituary.genome.capacity() * size_of::<gene::Gene>()).sum::<usize>()
+    }
+}
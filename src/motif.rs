@@ -0,0 +1,317 @@
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use crate::agent::gene::ActionType;
+use crate::event::EventKind;
+use crate::simulation::Simulation;
+use crate::tile;
+use crate::tile::coord;
+
+// Emergent behaviors the MotifScanner knows how to recognize
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Motif {
+    // Agents that keep moving but never leave a handful of Tiles
+    Circling,
+    // Agents that travel while staying beside walls or water
+    WallHugging,
+    // neighbors that produce food and eat it in the same small region
+    Farming,
+    // predators that rise and fall in step behind the prey they hunt
+    PredatorPrey
+}
+
+impl fmt::Display for Motif {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Motif::Circling => "Circling",
+            Motif::WallHugging => "Wall hugging",
+            Motif::Farming => "Farming cluster",
+            Motif::PredatorPrey => "Predator-prey oscillation"
+        } )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Finding {
+    pub motif: Motif,
+    pub tick: u64,
+    // where to look, naming Agents and Coords where possible
+    pub detail: String
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (step {}): {}", self.motif, self.tick, self.detail)
+    }
+}
+
+// The Tiles an Agent stood on during its most recent steps, newest last
+// Each is paired with whether it was beside a wall or water
+type Trace = VecDeque<(coord::Coord, bool)>;
+
+// What happened in one region of the world during the latest interval
+#[derive(Default)]
+struct Region {
+    producers: HashSet<u64>,
+    eaters: HashSet<u64>,
+    // units of food produced
+    produced: usize
+}
+
+// Follows every Agent's movements and the size of the predator and prey populations,
+// and periodically scans them for recognizable motifs
+#[derive(Default)]
+pub struct MotifScanner {
+    // keyed by Agent::id
+    traces: HashMap<u64, Trace>,
+    // the number of prey and predators after each step, oldest first
+    populations: VecDeque<(usize, usize)>,
+    // the findings of the latest scan
+    findings: Vec<Finding>
+}
+
+impl MotifScanner {
+    pub const INTERVAL: u64 = 50;
+
+    // the number of steps traced for each Agent
+    const TRACE: usize = 16;
+    // circling Agents stay within this many Tiles over their whole trace
+    const CIRCLE_TILES: usize = 4;
+    // wall huggers spend at least this fraction of their trace beside a wall
+    const HUG_FRACTION: f32 = 0.75;
+
+    // Agents that killed within this many steps count as predators
+    const PREDATOR_MEMORY: u64 = 20;
+    // the number of steps of population history analysed for oscillations
+    const WINDOW: usize = 400;
+    // each population must cross its mean this many times, two full cycles
+    const CROSSINGS: usize = 4;
+    // the lagged correlation needed to call the populations coupled
+    const COUPLING: f32 = 0.5;
+
+    // farming is looked for in square regions of this size
+    const REGION: usize = 8;
+    // the number of Agents that must both produce and eat within a region
+    const FARMERS: usize = 3;
+
+    // Records the latest step, then scans for motifs if one is due
+    pub fn poll(&mut self, simulation: &Simulation) {
+        self.trace(simulation);
+
+        let since = simulation.tick().saturating_sub(Self::PREDATOR_MEMORY);
+        let predators = simulation.events_since(since)
+            .filter_map(|event| match event.kind {
+                EventKind::Kill { killer, .. } => Some(killer),
+                _ => None
+            } )
+            .collect::<HashSet<u64>>()
+            .len();
+
+        if self.populations.len() == Self::WINDOW {
+            self.populations.pop_front();
+        }
+
+        let agents = simulation.agents().len();
+        self.populations.push_back((agents.saturating_sub(predators), predators.min(agents)));
+
+        if simulation.tick() > 0 && simulation.tick().is_multiple_of(Self::INTERVAL) {
+            self.findings = self.scan(simulation);
+        }
+    }
+
+    pub fn report(&self) -> String {
+        if self.findings.is_empty() {
+            return format!("No motifs were found in the latest scan, the world is scanned every {} steps", Self::INTERVAL);
+        }
+
+        self.findings.iter().fold(String::new(), |output, finding| {
+            output + &format!("{}\n\n", finding)
+        } ).trim_end().to_string()
+    }
+
+    fn trace(&mut self, simulation: &Simulation) {
+        let size = simulation.size();
+        let boundary = simulation.settings().boundary();
+
+        // a Tile is sheltered if it's beside something solid, solid edges included
+        let sheltered = |coord: coord::Coord| {
+            let neighbors = coord.neighbors(&size, coord::Neighborhood::VonNeumann, boundary);
+            (neighbors.len() < 4 && matches!(boundary, coord::BoundaryMode::Wall)) || neighbors.iter().any(|neighbor| {
+                simulation.exists(*neighbor) && matches!(simulation.get(*neighbor), tile::Tile::Wall | tile::Tile::Water)
+            } )
+        };
+
+        let mut living = HashSet::new();
+        for coord in simulation.agents() {
+            let id = match simulation.try_agent(coord) {
+                Ok(agent) => agent.id,
+                Err(..) => continue
+            };
+
+            let trace = self.traces.entry(id).or_default();
+            if trace.len() == Self::TRACE {
+                trace.pop_front();
+            }
+
+            trace.push_back((coord, sheltered(coord)));
+            living.insert(id);
+        }
+
+        self.traces.retain(|id, _| living.contains(id));
+    }
+
+    fn scan(&self, simulation: &Simulation) -> Vec<Finding> {
+        let tick = simulation.tick();
+
+        let mut findings = Vec::new();
+        findings.extend(self.circling(simulation).map(|detail| Finding { motif: Motif::Circling, tick, detail }));
+        findings.extend(self.wall_hugging().map(|detail| Finding { motif: Motif::WallHugging, tick, detail }));
+        findings.extend(self.farming(simulation).into_iter().map(|detail| Finding { motif: Motif::Farming, tick, detail }));
+        findings.extend(self.predator_prey().map(|detail| Finding { motif: Motif::PredatorPrey, tick, detail }));
+        findings
+    }
+
+    // Agents whose trace is full, and who moved, but only ever between a few Tiles
+    fn circling(&self, simulation: &Simulation) -> Option<String> {
+        let mut circling = self.full_traces()
+            .filter(|(_, trace)| (2..=Self::CIRCLE_TILES).contains(&Self::tiles(trace)))
+            .collect::<Vec<(u64, &Trace)>>();
+
+        circling.sort_by_key(|(id, _)| *id);
+        let (id, trace) = circling.first()?;
+
+        // the turns of the example Agent tell which way it circles
+        let coord = trace.back().unwrap().0;
+        let direction = simulation.try_agent(coord).ok().and_then(|agent| {
+            let left = agent.history.iter().filter(|(_, action, _)| *action == ActionType::TurnLeft).count();
+            let right = agent.history.iter().filter(|(_, action, _)| *action == ActionType::TurnRight).count();
+            match (left, right) {
+                (0, 0) => None,
+                (_, 0) => Some(" turning left"),
+                (0, _) => Some(" turning right"),
+                _ => None
+            }
+        } ).unwrap_or("");
+
+        Some(format!("{} {} within {} tiles over their last {} steps, e.g. agent {} at ({}, {}){}",
+            circling.len(),
+            if circling.len() == 1 { "agent keeps" } else { "agents keep" },
+            Self::CIRCLE_TILES,
+            Self::TRACE,
+            id,
+            coord.x,
+            coord.y,
+            direction))
+    }
+
+    // Agents that covered ground while spending most of their trace beside a wall
+    fn wall_hugging(&self) -> Option<String> {
+        let mut hugging = self.full_traces()
+            .filter(|(_, trace)| Self::tiles(trace) > Self::CIRCLE_TILES)
+            .filter(|(_, trace)| {
+                let sheltered = trace.iter().filter(|(_, sheltered)| *sheltered).count();
+                sheltered as f32 / trace.len() as f32 >= Self::HUG_FRACTION
+            } )
+            .collect::<Vec<(u64, &Trace)>>();
+
+        hugging.sort_by_key(|(id, _)| *id);
+        let (id, trace) = hugging.first()?;
+        let coord = trace.back().unwrap().0;
+
+        Some(format!("{} {} along walls or water, e.g. agent {} at ({}, {})",
+            hugging.len(),
+            if hugging.len() == 1 { "agent travels" } else { "agents travel" },
+            id,
+            coord.x,
+            coord.y))
+    }
+
+    // Regions where several Agents both produced food and ate recently
+    fn farming(&self, simulation: &Simulation) -> Vec<String> {
+        let since = simulation.tick().saturating_sub(Self::INTERVAL);
+
+        let mut regions: BTreeMap<(usize, usize), Region> = BTreeMap::new();
+        for event in simulation.events_since(since) {
+            let region = regions.entry((event.coord.x / Self::REGION, event.coord.y / Self::REGION)).or_default();
+            match event.kind {
+                EventKind::ProduceFood { agent } => {
+                    region.producers.insert(agent);
+                    region.produced += 1;
+                },
+                EventKind::Eat { agent } => {
+                    region.eaters.insert(agent);
+                },
+                _ => {  }
+            }
+        }
+
+        regions.into_iter().filter_map(|((x, y), region)| {
+            let farmers = region.producers.intersection(&region.eaters).count();
+            (farmers >= Self::FARMERS).then(|| format!("{} agents produced {} units of food and ate in the region from ({}, {}) to ({}, {}) over the last {} steps",
+                farmers,
+                region.produced,
+                x * Self::REGION,
+                y * Self::REGION,
+                ((x + 1) * Self::REGION).min(simulation.size().width) - 1,
+                ((y + 1) * Self::REGION).min(simulation.size().height) - 1,
+                Self::INTERVAL))
+        } ).collect()
+    }
+
+    // Both populations oscillate, and the predators follow the prey with a lag
+    fn predator_prey(&self) -> Option<String> {
+        if self.populations.len() < Self::WINDOW {
+            return None;
+        }
+
+        let prey = Self::normalize(self.populations.iter().map(|(prey, _)| *prey as f32).collect())?;
+        let predators = Self::normalize(self.populations.iter().map(|(_, predators)| *predators as f32).collect())?;
+
+        if Self::crossings(&prey) < Self::CROSSINGS || Self::crossings(&predators) < Self::CROSSINGS {
+            return None;
+        }
+
+        // the lag at which the predator population best tracks the prey population
+        let (lag, correlation) = (1..Self::WINDOW / 4).map(|lag| {
+            let pairs = prey.len() - lag;
+            let correlation = prey.iter().zip(predators[lag..].iter())
+                .map(|(prey, predators)| prey * predators)
+                .sum::<f32>() / pairs as f32;
+
+            (lag, correlation)
+        } ).max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+        (correlation >= Self::COUPLING).then(|| format!("predators follow the prey population {} steps behind (correlation {:.2}) over the last {} steps",
+            lag,
+            correlation,
+            Self::WINDOW))
+    }
+
+    fn full_traces(&self) -> impl Iterator<Item = (u64, &Trace)> {
+        self.traces.iter()
+            .filter(|(_, trace)| trace.len() == Self::TRACE)
+            .map(|(id, trace)| (*id, trace))
+    }
+
+    // The number of distinct Tiles in a trace
+    fn tiles(trace: &Trace) -> usize {
+        trace.iter().map(|(coord, _)| *coord).collect::<HashSet<coord::Coord>>().len()
+    }
+
+    // Rescales a series to a mean of 0 and a standard deviation of 1
+    // Returns None if it barely varies
+    fn normalize(series: Vec<f32>) -> Option<Vec<f32>> {
+        let mean = series.iter().sum::<f32>() / series.len() as f32;
+        let deviation = (series.iter().map(|value| (value - mean).powi(2)).sum::<f32>() / series.len() as f32).sqrt();
+        if deviation <= f32::EPSILON {
+            return None;
+        }
+
+        Some(series.into_iter().map(|value| (value - mean) / deviation).collect())
+    }
+
+    // The number of times a normalized series crosses its mean
+    fn crossings(series: &[f32]) -> usize {
+        series.windows(2).filter(|pair| (pair[0] < 0f32) != (pair[1] < 0f32)).count()
+    }
+}
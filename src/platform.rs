@@ -0,0 +1,108 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// The file access and clock used outside of the interface, which differ between the desktop and the browser
+// File access is gated behind the fs feature. Without it files are offered as downloads named after the path
+// in the browser, and nothing can be read back. Elsewhere, every file operation fails
+
+#[cfg(feature = "fs")]
+pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    std::fs::write(path, contents)
+}
+
+#[cfg(all(not(feature = "fs"), target_arch = "wasm32"))]
+pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    use wasm_bindgen::JsCast;
+
+    let failed = |e: wasm_bindgen::JsValue| io::Error::other(format!("{:?}", e));
+
+    let name = path.as_ref().file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("download");
+
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(contents.as_ref()));
+    let blob = web_sys::Blob::new_with_u8_array_sequence(&parts).map_err(failed)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(failed)?;
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| io::Error::other("the page has no document"))?;
+
+    let anchor = document.create_element("a")
+        .map_err(failed)?
+        .dyn_into::<web_sys::HtmlAnchorElement>()
+        .map_err(|_| io::Error::other("the page could not create a link"))?;
+
+    anchor.set_href(&url);
+    anchor.set_download(name);
+    anchor.click();
+
+    web_sys::Url::revoke_object_url(&url).map_err(failed)
+}
+
+#[cfg(all(not(feature = "fs"), not(target_arch = "wasm32")))]
+pub fn write(path: impl AsRef<Path>, _contents: impl AsRef<[u8]>) -> io::Result<()> {
+    Err(unsupported(path))
+}
+
+pub fn read_to_string(path: impl AsRef<Path>) -> io::Result<String> {
+    if cfg!(feature = "fs") {
+        std::fs::read_to_string(path)
+    } else {
+        Err(unsupported(path))
+    }
+}
+
+// Downloads aren't placed in directories, so there is nothing to create without the fs feature
+pub fn create_dir_all(path: impl AsRef<Path>) -> io::Result<()> {
+    if cfg!(feature = "fs") {
+        std::fs::create_dir_all(path)
+    } else {
+        Ok(())
+    }
+}
+
+// Creates the file, or truncates it if it exists
+pub fn create(path: impl AsRef<Path>) -> io::Result<fs::File> {
+    if cfg!(feature = "fs") {
+        fs::File::create(path)
+    } else {
+        Err(unsupported(path))
+    }
+}
+
+// Opens the file for appending, creating it if it doesn't exist
+pub fn append(path: impl AsRef<Path>) -> io::Result<fs::File> {
+    if cfg!(feature = "fs") {
+        fs::OpenOptions::new().create(true).append(true).open(path)
+    } else {
+        Err(unsupported(path))
+    }
+}
+
+// Where temporary files can be kept, None without the fs feature
+// The standard library panics when asked for it in the browser
+pub fn temp_dir() -> Option<PathBuf> {
+    cfg!(feature = "fs").then(std::env::temp_dir)
+}
+
+fn unsupported(path: impl AsRef<Path>) -> io::Error {
+    io::Error::new(io::ErrorKind::Unsupported,
+        format!("{} can't be accessed without the fs feature", path.as_ref().display()))
+}
+
+// The number of seconds since the Unix epoch
+// The standard library's clock panics in the browser, so the page's clock is used there
+#[cfg(not(target_arch = "wasm32"))]
+pub fn unix_time() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn unix_time() -> u64 {
+    (js_sys::Date::now() / 1000f64) as u64
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+
+// A global rule change that only lasts for a set number of steps
+#[derive(Debug, Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum Modifier {
+    // multiplies the mutation rate of every offspring
+    Mutation(f32),
+    // Kill actions have no effect
+    Truce
+}
+
+impl fmt::Display for Modifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use Modifier::*;
+        match self {
+            Mutation(multiplier) => write!(f, "mutation rate x{}", multiplier),
+            Truce => write!(f, "truce")
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Pulse {
+    pub modifier: Modifier,
+    // the tick the pulse was applied on, it takes effect from the following step
+    pub start: u64,
+    // the last step the modifier applies to
+    pub end: u64
+}
+
+impl Pulse {
+    pub fn active(&self, tick: u64) -> bool {
+        self.start < tick && tick <= self.end
+    }
+}
+
+impl fmt::Display for Pulse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} from step {} to {}", self.modifier, self.start + 1, self.end)
+    }
+}
+
+// Every pulse applied during the run, including those that have ended
+// Overlapping pulses stack
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct Pulses {
+    pulses: Vec<Pulse>
+}
+
+impl Pulses {
+    pub fn push(&mut self, pulse: Pulse) {
+        self.pulses.push(pulse);
+    }
+
+    pub fn all(&self) -> &[Pulse] {
+        &self.pulses
+    }
+
+    pub fn active(&self, tick: u64) -> impl Iterator<Item = &Pulse> {
+        self.pulses.iter().filter(move |pulse| pulse.active(tick))
+    }
+
+    // The product of every active mutation multiplier
+    pub fn mutation(&self, tick: u64) -> f32 {
+        self.active(tick).fold(1f32, |rate, pulse| match pulse.modifier {
+            Modifier::Mutation(multiplier) => rate * multiplier.max(0f32),
+            _ => rate
+        } )
+    }
+
+    pub fn truce(&self, tick: u64) -> bool {
+        self.active(tick).any(|pulse| pulse.modifier == Modifier::Truce)
+    }
+}
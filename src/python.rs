@@ -0,0 +1,299 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use crate::agent::gene::Genome;
+use crate::error::LoadError;
+use crate::simulation::{Basics, Simulation, SimulationSettings};
+use crate::stats::Sample;
+use crate::tile::Tile;
+use crate::tile::coord::Coord;
+
+// Python bindings, built with the pyo3 feature and packaged with maturin, see pyproject.toml
+// World state is returned as nested lists indexed [y][x], which numpy.array accepts as they are
+// Doc comments on the classes and methods below are their Python docstrings
+
+// The contents of a tile in Simulation.tiles, the same codes the Agents sense
+// Grids hold u32s, as rows of u8s would reach Python as bytes
+const EMPTY: u32 = 0;
+const AGENT: u32 = 1;
+const FOOD: u32 = 2;
+const WALL: u32 = 3;
+const WATER: u32 = 4;
+
+fn load_error(e: LoadError) -> PyErr {
+    match e {
+        LoadError::Io(e) => e.into(),
+        e => PyValueError::new_err(e.to_string())
+    }
+}
+
+/// The settings a Simulation is built from.
+/// Any argument left out keeps its default, and a seed is drawn when none is given.
+#[pyclass(name = "SimulationSettings", module = "evolution_simulation")]
+#[derive(Clone)]
+pub struct PySimulationSettings {
+    settings: SimulationSettings
+}
+
+#[pymethods]
+impl PySimulationSettings {
+    #[new]
+    #[pyo3(signature = (width=None, height=None, agents=None, complexity=None, seed=None, mutation_rate=None, reproduction_threshold=None))]
+    fn new(
+        width: Option<usize>,
+        height: Option<usize>,
+        agents: Option<usize>,
+        complexity: Option<usize>,
+        seed: Option<u64>,
+        mutation_rate: Option<f32>,
+        reproduction_threshold: Option<u8>) -> PyResult<Self> {
+
+        let settings = SimulationSettings::default();
+        let basics = settings.basics();
+
+        let settings = settings.with_basics(Basics {
+            dimensions: iced::Size::new(
+                width.unwrap_or(basics.dimensions.width),
+                height.unwrap_or(basics.dimensions.height)),
+            agents: agents.unwrap_or(basics.agents),
+            complexity: complexity.unwrap_or(basics.complexity),
+            seed: seed.or(basics.seed),
+            mutation_rate: mutation_rate.unwrap_or(basics.mutation_rate),
+            reproduction_threshold: reproduction_threshold.unwrap_or(basics.reproduction_threshold),
+            ..basics
+        } );
+
+        settings.validate().map_err(load_error)?;
+        Ok(Self { settings })
+    }
+
+    /// Reads settings from a TOML configuration file, the fields it leaves out keep their defaults.
+    #[staticmethod]
+    fn from_path(path: &str) -> PyResult<Self> {
+        SimulationSettings::from_path(path)
+            .map(|settings| Self { settings })
+            .map_err(load_error)
+    }
+
+    #[getter]
+    fn width(&self) -> usize {
+        self.settings.basics().dimensions.width
+    }
+
+    #[getter]
+    fn height(&self) -> usize {
+        self.settings.basics().dimensions.height
+    }
+
+    #[getter]
+    fn agents(&self) -> usize {
+        self.settings.basics().agents
+    }
+
+    #[getter]
+    fn complexity(&self) -> usize {
+        self.settings.basics().complexity
+    }
+
+    #[getter]
+    fn seed(&self) -> Option<u64> {
+        self.settings.seed()
+    }
+
+    #[getter]
+    fn mutation_rate(&self) -> f32 {
+        self.settings.basics().mutation_rate
+    }
+
+    #[getter]
+    fn reproduction_threshold(&self) -> u8 {
+        self.settings.basics().reproduction_threshold
+    }
+
+    fn __repr__(&self) -> String {
+        let basics = self.settings.basics();
+        format!("SimulationSettings(width={}, height={}, agents={}, complexity={}, seed={}, mutation_rate={}, reproduction_threshold={})",
+            basics.dimensions.width,
+            basics.dimensions.height,
+            basics.agents,
+            basics.complexity,
+            basics.seed.map_or(String::from("None"), |seed| seed.to_string()),
+            basics.mutation_rate,
+            basics.reproduction_threshold)
+    }
+}
+
+/// A world of Agents, food, walls and water, advanced with step().
+/// Built from the default settings when none are given.
+// Tiles hold their Agents in RefCells, so a Simulation stays on the thread that created it
+#[pyclass(name = "Simulation", module = "evolution_simulation", unsendable)]
+pub struct PySimulation {
+    simulation: Simulation
+}
+
+#[pymethods]
+impl PySimulation {
+    #[new]
+    #[pyo3(signature = (settings=None))]
+    fn new(settings: Option<PySimulationSettings>) -> Self {
+        Self {
+            simulation: Simulation::new(settings.map_or_else(SimulationSettings::default, |settings| settings.settings))
+        }
+    }
+
+    /// Restores a world saved with save().
+    #[staticmethod]
+    fn load(path: &str) -> PyResult<Self> {
+        Simulation::load_from(path)
+            .map(|simulation| Self { simulation })
+            .map_err(load_error)
+    }
+
+    fn save(&self, path: &str) -> PyResult<()> {
+        self.simulation.save_to(path).map_err(PyErr::from)
+    }
+
+    /// Advances the world by the given number of steps, stopping early if every Agent dies.
+    /// Returns the number of steps taken.
+    #[pyo3(signature = (steps=1))]
+    fn step(&mut self, steps: u64) -> u64 {
+        let started = self.simulation.tick();
+        while self.simulation.tick() - started < steps && !self.simulation.agents().is_empty() {
+            self.simulation.step();
+        }
+
+        self.simulation.tick() - started
+    }
+
+    #[getter]
+    fn tick(&self) -> u64 {
+        self.simulation.tick()
+    }
+
+    #[getter]
+    fn width(&self) -> usize {
+        self.simulation.size().width
+    }
+
+    #[getter]
+    fn height(&self) -> usize {
+        self.simulation.size().height
+    }
+
+    #[getter]
+    fn population(&self) -> usize {
+        self.simulation.agents().len()
+    }
+
+    #[getter]
+    fn settings(&self) -> PySimulationSettings {
+        PySimulationSettings { settings: self.simulation.settings().clone() }
+    }
+
+    /// The contents of every tile, indexed [y][x].
+    /// Tiles hold EMPTY, AGENT, FOOD, WALL or WATER.
+    fn tiles(&self) -> Vec<Vec<u32>> {
+        self.grid(|tile| {
+            use Tile::*;
+            match tile {
+                Agent(..) => AGENT,
+                Food(..) => FOOD,
+                Wall => WALL,
+                Water => WATER
+            }
+        } )
+    }
+
+    /// The density of the food on every tile, indexed [y][x].
+    fn food(&self) -> Vec<Vec<u32>> {
+        self.grid(|tile| match tile {
+            Tile::Food(..) => u32::from(tile.food()),
+            _ => 0
+        } )
+    }
+
+    /// The temperature of every tile, indexed [y][x].
+    fn temperature(&self) -> Vec<Vec<f32>> {
+        let size = self.simulation.size();
+        (0..size.height).map(|y| {
+            (0..size.width).map(|x| self.simulation.temperature(Coord::new(x, y))).collect()
+        } ).collect()
+    }
+
+    /// One dict per living Agent, ready to be passed to pandas.DataFrame.
+    fn agents<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        self.simulation.agents().into_iter().map(|coord| {
+            let agent = self.simulation.get(coord).agent();
+
+            let row = PyDict::new(py);
+            row.set_item("id", agent.id)?;
+            row.set_item("x", coord.x)?;
+            row.set_item("y", coord.y)?;
+            row.set_item("direction", format!("{:?}", agent.direction))?;
+            row.set_item("fitness", u8::from(agent.fitness))?;
+            row.set_item("energy", u8::from(agent.energy))?;
+            row.set_item("thirst", u8::from(agent.thirst))?;
+            row.set_item("born", agent.born)?;
+            row.set_item("offspring", agent.offspring)?;
+            row.set_item("species", agent.attributes.species())?;
+            row.set_item("marker", agent.marker)?;
+            row.set_item("brain_size", agent.brain.node_count())?;
+            row.set_item("genome", Genome::get(agent.genome.clone()))?;
+            Ok(row)
+        } ).collect()
+    }
+
+    /// The population statistics of the latest step, the same columns as the CSV log.
+    fn stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let sample = Sample::new(&self.simulation);
+
+        let stats = PyDict::new(py);
+        stats.set_item("tick", sample.tick)?;
+        stats.set_item("population", sample.population)?;
+        stats.set_item("births", sample.births)?;
+        stats.set_item("deaths", sample.deaths)?;
+        stats.set_item("mean_fitness", sample.mean_fitness)?;
+        stats.set_item("max_fitness", sample.max_fitness)?;
+        stats.set_item("food", sample.food)?;
+        stats.set_item("mean_brain_size", sample.brain_size)?;
+        Ok(stats)
+    }
+
+    fn __repr__(&self) -> String {
+        let size = self.simulation.size();
+        format!("Simulation({}x{}, tick {}, {} agents)",
+            size.width,
+            size.height,
+            self.simulation.tick(),
+            self.simulation.agents().len())
+    }
+}
+
+impl PySimulation {
+    // Tiles that hold nothing are EMPTY
+    fn grid(&self, value: impl Fn(&Tile) -> u32) -> Vec<Vec<u32>> {
+        let size = self.simulation.size();
+
+        let mut grid = vec![vec![EMPTY; size.width]; size.height];
+        for coord in self.simulation.coords() {
+            grid[coord.y][coord.x] = value(self.simulation.get(coord));
+        }
+
+        grid
+    }
+}
+
+#[pymodule]
+fn evolution_simulation(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PySimulationSettings>()?;
+    m.add_class::<PySimulation>()?;
+
+    m.add("EMPTY", EMPTY)?;
+    m.add("AGENT", AGENT)?;
+    m.add("FOOD", FOOD)?;
+    m.add("WALL", WALL)?;
+    m.add("WATER", WATER)?;
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::error::LoadError;
+use crate::manifest::Manifest;
+use crate::pulse::Modifier;
+use crate::simulation::{Rule, Simulation};
+use crate::tile::coord::Coord;
+
+// A change made to the Simulation from outside of Simulation::step
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub enum Intervention {
+    // an Agent was imported from the given genome
+    Import(String),
+    // an Agent built from the given genome was placed on the empty Tile at the Coord
+    Place(Coord, String),
+    // the Agent at the Coord was given a lineage marker
+    Mark(Coord, u8),
+    // the Agent at the Coord was removed, leaving its remains behind
+    Kill(Coord),
+    // a unit of food was placed at the Coord
+    Feed(Coord),
+    // a wall was built on the empty Tile at the Coord
+    Build(Coord),
+    // the wall at the Coord was torn down
+    Erase(Coord),
+    // the food, wall or water at the Coord was cleared away
+    Clear(Coord),
+    // a randomly generated Agent was placed on the empty Tile at the Coord
+    Spawn(Coord),
+    // a global modifier was applied for the given number of steps
+    Pulse(Modifier, u64),
+    Rule(Rule),
+    // whether the Agent of the preceding import or placement passed quarantine
+    // replays reuse the verdict rather than trusting quarantine to reach it again
+    Screened(bool)
+}
+
+// Everything needed to replay a run tick-for-tick
+// Each intervention is tagged with the tick it was made on, it is applied before the following step
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Recording {
+    pub manifest: Manifest,
+    pub interventions: Vec<(u64, Intervention)>,
+    // the number of steps that had been taken when the recording was made
+    pub ticks: u64
+}
+
+impl Recording {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LoadError> {
+        let contents = crate::platform::read_to_string(path).map_err(LoadError::Io)?;
+        serde_json::from_str(&contents).map_err(LoadError::Parse)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        // serializing plain settings can't fail
+        crate::platform::write(path, serde_json::to_string_pretty(self).unwrap())
+    }
+}
+
+// A Recording being replayed, which keeps a copy of the replayed Simulation at regular intervals
+// Seeking replays forward from the nearest copy at or before the requested tick, rather than from the start
+pub struct Replay {
+    pub recording: Recording,
+    // keyed by tick, each copy is taken before the interventions made on its tick are applied
+    checkpoints: BTreeMap<u64, Simulation>
+}
+
+impl Replay {
+    // checkpoints are never closer together than this
+    const MIN_INTERVAL: u64 = 100;
+    // long recordings space their checkpoints out, so that no more than this many are kept
+    const MAX_CHECKPOINTS: u64 = 64;
+
+    pub fn new(recording: Recording) -> Self {
+        Self {
+            recording,
+            checkpoints: BTreeMap::new()
+        }
+    }
+
+    fn interval(&self) -> u64 {
+        (self.recording.ticks / Self::MAX_CHECKPOINTS).max(Self::MIN_INTERVAL)
+    }
+
+    // The replayed Simulation as it was at the tick
+    pub fn seek(&mut self, tick: u64) -> Simulation {
+        let mut simulation = match self.checkpoints.range(..=tick).next_back() {
+            Some((_, checkpoint)) => checkpoint.clone(),
+            None => Simulation::replay(&self.recording, 0)
+        };
+
+        let interval = self.interval();
+        while simulation.tick() < tick {
+            simulation.step();
+            if simulation.tick().is_multiple_of(interval) {
+                self.checkpoints.entry(simulation.tick()).or_insert_with(|| simulation.clone());
+            }
+        }
+
+        simulation.replay_until(tick);
+        simulation
+    }
+}
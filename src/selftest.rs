@@ -0,0 +1,160 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::simulation::{Simulation, SimulationSettings};
+use crate::tile::coord::Coord;
+
+// A deterministic suite that runs fixed seeds for a fixed number of steps, checking the
+// invariants the rest of the program relies on and timing how quickly each world steps
+// Run with `evolution_simulation selftest`, or from the interface's Run Self-Test button
+
+// every case runs the same settings with a different seed
+const SEEDS: [u64; 3] = [1, 2, 3];
+const STEPS: u64 = 1000;
+
+// The outcome of running a single seed
+pub struct Case {
+    seed: u64,
+    steps: u64,
+    elapsed: Duration,
+    // each invariant that was broken, empty if the case passed
+    failures: Vec<String>
+}
+
+impl Case {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    // The rate of the timed run alone, the checks aren't counted
+    pub fn rate(&self) -> f64 {
+        self.steps as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+pub struct Report {
+    cases: Vec<Case>
+}
+
+impl Report {
+    pub fn passed(&self) -> bool {
+        self.cases.iter().all(Case::passed)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let passed = self.cases.iter().filter(|case| case.passed()).count();
+        write!(f, "Self-test {}, {} of {} cases passed",
+            if self.passed() { "passed" } else { "FAILED" },
+            passed,
+            self.cases.len())?;
+
+        for case in self.cases.iter() {
+            write!(f, "\n  seed {}: {}, {} steps at {:.0} steps/s",
+                case.seed,
+                if case.passed() { "passed" } else { "FAILED" },
+                case.steps,
+                case.rate())?;
+
+            for failure in case.failures.iter() {
+                write!(f, "\n    {}", failure)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+pub fn run() -> Report {
+    Report {
+        cases: SEEDS.iter().map(|seed| run_case(*seed)).collect()
+    }
+}
+
+fn run_case(seed: u64) -> Case {
+    let settings = SimulationSettings::self_test(seed);
+
+    let mut failures = Vec::new();
+    let mut fail = |failure: String| if !failures.contains(&failure) {
+        failures.push(failure);
+    };
+
+    let mut simulation = Simulation::new(settings.clone());
+    let mut elapsed = Duration::ZERO;
+    for _ in 0..STEPS {
+        let started = Instant::now();
+        simulation.step();
+        elapsed += started.elapsed();
+
+        if let Err(failure) = check_occupancy(&simulation) {
+            fail(format!("step {}: {}", simulation.tick(), failure));
+        }
+    }
+
+    let unbalanced = simulation.ledger().unbalanced().count();
+    if unbalanced > 0 {
+        fail(format!("{} steps didn't balance their energy", unbalanced));
+    }
+
+    // the same seed must always produce the same world
+    let mut repeat = Simulation::new(settings);
+    while repeat.tick() < simulation.tick() {
+        repeat.step();
+    }
+
+    if fingerprint(&repeat) != fingerprint(&simulation) {
+        fail(String::from("a second run with the same seed diverged"));
+    }
+
+    if fingerprint(&Simulation::replay(&simulation.record(), simulation.tick())) != fingerprint(&simulation) {
+        fail(String::from("replaying the run didn't reproduce it"));
+    }
+
+    // a loaded world must resume exactly where it left off, so both are stepped once more
+    match serde_json::to_string(&simulation).and_then(|saved| serde_json::from_str::<Simulation>(&saved)) {
+        Ok(mut loaded) => {
+            simulation.step();
+            loaded.step();
+
+            if fingerprint(&loaded) != fingerprint(&simulation) {
+                fail(String::from("a saved and loaded world diverged from the original"));
+            }
+        },
+        Err(e) => fail(format!("the world couldn't be saved and loaded, {}", e))
+    }
+
+    Case {
+        seed,
+        steps: STEPS,
+        elapsed,
+        failures
+    }
+}
+
+// Every Agent must be reachable at its Coord and hold an id no other Agent holds
+fn check_occupancy(simulation: &Simulation) -> Result<(), String> {
+    let mut ids = HashSet::new();
+    for coord in simulation.agents() {
+        let agent = simulation.try_agent(coord)
+            .map_err(|e| format!("the agent at {:?} couldn't be reached, {}", coord, e))?;
+
+        if !ids.insert(agent.id) {
+            return Err(format!("more than one agent has the id {}", agent.id));
+        }
+    }
+
+    Ok(())
+}
+
+// The tick and the contents of every Tile, which two identical worlds share
+// Saves aren't compared directly, as some of the Simulation's maps serialize in no particular order
+fn fingerprint(simulation: &Simulation) -> (u64, Vec<(Coord, String)>) {
+    let tiles = simulation.coords().into_iter().map(|coord| {
+        let tile = serde_json::to_string(simulation.get(coord)).unwrap_or_default();
+        (coord, tile)
+    } ).collect();
+
+    (simulation.tick(), tiles)
+}
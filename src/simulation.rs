@@ -1,12 +1,31 @@
 use std::fmt;
 use std::fmt::Formatter;
+use std::collections::{HashMap, HashSet, VecDeque};
 
-use rand::{Rng, thread_rng};
+use rand::{Rng, SeedableRng};
+
+use rayon::prelude::*;
+
+use noise::{NoiseFn, Perlin};
+
+use strum::IntoEnumIterator;
 
 use crate::tile;
 use crate::tile::coord;
 use crate::agent;
 use crate::agent::gene;
+use crate::interaction::{Interaction, InteractionKind, InteractionLog};
+use crate::event::{Event, EventKind, EventLog};
+use crate::ledger::{Flow, Ledger};
+use crate::recording::{Intervention, Recording};
+use crate::morgue::{Cause, Morgue, Obituary};
+use crate::climate::{Climate, Temperature};
+use crate::weather::Weather;
+use crate::pulse::{Modifier, Pulse, Pulses};
+
+// Every source of randomness in a run descends from a single Prng
+// ChaCha is used directly (rather than StdRng) so that its state can be saved
+pub type Prng = rand_chacha::ChaCha12Rng;
 /*
 Eating raises fitness and refills a creatures energy.
 Creatures have a chance to reproduce when their fitness exceeds a certain threshold value R.
@@ -14,13 +33,58 @@ Reproducing resets fitness back to R - 1.
 Actions deplete energy; creatures are considered starving when no energy remains.
 Producing food completely depletes a creature's energy.
 Starving creatures lose fitness each turn (unless they produced food that turn).
+Larger brains drain additional energy each turn, in proportion to their node count.
  */
 
-pub(crate) struct SimulationSettings {
+/// The rules a [`Simulation`] is built from: its size, starting population, seed and every optional rule.
+/// Start from [`SimulationSettings::default`] or one of the presets, and use [`SimulationSettings::with_basics`]
+/// to change the settings the interface's settings pane edits.
+/// Fields missing from a save or settings file keep their defaults.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct SimulationSettings {
+    #[serde(with = "dimensions")]
     dimensions: iced::Size<usize>,
     agents: usize,
     complexity: usize,
-    seed: Option<u64>
+    seed: Option<u64>,
+    mutation: gene::MutationWeights,
+    neighborhood: coord::Neighborhood,
+    food: FoodDistribution,
+    world: WorldGen,
+    hysteresis: f32,
+    selection: agent::ActionSelection,
+    quarantine: bool,
+    // energy drained per brain node each turn
+    metabolism: f32,
+    // the learning rate of plastic edges, plasticity is disabled at zero
+    plasticity: f32,
+    // the interface warns when estimated memory usage approaches this many bytes
+    memory_budget: usize,
+    update: UpdateMode,
+    storage: tile::TileStorage,
+    // saves include each Agent's pruned brain, trading file size for faster loading
+    cache_brains: bool,
+    boundary: coord::BoundaryMode,
+    // the chance that newly grown food is toxic, eating it costs fitness instead of sating
+    toxicity: f32,
+    // None leaves the pattern to each Agent's spread genes
+    production: Option<agent::YieldPattern>,
+    predation: Predation,
+    hypermutation: Option<Hypermutation>,
+    seasons: Option<Seasons>,
+    climate: Option<Climate>,
+    weather: Option<Weather>,
+    crowding: Option<Crowding>,
+    barrier: Option<Barrier>,
+    // the number of steps covered by the interaction network
+    interaction_window: u64,
+    // multiplies the mutation rate of every offspring, alongside hypermutation and pulses
+    mutation_rate: f32,
+    // the fitness above which Agents may reproduce, and to which reproducing resets it
+    reproduction_threshold: u8,
+    // where each step's events are written as JSON lines, `-` for standard output, see EventStream
+    event_stream: Option<String>
 }
 
 impl Default for SimulationSettings {
@@ -29,194 +93,2223 @@ impl Default for SimulationSettings {
             dimensions: iced::Size::new(32, 32),
             agents: 64,
             complexity: 128,
-            seed: None
+            seed: None,
+            mutation: gene::MutationWeights::default(),
+            neighborhood: coord::Neighborhood::default(),
+            food: FoodDistribution::default(),
+            world: WorldGen::default(),
+            hysteresis: 0f32,
+            selection: agent::ActionSelection::default(),
+            quarantine: true,
+            metabolism: 0f32,
+            plasticity: 0f32,
+            memory_budget: 256 * 1024 * 1024,
+            update: UpdateMode::default(),
+            storage: tile::TileStorage::default(),
+            cache_brains: false,
+            boundary: coord::BoundaryMode::default(),
+            toxicity: 0f32,
+            production: Some(agent::YieldPattern::Ahead),
+            predation: Predation::default(),
+            hypermutation: None,
+            seasons: None,
+            climate: None,
+            weather: None,
+            crowding: None,
+            barrier: None,
+            interaction_window: 500,
+            mutation_rate: 1f32,
+            reproduction_threshold: 8,
+            event_stream: None
         }
     }
 }
 
-pub(crate) struct Simulation(tile::TileMap);
+impl SimulationSettings {
+    // None until the Simulation has been created
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
 
-impl Simulation {
-    const REPRODUCTION_THRESHOLD: ux::u5 = ux::u5::new(8);
+    pub fn boundary(&self) -> coord::BoundaryMode {
+        self.boundary
+    }
 
-    pub(crate) fn new(settings: SimulationSettings) -> Self {
-        let mut prng: rand::rngs::StdRng = match settings.seed {
-            Some(s) => rand::SeedableRng::seed_from_u64(s),
-            None => rand::SeedableRng::from_entropy()
-        };
+    pub fn update(&self) -> UpdateMode {
+        self.update
+    }
 
-        Self( {
-                let mut t = tile::TileMap::new(settings.dimensions);
+    pub fn neighborhood(&self) -> coord::Neighborhood {
+        self.neighborhood
+    }
 
-                for _ in 0..settings.agents {
-                    let agent = 'agent: loop {
-                        match agent::Agent::from_prng(settings.complexity, &mut prng) {
-                            Ok(agent) => break 'agent agent,
-                            Err(..) => continue 'agent
-                        }
-                    };
+    pub fn hysteresis(&self) -> f32 {
+        self.hysteresis
+    }
 
-                    'occupied: loop {
-                        let coord = coord::Coord::new(
-                            prng.gen_range(0..settings.dimensions.width),
-                            prng.gen_range(0..settings.dimensions.height)
-                        );
+    pub fn climate(&self) -> Option<Climate> {
+        self.climate
+    }
 
-                        if !t.exists(coord) {
-                            t.put(coord, tile::Tile::new_agent(agent));
-                            break 'occupied;
-                        }
-                    }
+    pub fn barrier(&self) -> Option<Barrier> {
+        self.barrier
+    }
+
+    pub fn event_stream(&self) -> Option<&str> {
+        self.event_stream.as_deref()
+    }
+
+    pub fn basics(&self) -> Basics {
+        Basics {
+            dimensions: self.dimensions,
+            agents: self.agents,
+            complexity: self.complexity,
+            seed: self.seed,
+            mutation_rate: self.mutation_rate,
+            reproduction_threshold: self.reproduction_threshold,
+            memory_budget: self.memory_budget
+        }
+    }
+
+    // Every other setting is kept
+    pub fn with_basics(&self, basics: Basics) -> Self {
+        Self {
+            dimensions: basics.dimensions,
+            agents: basics.agents,
+            complexity: basics.complexity,
+            seed: basics.seed,
+            mutation_rate: basics.mutation_rate,
+            reproduction_threshold: basics.reproduction_threshold,
+            memory_budget: basics.memory_budget,
+            ..self.clone()
+        }
+    }
+
+    /// Reads settings from a TOML configuration file, such as one holding `dimensions = [64, 64]` and `boundary = "Wall"`.
+    /// The file uses the same field names as the settings in a saved world, and the fields it leaves out keep their defaults.
+    pub fn from_path(path: impl AsRef<std::path::Path>) -> Result<Self, crate::error::LoadError> {
+        use crate::error::LoadError;
+
+        let contents = crate::platform::read_to_string(path).map_err(LoadError::Io)?;
+        let settings: Self = toml::from_str(&contents).map_err(LoadError::Config)?;
+        settings.validate()?;
+        Ok(settings)
+    }
+
+    /// Checks the settings a Simulation can't be built from, which would otherwise panic while the world is generated or stepped.
+    pub fn validate(&self) -> Result<(), crate::error::LoadError> {
+        use crate::error::LoadError;
+
+        if self.dimensions.width == 0 || self.dimensions.height == 0 {
+            return Err(LoadError::Invalid(String::from("the world must be at least 1 tile wide and tall")));
+        }
+
+        if let FoodDistribution::Uniform { probability } = self.food {
+            if !(0f64..=1f64).contains(&probability) {
+                return Err(LoadError::Invalid(format!("the food probability {} is not between 0 and 1", probability)));
+            }
+        }
+
+        if !(0f32..=1f32).contains(&self.toxicity) {
+            return Err(LoadError::Invalid(format!("the toxicity {} is not between 0 and 1", self.toxicity)));
+        }
+
+        if !(1..=Basics::MAX_REPRODUCTION_THRESHOLD).contains(&self.reproduction_threshold) {
+            return Err(LoadError::Invalid(format!("the reproduction threshold {} is not between 1 and {}",
+                self.reproduction_threshold, Basics::MAX_REPRODUCTION_THRESHOLD)));
+        }
+
+        if !self.mutation_rate.is_finite() || self.mutation_rate < 0f32 {
+            return Err(LoadError::Invalid(format!("the mutation rate {} is not a non-negative number", self.mutation_rate)));
+        }
+
+        Ok(())
+    }
+
+    // A small, well-fed world with a fixed seed, so every tutorial plays out the same way
+    pub fn tutorial() -> Self {
+        Self {
+            dimensions: iced::Size::new(16, 16),
+            agents: 24,
+            seed: Some(1),
+            food: FoodDistribution::Uniform { probability: 0.2 },
+            ..Self::default()
+        }
+    }
+
+    // An allopatric speciation experiment
+    // Two halves of a walled world are kept apart by a barrier that few migrants cross
+    pub fn split_world() -> Self {
+        Self {
+            dimensions: iced::Size::new(48, 32),
+            agents: 96,
+            food: FoodDistribution::Uniform { probability: 0.1 },
+            boundary: coord::BoundaryMode::Wall,
+            barrier: Some(Barrier::default()),
+            ..Self::default()
+        }
+    }
+
+    // The worlds run by the self-test, with a fixed seed and as many rules enabled as can coexist
+    pub fn self_test(seed: u64) -> Self {
+        Self {
+            seed: Some(seed),
+            toxicity: 0.1,
+            climate: Some(Climate::default()),
+            weather: Some(Weather::default()),
+            ..Self::default()
+        }
+    }
+}
+
+// The settings that can be changed from the interface's settings pane
+#[derive(Debug, Copy, Clone)]
+pub struct Basics {
+    pub dimensions: iced::Size<usize>,
+    pub agents: usize,
+    pub complexity: usize,
+    // a seed is drawn from entropy when None
+    pub seed: Option<u64>,
+    pub mutation_rate: f32,
+    pub reproduction_threshold: u8,
+    pub memory_budget: usize
+}
+
+impl Basics {
+    // fitness is a 5-bit value, and Agents must be able to rise above the threshold
+    pub const MAX_REPRODUCTION_THRESHOLD: u8 = 30;
+}
+
+// iced::Size isn't serializable, so dimensions are stored as [width, height]
+mod dimensions {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(size: &iced::Size<usize>, serializer: S) -> Result<S::Ok, S::Error> {
+        [size.width, size.height].serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<iced::Size<usize>, D::Error> {
+        let [width, height] = <[usize; 2]>::deserialize(deserializer)?;
+        Ok(iced::Size::new(width, height))
+    }
+}
+
+// A yearly cycle, food decays faster and produced food often fails to take during winter
+#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Seasons {
+    // the number of steps in a year
+    length: u64,
+    // how harsh winter is, abundance falls to 1 - severity at midwinter
+    severity: f32
+}
+
+impl Default for Seasons {
+    fn default() -> Self {
+        Self {
+            length: 1000,
+            severity: 0.8f32
+        }
+    }
+}
+
+impl Seasons {
+    // 1 at midsummer, when each year begins
+    pub fn abundance(&self, tick: u64) -> f32 {
+        let angle = (tick % self.length.max(1)) as f32 / self.length.max(1) as f32 * std::f32::consts::TAU;
+        1f32 - self.severity.clamp(0f32, 1f32) * (1f32 - angle.cos()) / 2f32
+    }
+
+    pub fn name(&self, tick: u64) -> &'static str {
+        match (tick % self.length.max(1)) * 4 / self.length.max(1) {
+            0 => "Summer",
+            1 => "Autumn",
+            2 => "Winter",
+            _ => "Spring"
+        }
+    }
+}
+
+// A local carrying capacity, Agents with too many neighbors lose extra energy
+#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Crowding {
+    // the number of neighboring agents tolerated, counted over the settings' neighborhood
+    capacity: usize,
+    // the energy drained each step for every neighbor beyond capacity
+    drain: f32
+}
+
+impl Default for Crowding {
+    fn default() -> Self {
+        Self {
+            capacity: 2,
+            drain: 0.5f32
+        }
+    }
+}
+
+// Splits the world down the middle into a left and a right side
+// Agents cross with the given probability each time they try, but food never does
+// In a wrapping world, the edges divide the sides too
+#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Barrier {
+    permeability: f32
+}
+
+impl Default for Barrier {
+    fn default() -> Self {
+        Self {
+            permeability: 0.02f32
+        }
+    }
+}
+
+impl Barrier {
+    // 0 on the left side, 1 on the right
+    pub fn side(coord: coord::Coord, dimensions: iced::Size<usize>) -> usize {
+        usize::from(coord.x >= dimensions.width / 2)
+    }
+}
+
+// Per-side statistics for a world split by a Barrier
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SideStats {
+    pub agents: usize,
+    // the sum of food density across the side
+    pub food: usize,
+    pub mean_fitness: f32,
+    pub mean_genome_length: f32,
+    // the number of Agents of each species
+    pub species: [usize; agent::Attributes::SPECIES as usize],
+    // the number of Agents that have crossed into this side
+    pub migrants: u64
+}
+
+impl fmt::Display for SideStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "agents: {}
+food: {}
+mean fitness: {:.2}
+mean genome length: {:.2}
+species: {:?}
+migrants: {}",
+            self.agents,
+            self.food,
+            self.mean_fitness,
+            self.mean_genome_length,
+            self.species,
+            self.migrants)
+    }
+}
+
+// Temporarily raises the mutation rate once mean fitness stops improving
+#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Hypermutation {
+    // the number of steps in an epoch, fitness is assessed at the end of each one
+    epoch: u64,
+    // hypermutation begins after this many epochs without improvement
+    patience: u32,
+    // the mutation rate multiplier applied when hypermutation begins
+    multiplier: f32,
+    // the fraction of the raised rate's excess that remains after each epoch
+    relaxation: f32
+}
+
+impl Default for Hypermutation {
+    fn default() -> Self {
+        Self {
+            epoch: 100,
+            patience: 5,
+            multiplier: 4f32,
+            relaxation: 0.5f32
+        }
+    }
+}
+
+// An action applied during a step
+#[derive(Debug, Copy, Clone)]
+pub struct StepAction {
+    pub agent: u64,
+    // where the Agent was once the action was applied
+    pub coord: coord::Coord,
+    pub action: gene::ActionType
+}
+
+// Tracks how long mean fitness has gone without improving
+#[derive(Debug, Copy, Clone, Default, serde::Serialize, serde::Deserialize)]
+struct Stagnation {
+    best: f32,
+    epochs: u32
+}
+
+// Coords can't be JSON keys, so provenance is stored as a sorted list of pairs
+mod provenance {
+    use std::collections::HashMap;
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::tile::coord::Coord;
+
+    pub(super) fn serialize<S: Serializer>(provenance: &HashMap<Coord, u64>, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut pairs = provenance.iter().map(|(coord, id)| (*coord, *id)).collect::<Vec<(Coord, u64)>>();
+        pairs.sort();
+        pairs.serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashMap<Coord, u64>, D::Error> {
+        Ok(Vec::<(Coord, u64)>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
+
+// Determines how agent actions are applied each step
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum UpdateMode {
+    // agents sense and act one at a time, in order of fitness
+    // each agent sees the changes made by those before it
+    #[default]
+    Sequential,
+    // every agent senses the same frozen world, then all actions are resolved together
+    // moves and food production into a tile that any other agent also targets fail
+    Synchronous
+}
+
+// A rule that can be changed while the Simulation is running
+#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub enum Rule {
+    Boundary(coord::BoundaryMode),
+    Update(UpdateMode),
+    Neighborhood(coord::Neighborhood)
+}
+
+// Decides which species can kill which, a kill that isn't allowed has no effect
+// Species are set by each Agent's first species gene
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub enum Predation {
+    // any Agent can kill any other
+    #[default]
+    Any,
+    // Agents never kill their own species
+    OtherSpecies,
+    // Agents only kill their own species
+    SameSpecies,
+    // only the listed (predator, prey) pairs are allowed
+    Pairs(Vec<(u8, u8)>)
+}
+
+impl Predation {
+    fn allows(&self, predator: u8, prey: u8) -> bool {
+        use Predation::*;
+        match self {
+            Any => true,
+            OtherSpecies => predator != prey,
+            SameSpecies => predator == prey,
+            Pairs(pairs) => pairs.contains(&(predator, prey))
+        }
+    }
+}
+
+// Describes how food is laid out before the first step
+// Seeded food never exceeds the diffusion threshold
+#[derive(Debug, Copy, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub enum FoodDistribution {
+    #[default]
+    Empty,
+    // each tile has the given probability of holding food
+    Uniform { probability: f64 },
+    // food appears where Perlin noise rises above the threshold
+    // lower frequencies produce larger blobs
+    Noise { frequency: f64, threshold: f64 },
+    // a single patch in the center of the world, densest in the middle
+    Oasis { radius: f32 },
+    // a band of food at a fixed distance from the center
+    Ring { radius: f32, width: f32 }
+}
+
+impl FoodDistribution {
+    // Returns the density of food to place at the given Coord (0 if none)
+    fn density(&self, coord: coord::Coord, dimensions: iced::Size<usize>, perlin: &Perlin, prng: &mut Prng) -> u8 {
+        let scale = Self::scale;
+        let distance = distance(coord, dimensions.width as f32 / 2f32, dimensions.height as f32 / 2f32);
+
+        use FoodDistribution::*;
+        match *self {
+            Empty => 0,
+            Uniform { probability } => {
+                // clamped, as gen_bool panics on probabilities outside of [0, 1] and NaN
+                if probability > 0f64 && prng.gen_bool(probability.min(1f64)) {
+                    prng.gen_range(1..=tile::Tile::DIFFUSION_THRESHOLD)
+                } else {
+                    0
+                }
+            },
+            Noise { frequency, threshold } => {
+                let value = perlin.get([coord.x as f64 * frequency, coord.y as f64 * frequency]);
+                if value > threshold {
+                    scale(((value - threshold) / (1f64 - threshold)) as f32)
+                } else {
+                    0
+                }
+            },
+            Oasis { radius } => {
+                if distance <= radius {
+                    scale(1f32 - distance / radius)
+                } else {
+                    0
                 }
+            },
+            Ring { radius, width } => {
+                let offset = (distance - radius).abs();
+                if offset <= width / 2f32 {
+                    scale(1f32 - offset / (width / 2f32))
+                } else {
+                    0
+                }
+            }
+        }
+    }
+
+    // scales a value in [0, 1] to a density between 1 and the diffusion threshold
+    fn scale(strength: f32) -> u8 {
+        (strength * tile::Tile::DIFFUSION_THRESHOLD as f32).ceil()
+            .clamp(1f32, tile::Tile::DIFFUSION_THRESHOLD as f32) as u8
+    }
+}
+
+// The distance from the center of the Tile at `coord` to the point (x, y)
+fn distance(coord: coord::Coord, x: f32, y: f32) -> f32 {
+    let dx = coord.x as f32 + 0.5f32 - x;
+    let dy = coord.y as f32 + 0.5f32 - y;
+    (dx * dx + dy * dy).sqrt()
+}
+
+// Shapes the world before the first step
+// Walls are placed first, then water, then agents within the spawn region, then food
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct WorldGen {
+    walls: Option<TerrainNoise>,
+    // Agents only grow thirsty in worlds with water
+    water: Option<TerrainNoise>,
+    // laid over the FoodDistribution
+    clusters: Option<FoodClusters>,
+    spawn: SpawnRegion
+}
+
+// walls and water are placed where Perlin noise rises above the threshold
+// lower frequencies produce larger obstacles
+#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TerrainNoise {
+    frequency: f64,
+    threshold: f64
+}
+
+// patches of food scattered at random, each densest in its center
+#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FoodClusters {
+    count: usize,
+    radius: f32
+}
+
+#[derive(Debug, Copy, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub enum SpawnRegion {
+    #[default]
+    Anywhere,
+    // a disc in the center of the world
+    Center { radius: f32 },
+    // given by its top left corner and size, in Tiles
+    Rectangle { x: usize, y: usize, width: usize, height: usize }
+}
+
+impl SpawnRegion {
+    fn contains(&self, coord: coord::Coord, dimensions: iced::Size<usize>) -> bool {
+        use SpawnRegion::*;
+        match *self {
+            Anywhere => true,
+            Center { radius } => distance(coord, dimensions.width as f32 / 2f32, dimensions.height as f32 / 2f32) <= radius,
+            Rectangle { x, y, width, height } => {
+                (x..x + width).contains(&coord.x) && (y..y + height).contains(&coord.y)
+            }
+        }
+    }
+}
+
+// A genome preserved after the last member of a marked lineage died
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Extinction {
+    pub marker: u8,
+    pub genome: Vec<gene::Gene>
+}
+
+// Statistics gathered while an imported Agent is held in quarantine
+#[derive(Debug, Clone, Default)]
+pub struct Viability {
+    pub steps: usize,
+    pub actions: usize,
+    pub starved: bool,
+    // the nodes and edges evaluated each time the Agent decides, see CompiledBrain::cost
+    pub cost: usize
+}
+
+impl Viability {
+    // The most work a single brain evaluation may take
+    // Counted rather than timed, so that an import is accepted or rejected the same way on every run of a seed
+    pub const EVALUATION_BUDGET: usize = 4096;
+
+    // Agents must act at least once, and think cheaply enough
+    pub fn viable(&self) -> bool {
+        self.actions > 0 && self.cost <= Self::EVALUATION_BUDGET
+    }
+}
 
-                t
+impl fmt::Display for Viability {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "acted {} of {} steps{}, each evaluation costs {} operations",
+            self.actions,
+            self.steps,
+            if self.starved { " before starving" } else { "" },
+            self.cost
+        )
+    }
+}
+
+// The arenas used to measure an Agent's competence, away from the pressures of the full world
+// Each arena is always generated from the same seed, so scores can be compared over time
+#[derive(Debug, Copy, Clone, PartialEq, Eq, strum_macros::EnumIter)]
+pub enum BenchmarkTask {
+    // food scattered evenly
+    Forage,
+    // food in a ring around the starting Tile, out of sight
+    Ring,
+    // food scattered between walls
+    Maze
+}
+
+impl BenchmarkTask {
+    fn settings(&self, settings: &SimulationSettings) -> SimulationSettings {
+        let seed = Some(BenchmarkTask::iter().position(|task| task == *self).unwrap() as u64);
+
+        use BenchmarkTask::*;
+        let (food, world) = match self {
+            Forage => (FoodDistribution::Uniform { probability: 0.1 }, WorldGen::default()),
+            Ring => (FoodDistribution::Ring { radius: 6f32, width: 1.5f32 }, WorldGen::default()),
+            Maze => (FoodDistribution::Uniform { probability: 0.1 }, WorldGen {
+                walls: Some(TerrainNoise { frequency: 0.2, threshold: 0.3 }),
+                ..WorldGen::default()
             } )
+        };
+
+        SimulationSettings {
+            dimensions: Simulation::QUARANTINE_SIZE,
+            agents: 0,
+            seed,
+            food,
+            world,
+            boundary: coord::BoundaryMode::Wall,
+            ..settings.clone()
+        }
     }
+}
 
-    pub(crate) fn step(&mut self) {
-        // food diffusion
-        'topple: loop {
-            for coord in self.food() {
-                if self.get(coord).should_diffuse() {
-                    self.topple(coord);
-                }
+impl fmt::Display for BenchmarkTask {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+// The outcome of Simulation::import and Simulation::place
+#[derive(Debug, Clone)]
+pub enum Import {
+    // the Agent was placed at the given Coord
+    // carries quarantine statistics when quarantine is enabled
+    Inserted(coord::Coord, Option<Viability>),
+    Rejected(Viability),
+    // there was no empty Tile, or the chosen Tile was occupied
+    NoSpace
+}
+
+// A summary of a Tile's contents at the end of a step
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TileRecord {
+    Empty,
+    Agent { marker: Option<u8>, fitness: u8 },
+    Food(u8),
+    Wall,
+    Water
+}
+
+impl fmt::Display for TileRecord {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        use TileRecord::*;
+        match self {
+            Empty => write!(f, "Empty"),
+            Agent { marker: Some(marker), fitness } => write!(f, "Agent (lineage {}, fitness {})", marker, fitness),
+            Agent { marker: None, fitness } => write!(f, "Agent (fitness {})", fitness),
+            Food(density) => write!(f, "Food ({})", density),
+            Wall => write!(f, "Wall"),
+            Water => write!(f, "Water")
+        }
+    }
+}
+
+// The recent contents of a single watched Tile, oldest first
+#[derive(Debug, Clone)]
+pub struct TileHistory {
+    pub coord: coord::Coord,
+    pub records: VecDeque<(u64, TileRecord)>
+}
+
+impl TileHistory {
+    const SIZE: usize = 128;
+
+    fn record(&mut self, tick: u64, record: TileRecord) {
+        if self.records.len() == Self::SIZE {
+            self.records.pop_front();
+        }
+
+        self.records.push_back((tick, record));
+    }
+
+    // The number of times an Agent arrived at the Tile
+    pub fn visits(&self) -> usize {
+        let mut previous = None;
+        self.records.iter().filter(|(_, record)| {
+            let arrived = matches!(record, TileRecord::Agent { .. })
+                && !matches!(previous, Some(TileRecord::Agent { .. }));
+
+            previous = Some(*record);
+            arrived
+        } ).count()
+    }
+
+    pub fn mean_food(&self) -> f32 {
+        let total = self.records.iter().fold(0u32, |total, (_, record)| {
+            match record {
+                TileRecord::Food(density) => total + *density as u32,
+                _ => total
             }
+        } );
 
-            let mut invalid = false;
-            self.food().drain(0..).for_each(|coord| {
-                if self.get(coord).should_diffuse() {
-                    invalid = true;
+        total as f32 / self.records.len().max(1) as f32
+    }
+}
+
+// What the inspector shows for a clicked Tile
+// Only the Agent's id is carried, as the inspector reads everything else from the Agent itself
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TileSummary {
+    Agent(u64),
+    Food {
+        density: u8,
+        toxic: bool,
+        // the chance that a unit of the food decays during the next step
+        decay: f32,
+        diffusion: Diffusion
+    },
+    Empty(Diffusion),
+    Wall,
+    Water
+}
+
+// How food moves between a Tile and its neighbors
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Diffusion {
+    // neighbors that food spreading from the Tile would land in
+    pub open: usize,
+    // neighbors holding an Agent, a wall or water, where spreading food is spilled
+    pub blocked: usize,
+    // neighbors across the barrier, where spreading food is also spilled
+    pub divided: usize,
+    // neighbors dense enough to spread food onto the Tile during the next step
+    pub toppling: usize
+}
+
+impl fmt::Display for Diffusion {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Neighbors\n  open to diffusion: {}\n  blocked: {}", self.open, self.blocked)?;
+        if self.divided > 0 {
+            write!(f, "\n  across the barrier: {}", self.divided)?;
+        }
+
+        write!(f, "\n  spreading food here next step: {}", self.toppling)
+    }
+}
+
+impl fmt::Display for TileSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        use TileSummary::*;
+        match self {
+            Agent(id) => write!(f, "Agent {}", id),
+            Food { density, toxic, decay, diffusion } => {
+                write!(f, "{} (density {})\n\nDecays next step with probability {:.2}\n",
+                    if *toxic { "Toxic food" } else { "Food" },
+                    density,
+                    decay)?;
+
+                if *density > tile::Tile::DIFFUSION_THRESHOLD {
+                    writeln!(f, "Spreads to its neighbors next step")?;
+                } else {
+                    writeln!(f, "Spreads to its neighbors above density {}", tile::Tile::DIFFUSION_THRESHOLD)?;
                 }
-            } );
 
-            if !invalid {
-                break 'topple;
-            }
+                write!(f, "\n{}", diffusion)
+            },
+            Empty(diffusion) => write!(f, "Empty\n\n{}", diffusion),
+            Wall => write!(f, "Wall"),
+            Water => write!(f, "Water")
+        }
+    }
+}
+
+// A snapshot of world-level metrics, used to compare a run at different points
+#[derive(Debug, Copy, Clone)]
+pub struct WorldStats {
+    pub tick: u64,
+    pub agents: usize,
+    // the sum of food density across every Tile
+    pub food: usize,
+    pub mean_fitness: f32,
+    pub mean_energy: f32,
+    pub mean_brain_size: f32,
+    pub mean_genome_length: f32,
+    pub lineages: usize,
+    pub mutation_rate: f32,
+    pub stagnant_epochs: u32,
+    // always 1 without seasons
+    pub abundance: f32
+}
+
+impl WorldStats {
+    // The label and value of each metric, in display order
+    pub fn metrics(&self) -> [(&'static str, f32); 10] {
+        [
+            ("agents", self.agents as f32),
+            ("food", self.food as f32),
+            ("mean fitness", self.mean_fitness),
+            ("mean energy", self.mean_energy),
+            ("mean brain size", self.mean_brain_size),
+            ("mean genome length", self.mean_genome_length),
+            ("marked lineages", self.lineages as f32),
+            ("mutation rate", self.mutation_rate),
+            ("stagnant epochs", self.stagnant_epochs as f32),
+            ("food abundance", self.abundance)
+        ]
+    }
+}
+
+// Approximate memory held by major structures, in bytes
+#[derive(Debug, Copy, Clone, Default)]
+pub struct MemoryUsage {
+    pub tiles: usize,
+    pub agents: usize,
+    // tile history, fallen genomes and extinct lineages
+    pub history: usize,
+    // statistics kept by the interface
+    pub statistics: usize,
+    // the snapshots the interface keeps in memory to step back with
+    pub snapshots: usize,
+    pub budget: usize
+}
+
+impl MemoryUsage {
+    // usage above this fraction of the budget raises a warning
+    pub const WARNING_THRESHOLD: f32 = 0.9;
+
+    pub fn total(&self) -> usize {
+        self.tiles + self.agents + self.history + self.statistics + self.snapshots
+    }
+
+    pub fn near_budget(&self) -> bool {
+        self.total() as f32 >= self.budget as f32 * Self::WARNING_THRESHOLD
+    }
+
+    fn readable(bytes: usize) -> String {
+        const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+        let mut value = bytes as f32;
+        let mut unit = 0;
+        while value >= 1024f32 && unit < UNITS.len() - 1 {
+            value /= 1024f32;
+            unit += 1;
+        }
+
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+impl fmt::Display for MemoryUsage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Memory: {} of {}\n  tiles: {}\n  agents: {}\n  history: {}\n  statistics: {}\n  snapshots: {}",
+            Self::readable(self.total()),
+            Self::readable(self.budget),
+            Self::readable(self.tiles),
+            Self::readable(self.agents),
+            Self::readable(self.history),
+            Self::readable(self.statistics),
+            Self::readable(self.snapshots)
+        )
+    }
+}
+
+/// A world of Agents, food, walls and water on a grid, advanced one step at a time.
+/// Every source of randomness descends from the settings' seed, so a seeded run always plays out the same way.
+/// Saved worlds hold everything except the watched Tile's history and any pending replay.
+/// Cloning a Simulation forks the run, the clone carries on independently.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct Simulation {
+    tiles: tile::TileMap,
+    settings: SimulationSettings,
+    // the settings the run started with, before any rules were changed
+    // None in saves from before they were kept, which are taken to have started with their current settings
+    #[serde(default)]
+    initial: Option<SimulationSettings>,
+    prng: Prng,
+    tick: u64,
+    fallen: HashMap<u8, Vec<gene::Gene>>,
+    extinctions: Vec<Extinction>,
+    #[serde(skip)]
+    watched: Option<TileHistory>,
+    interactions: InteractionLog,
+    // the id of the Agent responsible for the food at each Coord, if known
+    #[serde(with = "provenance")]
+    provenance: HashMap<coord::Coord, u64>,
+    events: EventLog,
+    // saves from before the ledger open one at the world's energy when loaded
+    #[serde(default)]
+    ledger: Ledger,
+    // empty in saves from before the morgue
+    #[serde(default)]
+    morgue: Morgue,
+    // every intervention made so far, and those still to be applied when replaying
+    interventions: Vec<(u64, Intervention)>,
+    #[serde(skip)]
+    scheduled: VecDeque<(u64, Intervention)>,
+    // multiplies the mutation rate of offspring, raised while the population is stagnating
+    mutation_rate: f32,
+    stagnation: Stagnation,
+    // temporary global modifiers, kept after they end so they can be charted
+    #[serde(default)]
+    pulses: Pulses,
+    // the number of Agents that have crossed the Barrier into the left and right sides
+    #[serde(default)]
+    migrants: [u64; 2],
+    // None unless the settings include a Climate
+    #[serde(default)]
+    temperature: Option<Temperature>,
+    // the severity of the weather, which stays at 0 unless the settings include Weather
+    #[serde(default)]
+    weather: f32,
+    // the actions taken during the latest step, in the order they were applied
+    #[serde(skip)]
+    actions: Vec<StepAction>,
+    // the events recorded during the latest step, which the EventLog may have already evicted in large worlds
+    #[serde(skip)]
+    step_events: Vec<Event>,
+    #[serde(skip)]
+    births: usize,
+    #[serde(skip)]
+    deaths: usize,
+    next_id: u64
+}
+
+impl Simulation {
+    // the number of random genomes tried for each starting Agent before giving up
+    const GENERATION_ATTEMPTS: usize = 1000;
+
+    /// Creates a world from the settings, filling it with food, terrain and random Agents.
+    /// A seed is drawn and stored in the settings if they don't have one.
+    pub fn new(mut settings: SimulationSettings) -> Self {
+        // unseeded runs draw a seed from entropy, and keep it so the run can be reproduced
+        let seed = *settings.seed.get_or_insert_with(rand::random);
+        let mut prng = Prng::seed_from_u64(seed);
+
+        let tiles = {
+            let mut t = tile::TileMap::new(settings.dimensions, settings.storage, settings.boundary);
+            t.cache_brains = settings.cache_brains;
+
+            // nothing else can be placed inside a wall
+            if let Some(walls) = settings.world.walls {
+                let perlin = Perlin::new(prng.gen());
+                for x in 0..settings.dimensions.width {
+                    for y in 0..settings.dimensions.height {
+                        if perlin.get([x as f64 * walls.frequency, y as f64 * walls.frequency]) > walls.threshold {
+                            let _ = t.put(coord::Coord::new(x, y), tile::Tile::Wall);
+                        }
+                    }
+                }
+            }
+
+            if let Some(water) = settings.world.water {
+                let perlin = Perlin::new(prng.gen());
+                for x in 0..settings.dimensions.width {
+                    for y in 0..settings.dimensions.height {
+                        let coord = coord::Coord::new(x, y);
+                        if !t.exists(coord) && perlin.get([x as f64 * water.frequency, y as f64 * water.frequency]) > water.threshold {
+                            let _ = t.put(coord, tile::Tile::Water);
+                        }
+                    }
+                }
+            }
+
+            // the number of Tiles still available to spawn on
+            let mut open = (0..settings.dimensions.width)
+                .flat_map(|x| (0..settings.dimensions.height).map(move |y| coord::Coord::new(x, y)))
+                .filter(|coord| !t.exists(*coord) && settings.world.spawn.contains(*coord, settings.dimensions))
+                .count();
+
+            for id in 0..settings.agents {
+                if open == 0 {
+                    tracing::warn!("the spawn region is full, only {} of {} agents were placed", id, settings.agents);
+                    break;
+                }
+
+                // some complexities (0, for instance) can never produce a working brain
+                let agent = (0..Self::GENERATION_ATTEMPTS).find_map(|_| {
+                    match agent::Agent::from_prng(settings.complexity, &mut prng) {
+                        Ok(agent) => Some(agent),
+                        Err(e) => {
+                            tracing::debug!("discarded a randomly generated agent: {}", e);
+                            None
+                        }
+                    }
+                } );
+
+                let mut agent = match agent {
+                    Some(agent) => agent,
+                    None => {
+                        tracing::warn!("no working agent could be generated with complexity {}, only {} of {} agents were placed",
+                            settings.complexity, id, settings.agents);
+                        break;
+                    }
+                };
+
+                agent.id = id as u64;
+
+                'occupied: loop {
+                    let coord = coord::Coord::new(
+                        prng.gen_range(0..settings.dimensions.width),
+                        prng.gen_range(0..settings.dimensions.height)
+                    );
+
+                    if !t.exists(coord) && settings.world.spawn.contains(coord, settings.dimensions) {
+                        let _ = t.put(coord, tile::Tile::new_agent(agent));
+                        open -= 1;
+                        break 'occupied;
+                    }
+                }
+            }
+
+            // food is placed after the agents, so it can never crowd them out
+            let perlin = Perlin::new(prng.gen());
+            for x in 0..settings.dimensions.width {
+                for y in 0..settings.dimensions.height {
+                    let coord = coord::Coord::new(x, y);
+                    if !t.exists(coord) {
+                        let density = settings.food.density(coord, settings.dimensions, &perlin, &mut prng);
+                        if density > 0 {
+                            let _ = t.put(coord, Self::grow_food(density, settings.toxicity, &mut prng));
+                        }
+                    }
+                }
+            }
+
+            if let Some(clusters) = settings.world.clusters {
+                for _ in 0..clusters.count {
+                    let x = prng.gen_range(0f32..settings.dimensions.width as f32);
+                    let y = prng.gen_range(0f32..settings.dimensions.height as f32);
+
+                    // clusters are clipped by the edges of the world
+                    let reach = clusters.radius.ceil() as usize;
+                    let columns = (x as usize).saturating_sub(reach)..(x as usize + reach + 1).min(settings.dimensions.width);
+                    for column in columns {
+                        let rows = (y as usize).saturating_sub(reach)..(y as usize + reach + 1).min(settings.dimensions.height);
+                        for row in rows {
+                            let coord = coord::Coord::new(column, row);
+                            let distance = distance(coord, x, y);
+                            if distance > clusters.radius {
+                                continue;
+                            }
+
+                            // overlapping food keeps the greater density
+                            let density = FoodDistribution::scale(1f32 - distance / clusters.radius);
+                            if !t.exists(coord) || (t.contains_food(coord) && t.get(coord).food() < density) {
+                                let _ = t.put(coord, Self::grow_food(density, settings.toxicity, &mut prng));
+                            }
+                        }
+                    }
+                }
+            }
+
+            t
+        };
+
+        let temperature = settings.climate.map(|climate| {
+            Temperature::new(climate.field, settings.dimensions.width, settings.dimensions.height, &mut prng)
+        } );
+
+        let ledger = Ledger::new(Self::energy(&tiles));
+
+        Self {
+            tiles,
+            prng,
+            tick: 0,
+            fallen: HashMap::new(),
+            extinctions: Vec::new(),
+            watched: None,
+            interactions: InteractionLog::new(settings.interaction_window),
+            provenance: HashMap::new(),
+            events: EventLog::default(),
+            ledger,
+            morgue: Morgue::default(),
+            interventions: Vec::new(),
+            scheduled: VecDeque::new(),
+            mutation_rate: 1f32,
+            stagnation: Stagnation::default(),
+            pulses: Pulses::default(),
+            migrants: [0; 2],
+            temperature,
+            weather: settings.weather.map_or(0f32, |weather| weather.initial()),
+            actions: Vec::new(),
+            step_events: Vec::new(),
+            births: 0,
+            deaths: 0,
+            next_id: settings.agents as u64,
+            initial: Some(settings.clone()),
+            settings
+        }
+    }
+
+    /// Advances the world by a single tick, in which every Agent senses, acts, and may reproduce or die.
+    pub fn step(&mut self) {
+        self.apply_scheduled();
+
+        self.tick += 1;
+        self.actions.clear();
+        self.step_events.clear();
+        self.births = 0;
+        self.deaths = 0;
+
+        if let (Some(climate), Some(temperature)) = (self.settings.climate, self.temperature.as_mut()) {
+            temperature.diffuse(climate.field);
+        }
+
+        // the prng is only drawn from when the weather is enabled
+        if let Some(weather) = self.settings.weather {
+            self.weather = weather.advance(self.weather, &mut self.prng);
+        }
+
+        // food diffusion
+        'topple: loop {
+            for coord in self.food() {
+                if self.get(coord).should_diffuse() {
+                    self.topple(coord);
+                }
+            }
+
+            let mut invalid = false;
+            self.food().drain(0..).for_each(|coord| {
+                if self.get(coord).should_diffuse() {
+                    invalid = true;
+                }
+            } );
+
+            if !invalid {
+                break 'topple;
+            }
+        }
+
+        // handle deaths before births
+        for coord in self.agents() {
+            if self.should_die(coord) {
+                let cause = if self.get(coord).agent().starving() { Cause::Starved } else { Cause::Dehydrated };
+                self.kill(coord, cause);
+            }
+        }
+
+        // handle births
+        let mutation_rate = self.mutation_rate();
+        let threshold = self.reproduction_threshold();
+        for coord in self.agents() {
+            if self.prng.gen_range(u8::from(threshold)..u8::from(ux::u5::MAX))
+                < u8::from(self.get(coord).agent().fitness) {
+                let child_coord = self.tiles.facing(coord, self.get(coord).agent().direction.opposite());
+
+                // offspring are never born across the barrier
+                let child_coord = child_coord.filter(|child_coord| !self.exists(*child_coord) && !self.divided(coord, *child_coord));
+                if let Some(child_coord) = child_coord {
+                    self.get(coord).update_agent(|mut agent| {
+                        agent.fitness = threshold;
+                    } );
+
+                    let child = self.tiles.get(coord).agent().reproduce(&self.settings.mutation, mutation_rate, &mut self.prng);
+                    match child {
+                        Ok(mut child) => {
+                            child.id = self.next_id();
+                            child.born = self.tick;
+                            self.get(coord).update_agent(|mut agent| agent.offspring += 1);
+
+                            self.ledger.book(Flow::Born, u8::from(child.energy) as u64);
+                            self.log(child_coord, EventKind::Birth { parent: self.id(coord), child: child.id });
+
+                            let _ = self.tiles.put(child_coord, tile::Tile::new_agent(child));
+                        },
+                        Err(e) => tracing::debug!(tick = self.tick, ?coord, "offspring was not viable: {}", e)
+                    }
+                }
+
+            }
+        }
+
+        // agents perform actions
+        match self.settings.update {
+            UpdateMode::Sequential => {
+                for coord in self.agents() {
+                    if self.exists(coord) {
+                        if let tile::Tile::Agent(..) = self.get(coord) {
+                            if let Some(action) = self.decide(coord) {
+                                self.act(coord, action);
+                            }
+                        }
+                    }
+                }
+            },
+            UpdateMode::Synchronous => {
+                // no Tile changes until every agent has decided
+                let intents = self.decide_all();
+                self.resolve(intents);
+            }
+        }
+
+        // food randomly decays, and decays up to twice as often in the depths of winter or during a storm
+        let scarcity = 1f32 - self.abundance();
+        let storm = self.storm();
+        for coord in self.food() {
+            let decays = self.prng.gen_range(0..=tile::Tile::DIFFUSION_THRESHOLD) == self.get(coord).food()
+                || (scarcity > 0f32 && self.prng.gen_bool((scarcity / (tile::Tile::DIFFUSION_THRESHOLD + 1) as f32) as f64))
+                || (storm > 0f32 && self.prng.gen_bool((storm / (tile::Tile::DIFFUSION_THRESHOLD + 1) as f32) as f64));
+
+            if decays {
+                self.remove_food_at(coord);
+                self.ledger.book(Flow::Decayed, 1);
+            }
+        }
+
+        self.adapt_mutation_rate();
+
+        for pulse in self.pulses.all().iter().filter(|pulse| pulse.end == self.tick) {
+            tracing::info!(tick = self.tick, "the {} pulse ended", pulse.modifier);
+        }
+
+        // archive lineages whose last member died this step
+        let living = self.markers();
+        let mut fallen = self.fallen.drain().collect::<Vec<(u8, Vec<gene::Gene>)>>();
+        fallen.sort_by_key(|(marker, ..)| *marker);
+        for (marker, genome) in fallen {
+            if !living.contains(&marker) {
+                self.extinctions.push(Extinction { marker, genome });
+            }
+        }
+
+        if let Some(coord) = self.watched.as_ref().map(|watched| watched.coord) {
+            let record = self.tile_record(coord);
+            if let Some(watched) = self.watched.as_mut() {
+                watched.record(self.tick, record);
+            }
+        }
+
+        self.ledger.close(self.tick, Self::energy(&self.tiles));
+    }
+
+    // At the end of each epoch, raises the mutation rate if fitness has stagnated,
+    // otherwise relaxes it back towards normal
+    fn adapt_mutation_rate(&mut self) {
+        let hypermutation = match self.settings.hypermutation {
+            Some(hypermutation) if self.tick.is_multiple_of(hypermutation.epoch.max(1)) => hypermutation,
+            _ => return
+        };
+
+        let fitness = self.statistics().mean_fitness;
+        if fitness > self.stagnation.best {
+            self.stagnation.best = fitness;
+            self.stagnation.epochs = 0;
+        } else {
+            self.stagnation.epochs += 1;
+        }
+
+        if self.stagnation.epochs >= hypermutation.patience {
+            tracing::info!(tick = self.tick, "fitness stagnated for {} epochs, raising the mutation rate", self.stagnation.epochs);
+
+            // improvement is measured from here on
+            self.stagnation = Stagnation { best: fitness, epochs: 0 };
+            self.mutation_rate = hypermutation.multiplier;
+        } else {
+            self.mutation_rate = 1f32 + (self.mutation_rate - 1f32) * hypermutation.relaxation;
+        }
+    }
+
+    // Asks the Agent at the given Coord to choose an action
+    fn decide(&mut self, coord: coord::Coord) -> Option<gene::ActionType> {
+        self.tiles.get(coord).agent().process(
+            &Sense::new(&self.tiles, coord, self.settings.neighborhood, self.temperature(coord), self.weather),
+            self.settings.hysteresis,
+            self.settings.selection,
+            &mut self.prng
+        )
+    }
+
+    // Asks every Agent to choose an action, spreading the brains across threads
+    // Senses and seeds are gathered up front, so the outcome doesn't depend on the thread count
+    fn decide_all(&mut self) -> Vec<(coord::Coord, gene::ActionType)> {
+        // matches the order of TileMap::agents_mut
+        let mut coords = self.agents();
+        coords.sort();
+
+        let senses = coords.iter()
+            .map(|coord| Sense::new(&self.tiles, *coord, self.settings.neighborhood, self.temperature(*coord), self.weather))
+            .collect::<Vec<Sense>>();
+        let seeds = coords.iter()
+            .map(|_| self.prng.gen())
+            .collect::<Vec<u64>>();
+
+        let hysteresis = self.settings.hysteresis;
+        let selection = self.settings.selection;
+        self.tiles.agents_mut()
+            .into_par_iter()
+            .zip(senses)
+            .zip(seeds)
+            .filter_map(|(((coord, agent), sense), seed)| {
+                let mut prng = Prng::seed_from_u64(seed);
+                agent.process(&sense, hysteresis, selection, &mut prng)
+                    .map(|action| (coord, action))
+            } )
+            .collect()
+    }
+
+    // Applies every action at once, judging each one against the world as it was when the agents decided
+    // The order of `intents` does not affect the outcome
+    fn resolve(&mut self, intents: Vec<(coord::Coord, gene::ActionType)>) {
+        use gene::ActionType::*;
+
+        let facing = |simulation: &Self, coord: coord::Coord| {
+            simulation.tiles.facing(coord, simulation.get(coord).agent().direction)
+        };
+
+        // moves lay claim to the facing tile, and food production to every tile it yields to
+        let mut claims: HashMap<coord::Coord, usize> = HashMap::new();
+        let mut victims: HashMap<coord::Coord, Cause> = HashMap::new();
+        // the Agents whose actions had an effect, by the Coord they acted from
+        let mut successful: HashSet<coord::Coord> = HashSet::new();
+        for (coord, action) in intents.iter() {
+            let target = match facing(self, *coord) {
+                Some(target) => target,
+                None => {
+                    // agents that step off a deadly edge are lost
+                    if *action == Move && self.settings.boundary == coord::BoundaryMode::Kill {
+                        victims.insert(*coord, Cause::FellOffEdge);
+                    }
+
+                    continue;
+                }
+            };
+
+            match action {
+                Move => *claims.entry(target).or_insert(0) += 1,
+                // each tile is claimed once, even if a pattern folds back onto it in a small world
+                ProduceFood => for tile in self.yield_tiles(*coord).into_iter().collect::<HashSet<coord::Coord>>() {
+                    *claims.entry(tile).or_insert(0) += 1;
+                },
+                // in a world one tile across, an Agent can face itself
+                Kill if self.contains_agent(target) && target != *coord && self.preys_on(*coord, target) => {
+                    self.interact(*coord, target, InteractionKind::Killed);
+                    self.log(target, EventKind::Kill { killer: self.id(*coord), victim: self.id(target) });
+
+                    successful.insert(*coord);
+
+                    // a victim with several killers is credited to the oldest of them
+                    let killer = self.id(*coord);
+                    victims.entry(target)
+                        .and_modify(|cause| if let Cause::Killed { killer: first } = cause {
+                            *first = (*first).min(killer);
+                        } )
+                        .or_insert(Cause::Killed { killer });
+                },
+                _ => {  }
+            }
+        }
+
+        // classify each action before anything changes
+        // Agents killed this step neither produce, move nor drink, as their Tiles are cleared before those apply
+        let mut productions = Vec::new();
+        let mut moves = Vec::new();
+        let mut meals = Vec::new();
+        let mut drinks = Vec::new();
+        for (coord, action) in intents.iter() {
+            let target = match facing(self, *coord) {
+                Some(target) => target,
+                None => continue
+            };
+
+            match action {
+                // like moves, production only lands on tiles that no other agent laid claim to
+                ProduceFood if !victims.contains_key(coord) => productions.extend(self.yield_tiles(*coord)
+                    .into_iter()
+                    .filter(|tile| claims[tile] == 1 && !self.contains_agent(*tile))
+                    .map(|tile| (*coord, tile))),
+                Move if claims[&target] == 1 && !victims.contains_key(coord) => {
+                    if !self.exists(target) {
+                        if self.crosses(*coord, target) {
+                            moves.push(*coord);
+                        }
+                    } else if self.tiles.contains_food(target) {
+                        meals.push((*coord, target));
+                    }
+                },
+                // water never runs dry, so drinking can't be contested
+                Drink if self.tiles.contains_water(target) && !victims.contains_key(coord) => drinks.push(*coord),
+                _ => {  }
+            }
+        }
+
+        // all kills land, even if the killer is also killed
+        let mut victims = victims.drain().collect::<Vec<(coord::Coord, Cause)>>();
+        victims.sort_by_key(|(victim, _)| *victim);
+        for (victim, cause) in victims.iter() {
+            self.kill(*victim, *cause);
+        }
+
+        for (coord, target) in productions {
+            if self.produce_food(coord, target) {
+                successful.insert(coord);
+            }
+        }
+
+        for (coord, target) in meals {
+            self.eat(coord, target);
+            successful.insert(coord);
+        }
+
+        for coord in drinks {
+            self.get(coord).update_agent(|mut agent| agent.drink());
+            successful.insert(coord);
+        }
+
+        let mut moved: HashMap<coord::Coord, coord::Coord> = HashMap::new();
+        for coord in moves {
+            let direction = self.get(coord).agent().direction;
+            moved.insert(coord, self.tiles.walk_towards(coord, direction));
+            successful.insert(coord);
+        }
+
+        for (coord, action) in intents {
+            if victims.iter().any(|(victim, _)| *victim == coord) {
+                continue;
+            }
+
+            // turning always takes effect, even while facing a solid boundary
+            let effective = matches!(action, TurnLeft | TurnRight) || successful.contains(&coord);
+
+            let coord = moved.get(&coord).copied().unwrap_or(coord);
+            self.get(coord).update_agent(|mut agent| {
+                match action {
+                    TurnLeft => agent.direction = agent.direction.left(),
+                    TurnRight => agent.direction = agent.direction.right(),
+                    _ => {  }
+                }
+            } );
+
+            self.acted(coord, action, effective);
+        }
+    }
+
+    // returns the Agent's Coord after acting
+    fn act(&mut self, mut coord: coord::Coord, action: gene::ActionType) -> coord::Coord {
+        let direction = self.get(coord).agent().direction;
+        let facing = match self.tiles.facing(coord, direction) {
+            Some(facing) => facing,
+            None => return self.act_at_boundary(coord, action)
+        };
+
+        use gene::ActionType::*;
+        let successful = match action {
+            Move => {
+                if !self.exists(facing) {
+                    let crossed = self.crosses(coord, facing);
+                    if crossed {
+                        coord = self.tiles.walk_towards(coord, direction);
+                    }
+
+                    crossed
+                } else if self.tiles.contains_food(facing) {
+                    self.eat(coord, facing);
+                    true
+                } else {
+                    false
+                }
+            },
+            TurnLeft | TurnRight => {
+                self.get(coord).update_agent(|mut agent| {
+                    agent.direction = match action {
+                        TurnLeft => agent.direction.left(),
+                        TurnRight => agent.direction.right(),
+                        _ => unreachable!()
+                    };
+                } );
+
+                true
+            },
+            Kill => {
+                let prey = self.exists(facing) && self.contains_agent(facing) && facing != coord && self.preys_on(coord, facing);
+                if prey {
+                    self.interact(coord, facing, InteractionKind::Killed);
+                    self.log(facing, EventKind::Kill { killer: self.id(coord), victim: self.id(facing) });
+
+                    self.kill(facing, Cause::Killed { killer: self.id(coord) });
+                }
+
+                prey
+            },
+            ProduceFood => {
+                // every tile is tried, even once some food has taken
+                let mut produced = false;
+                for tile in self.yield_tiles(coord) {
+                    produced |= self.produce_food(coord, tile);
+                }
+
+                produced
+            },
+            Drink => {
+                let water = self.tiles.contains_water(facing);
+                if water {
+                    self.get(coord).update_agent(|mut agent| agent.drink());
+                }
+
+                water
+            }
+        };
+
+        self.acted(coord, action, successful);
+
+        coord
+    }
+
+    // Handles an action taken while facing a solid boundary
+    // Only turning has any effect, unless the boundary is deadly and the Agent walks into it
+    fn act_at_boundary(&mut self, coord: coord::Coord, action: gene::ActionType) -> coord::Coord {
+        use gene::ActionType::*;
+        match action {
+            Move if self.settings.boundary == coord::BoundaryMode::Kill => {
+                self.kill(coord, Cause::FellOffEdge);
+                return coord;
+            },
+            TurnLeft | TurnRight => {
+                self.get(coord).update_agent(|mut agent| {
+                    agent.direction = match action {
+                        TurnLeft => agent.direction.left(),
+                        TurnRight => agent.direction.right(),
+                        _ => unreachable!()
+                    };
+                } );
+            },
+            _ => {  }
+        }
+
+        self.acted(coord, action, matches!(action, TurnLeft | TurnRight));
+
+        coord
+    }
+
+    // Charges the Agent for its action and adds it to the Agent's history, noting when this leaves it starving
+    // `successful` is false if the action had no effect, such as moving into a wall
+    fn acted(&mut self, coord: coord::Coord, action: gene::ActionType, successful: bool) {
+        self.actions.push(StepAction { agent: self.id(coord), coord, action });
+
+        let starving = self.get(coord).agent().starving();
+        let energy = self.get(coord).agent().energy;
+
+        let metabolism = self.settings.metabolism;
+        let plasticity = self.settings.plasticity;
+        let thirst = self.settings.world.water.is_some();
+        let stress = self.settings.climate.map_or(0f32, |climate| climate.exposure(self.temperature(coord)))
+            + self.settings.crowding.map_or(0f32, |crowding| {
+                let neighbors = self.tiles.neighbors(coord, self.settings.neighborhood).into_iter()
+                    .filter(|neighbor| *neighbor != coord && self.tiles.contains_agent(*neighbor))
+                    .count();
+
+                neighbors.saturating_sub(crowding.capacity) as f32 * crowding.drain
+            } );
+
+        let tick = self.tick;
+        self.get(coord).update_agent(|mut agent| {
+            agent.acted(action, metabolism, stress, plasticity, thirst);
+            agent.remember(tick, action, successful);
+        } );
+
+        let spent = u8::from(energy) - u8::from(self.get(coord).agent().energy);
+        self.ledger.book(Flow::Spent, spent as u64);
+
+        if !starving && self.get(coord).agent().starving() {
+            self.log(coord, EventKind::Starvation { agent: self.id(coord) });
+        }
+    }
+
+    // Tags the Agent at the Coord with a lineage marker
+    pub fn mark(&mut self, coord: coord::Coord, marker: u8) -> Result<(), crate::error::AccessError> {
+        if !self.exists(coord) {
+            return Err(crate::error::AccessError::NoAgent);
+        }
+
+        self.tiles.get(coord).try_update_agent(|mut agent| agent.mark(marker))?;
+        self.interventions.push((self.tick, Intervention::Mark(coord, marker)));
+
+        Ok(())
+    }
+
+    // Kills the Agent at the Coord, leaving remains as though it had died naturally
+    pub fn remove_agent(&mut self, coord: coord::Coord) -> Result<(), crate::error::AccessError> {
+        if !self.tiles.contains_agent(coord) {
+            return Err(crate::error::AccessError::NoAgent);
+        }
+
+        self.kill(coord, Cause::Removed);
+        self.interventions.push((self.tick, Intervention::Kill(coord)));
+
+        Ok(())
+    }
+
+    // Returns false if the Tile is held by an Agent or a wall
+    pub fn place_food(&mut self, coord: coord::Coord) -> bool {
+        if !self.add_food_at(coord, false) {
+            return false;
+        }
+
+        self.ledger.book(Flow::Placed, 1);
+        self.interventions.push((self.tick, Intervention::Feed(coord)));
+
+        true
+    }
+
+    // Returns false unless the Tile was empty and within the world
+    pub fn build_wall(&mut self, coord: coord::Coord) -> bool {
+        if self.exists(coord) || self.tiles.put(coord, tile::Tile::Wall).is_err() {
+            return false;
+        }
+
+        self.interventions.push((self.tick, Intervention::Build(coord)));
+
+        true
+    }
+
+    // Returns false unless the Tile held a wall
+    pub fn erase_wall(&mut self, coord: coord::Coord) -> bool {
+        if !self.tiles.contains_wall(coord) {
+            return false;
+        }
+
+        self.tiles.clear(coord);
+        self.interventions.push((self.tick, Intervention::Erase(coord)));
+
+        true
+    }
+
+    // Returns false if the Tile was empty or held an Agent
+    pub fn clear_tile(&mut self, coord: coord::Coord) -> bool {
+        if !self.exists(coord) || self.tiles.contains_agent(coord) {
+            return false;
+        }
+
+        if self.tiles.contains_food(coord) {
+            self.ledger.book(Flow::Cleared, self.get(coord).food() as u64);
+            self.provenance.remove(&coord);
+        }
+
+        self.tiles.clear(coord);
+        self.interventions.push((self.tick, Intervention::Clear(coord)));
+
+        true
+    }
+
+    // Places an Agent with a random genome of the settings' complexity on an empty Tile
+    // Returns Ok(false) if the Tile was taken
+    pub fn spawn_agent(&mut self, coord: coord::Coord) -> Result<bool, crate::error::GenomeError> {
+        if self.exists(coord) || !self.tiles.in_world(coord) {
+            return Ok(false);
+        }
+
+        // recorded even if generation fails, since every attempt draws from the prng
+        self.interventions.push((self.tick, Intervention::Spawn(coord)));
+
+        let mut generated = Err(crate::error::GenomeError::EmptyGenome);
+        for _ in 0..Self::GENERATION_ATTEMPTS {
+            generated = agent::Agent::from_prng(self.settings.complexity, &mut self.prng);
+            if generated.is_ok() {
+                break;
+            }
+        }
+
+        let mut agent = generated?;
+        agent.id = self.next_id();
+        agent.born = self.tick;
+        self.ledger.book(Flow::Born, u8::from(agent.energy) as u64);
+
+        let _ = self.tiles.put(coord, tile::Tile::new_agent(agent));
+
+        Ok(true)
+    }
+
+    // Applies a global modifier to the following `duration` steps
+    pub fn pulse(&mut self, modifier: Modifier, duration: u64) {
+        let pulse = Pulse { modifier, start: self.tick, end: self.tick.saturating_add(duration) };
+        tracing::info!(tick = self.tick, "the {} pulse began, lasting {} steps", modifier, duration);
+
+        self.pulses.push(pulse);
+        self.interventions.push((self.tick, Intervention::Pulse(modifier, duration)));
+    }
+
+    pub fn pulses(&self) -> &Pulses {
+        &self.pulses
+    }
+
+    pub fn change_rule(&mut self, rule: Rule) {
+        match rule {
+            Rule::Boundary(boundary) => {
+                self.settings.boundary = boundary;
+                self.tiles.boundary = boundary;
+            },
+            Rule::Update(update) => self.settings.update = update,
+            Rule::Neighborhood(neighborhood) => self.settings.neighborhood = neighborhood
+        }
+
+        self.interventions.push((self.tick, Intervention::Rule(rule)));
+    }
+
+    // Writes the entire world to disk as JSON
+    // Loading it resumes the run exactly where it left off, prng included
+    pub fn save_to(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        let contents = serde_json::to_vec(self).map_err(std::io::Error::from)?;
+        crate::platform::write(path, contents)
+    }
+
+    pub fn load_from(path: impl AsRef<std::path::Path>) -> Result<Self, crate::error::LoadError> {
+        use crate::error::LoadError;
+
+        let contents = crate::platform::read_to_string(path).map_err(LoadError::Io)?;
+        let mut simulation: Self = serde_json::from_str(&contents).map_err(LoadError::Parse)?;
+        simulation.settings.validate()?;
+
+        if simulation.ledger.is_empty() {
+            simulation.ledger = Ledger::new(Self::energy(&simulation.tiles));
+        }
+
+        Ok(simulation)
+    }
+
+    /// Captures the run so far, so that it can be replayed.
+    pub fn record(&self) -> Recording {
+        Recording {
+            manifest: crate::manifest::Manifest::new(self.initial.as_ref().unwrap_or(&self.settings).clone()),
+            interventions: self.interventions.clone(),
+            ticks: self.tick
+        }
+    }
+
+    /// Rebuilds the recorded run as it was at the given tick.
+    /// Interventions made after that tick are still applied as the replayed Simulation steps.
+    pub fn replay(recording: &Recording, tick: u64) -> Self {
+        let mut simulation = Self::new(recording.manifest.settings.clone());
+        simulation.scheduled = recording.interventions.iter().cloned().collect();
+        simulation.replay_until(tick);
+        simulation
+    }
+
+    // Steps a replayed Simulation forward to the tick, applying the interventions made on it
+    pub fn replay_until(&mut self, tick: u64) {
+        while self.tick < tick {
+            self.step();
+        }
+
+        self.apply_scheduled();
+    }
+
+    // Applies the scheduled interventions that were made on the current tick
+    fn apply_scheduled(&mut self) {
+        while matches!(self.scheduled.front(), Some((tick, _)) if *tick <= self.tick) {
+            use Intervention::*;
+            match self.scheduled.pop_front().map(|(_, intervention)| intervention) {
+                Some(Import(genome)) => {
+                    let verdict = self.scheduled_verdict();
+                    let _ = self.import_screened(genome, verdict);
+                },
+                Some(Place(coord, genome)) => {
+                    let verdict = self.scheduled_verdict();
+                    let _ = self.place_screened(coord, genome, verdict);
+                },
+                // only meaningful straight after an import or placement
+                Some(Screened(..)) => {  },
+                Some(Mark(coord, marker)) => {
+                    let _ = self.mark(coord, marker);
+                },
+                Some(Kill(coord)) => {
+                    let _ = self.remove_agent(coord);
+                },
+                Some(Feed(coord)) => {
+                    self.place_food(coord);
+                },
+                Some(Build(coord)) => {
+                    self.build_wall(coord);
+                },
+                Some(Erase(coord)) => {
+                    self.erase_wall(coord);
+                },
+                Some(Clear(coord)) => {
+                    self.clear_tile(coord);
+                },
+                Some(Spawn(coord)) => {
+                    let _ = self.spawn_agent(coord);
+                },
+                Some(Pulse(modifier, duration)) => self.pulse(modifier, duration),
+                Some(Rule(rule)) => self.change_rule(rule),
+                None => unreachable!()
+            }
+        }
+    }
+
+    // The recorded quarantine verdict of the import or placement being replayed
+    // Recordings made before verdicts were kept have none, and quarantine is run again
+    fn scheduled_verdict(&mut self) -> Option<bool> {
+        match self.scheduled.front() {
+            Some((tick, Intervention::Screened(viable))) if *tick <= self.tick => {
+                let viable = *viable;
+                self.scheduled.pop_front();
+                Some(viable)
+            },
+            _ => None
+        }
+    }
+
+    const QUARANTINE_SIZE: iced::Size<usize> = iced::Size::new(16, 16);
+    const QUARANTINE_STEPS: usize = 64;
+
+    // Inserts an Agent built from the given genome at a random empty Tile
+    // When quarantine is enabled, the Agent must first prove viable in an isolated arena
+    pub fn import(&mut self, genome: String) -> Result<Import, crate::error::GenomeError> {
+        self.import_screened(genome, None)
+    }
+
+    fn import_screened(&mut self, genome: String, verdict: Option<bool>) -> Result<Import, crate::error::GenomeError> {
+        // recorded even if the import fails, since a failed import can still draw from the prng
+        self.interventions.push((self.tick, Intervention::Import(genome.clone())));
+
+        let agent = agent::Agent::from_string(genome, &mut self.prng)?;
+
+        let viability = match self.screen(&agent, verdict) {
+            Ok(viability) => viability,
+            Err(viability) => return Ok(Import::Rejected(viability))
+        };
+
+        let mut empty = Vec::new();
+        for x in 0..self.tiles.dimensions.width {
+            for y in 0..self.tiles.dimensions.height {
+                let coord = coord::Coord::new(x, y);
+                if !self.exists(coord) {
+                    empty.push(coord);
+                }
+            }
+        }
+
+        if empty.is_empty() {
+            return Ok(Import::NoSpace);
+        }
+
+        let mut agent = agent;
+        agent.id = self.next_id();
+        agent.born = self.tick;
+        self.ledger.book(Flow::Born, u8::from(agent.energy) as u64);
+
+        let coord = empty[self.prng.gen_range(0..empty.len())];
+        let _ = self.tiles.put(coord, tile::Tile::new_agent(agent));
+
+        Ok(Import::Inserted(coord, viability))
+    }
+
+    // Inserts an Agent built from the given genome on the empty Tile at the Coord
+    // Like an import, the Agent must first prove viable when quarantine is enabled
+    pub fn place(&mut self, coord: coord::Coord, genome: String) -> Result<Import, crate::error::GenomeError> {
+        self.place_screened(coord, genome, None)
+    }
+
+    fn place_screened(&mut self, coord: coord::Coord, genome: String, verdict: Option<bool>) -> Result<Import, crate::error::GenomeError> {
+        if self.exists(coord) || !self.tiles.in_world(coord) {
+            return Ok(Import::NoSpace);
+        }
+
+        // recorded even if placing fails, since building the Agent draws from the prng
+        self.interventions.push((self.tick, Intervention::Place(coord, genome.clone())));
+
+        let mut agent = agent::Agent::from_string(genome, &mut self.prng)?;
+
+        let viability = match self.screen(&agent, verdict) {
+            Ok(viability) => viability,
+            Err(viability) => return Ok(Import::Rejected(viability))
+        };
+
+        agent.id = self.next_id();
+        agent.born = self.tick;
+        self.ledger.book(Flow::Born, u8::from(agent.energy) as u64);
+
+        let _ = self.tiles.put(coord, tile::Tile::new_agent(agent));
+
+        Ok(Import::Inserted(coord, viability))
+    }
+
+    // Tries an incoming Agent out in quarantine, if it is enabled
+    // Returns Err if the Agent proved unviable, or if the recorded verdict being replayed says it did
+    // The verdict is recorded, so that replays accept and reject the same Agents
+    fn screen(&mut self, agent: &agent::Agent, verdict: Option<bool>) -> Result<Option<Viability>, Viability> {
+        if !self.settings.quarantine {
+            return Ok(None);
+        }
+
+        let viability = self.quarantine(agent.clone());
+        tracing::info!(tick = self.tick, "imported agent {}", viability);
+
+        let viable = match verdict {
+            Some(viable) if viable != viability.viable() => {
+                tracing::warn!(tick = self.tick, "quarantine disagreed with the recording, keeping the recorded verdict");
+                viable
+            },
+            Some(viable) => viable,
+            None => viability.viable()
+        };
+
+        self.interventions.push((self.tick, Intervention::Screened(viable)));
+
+        if !viable {
+            return Err(viability);
+        }
+
+        Ok(Some(viability))
+    }
+
+    // Runs the Agent alone in a small arena scattered with food
+    // Reproduction and death are not simulated, only the Agent's own decisions
+    fn quarantine(&mut self, agent: agent::Agent) -> Viability {
+        let mut arena = Simulation::new(SimulationSettings {
+            dimensions: Self::QUARANTINE_SIZE,
+            agents: 0,
+            seed: Some(self.prng.gen()),
+            food: FoodDistribution::Uniform { probability: 0.1 },
+            // the arena is walled in, so a deadly edge can't end the trial early
+            boundary: match self.settings.boundary {
+                coord::BoundaryMode::Kill => coord::BoundaryMode::Wall,
+                boundary => boundary
+            },
+            ..self.settings.clone()
+        } );
+
+        let mut coord = coord::Coord::new(
+            Self::QUARANTINE_SIZE.width / 2,
+            Self::QUARANTINE_SIZE.height / 2
+        );
+
+        let mut viability = Viability {
+            cost: agent.evaluation_cost(),
+            ..Viability::default()
+        };
+
+        arena.tiles.clear(coord);
+        let _ = arena.tiles.put(coord, tile::Tile::new_agent(agent));
+
+        for _ in 0..Self::QUARANTINE_STEPS {
+            let action = arena.decide(coord);
+            viability.steps += 1;
+
+            if let Some(action) = action {
+                viability.actions += 1;
+                coord = arena.act(coord, action);
+            }
+
+            if arena.should_die(coord) {
+                viability.starved = true;
+                break;
+            }
+        }
+
+        viability
+    }
+
+    const BENCHMARK_STEPS: usize = 128;
+
+    // Counts the food eaten by an Agent grown from the genome, alone in the task's arena
+    // Only the given settings are read, so benchmarks can run without access to the Simulation
+    pub fn benchmark(settings: &SimulationSettings, genome: Vec<gene::Gene>, task: BenchmarkTask) -> usize {
+        let mut arena = Simulation::new(task.settings(settings));
+
+        let agent = match agent::Agent::new(genome, &mut arena.prng) {
+            Ok(agent) => agent,
+            Err(..) => return 0
+        };
+
+        let mut coord = coord::Coord::new(
+            Self::QUARANTINE_SIZE.width / 2,
+            Self::QUARANTINE_SIZE.height / 2
+        );
+
+        arena.tiles.clear(coord);
+        let _ = arena.tiles.put(coord, tile::Tile::new_agent(agent));
+
+        for _ in 0..Self::BENCHMARK_STEPS {
+            if let Some(action) = arena.decide(coord) {
+                coord = arena.act(coord, action);
+            }
+
+            if arena.should_die(coord) {
+                break;
+            }
+        }
+
+        arena.events.since(0).filter(|event| matches!(event.kind, EventKind::Eat { .. })).count()
+    }
+
+    fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id - 1
+    }
+
+    // Whether the Barrier lies between two Coords
+    fn divided(&self, from: coord::Coord, to: coord::Coord) -> bool {
+        self.settings.barrier.is_some()
+            && Barrier::side(from, self.settings.dimensions) != Barrier::side(to, self.settings.dimensions)
+    }
+
+    // Decides whether an Agent stepping from one Coord to the other makes it, counting those that cross the Barrier
+    // Only attempts to cross the Barrier draw from the prng
+    fn crosses(&mut self, from: coord::Coord, to: coord::Coord) -> bool {
+        let barrier = match self.settings.barrier {
+            Some(barrier) if self.divided(from, to) => barrier,
+            _ => return true
+        };
+
+        let crossed = self.prng.gen_bool(barrier.permeability.clamp(0f32, 1f32) as f64);
+        if crossed {
+            self.migrants[Barrier::side(to, self.settings.dimensions)] += 1;
+        }
+
+        crossed
+    }
+
+    // The statistics of each side of the Barrier, left first
+    // None unless the world is split
+    pub fn sides(&self) -> Option<[SideStats; 2]> {
+        self.settings.barrier?;
+
+        let mut sides = [SideStats::default(); 2];
+        let mut totals = [(0f32, 0f32); 2];
+        for coord in self.agents() {
+            let side = Barrier::side(coord, self.settings.dimensions);
+            let agent = self.get(coord).agent();
+
+            sides[side].agents += 1;
+            sides[side].species[agent.attributes.species() as usize % sides[side].species.len()] += 1;
+            totals[side].0 += u8::from(agent.fitness) as f32;
+            totals[side].1 += agent.genome.len() as f32;
+        }
+
+        for coord in self.food() {
+            sides[Barrier::side(coord, self.settings.dimensions)].food += self.get(coord).food() as usize;
+        }
+
+        for (side, stats) in sides.iter_mut().enumerate() {
+            let count = stats.agents.max(1) as f32;
+            stats.mean_fitness = totals[side].0 / count;
+            stats.mean_genome_length = totals[side].1 / count;
+            stats.migrants = self.migrants[side];
+        }
+
+        Some(sides)
+    }
+
+    // assumes both Tiles are Agents
+    // Whether the Agent at `predator` is allowed to kill the Agent at `prey`
+    // Nothing can be killed during a truce
+    fn preys_on(&self, predator: coord::Coord, prey: coord::Coord) -> bool {
+        if self.pulses.truce(self.tick) {
+            return false;
         }
 
-        // handle deaths before births
-        for coord in self.agents() {
-            if self.should_die(coord) {
-                self.kill(coord);
-            }
+        let species = |coord: coord::Coord| self.get(coord).agent().attributes.species();
+        self.settings.predation.allows(species(predator), species(prey))
+    }
+
+    // assumes Tile is an Agent
+    fn id(&self, coord: coord::Coord) -> u64 {
+        self.get(coord).agent().id
+    }
+
+    fn log(&mut self, coord: coord::Coord, kind: EventKind) {
+        match kind {
+            EventKind::Birth { .. } => self.births += 1,
+            EventKind::Death { .. } => self.deaths += 1,
+            _ => {  }
         }
 
-        // handle births
-        for coord in self.agents() {
-            if thread_rng().gen_range(u8::from(Self::REPRODUCTION_THRESHOLD)..u8::from(ux::u5::MAX))
-                < u8::from(self.get(coord).agent().fitness) {
-                let child_coord = coord.sample_offset(
-                    coord::Offset::from_direction(
-                        self.get(coord).agent().direction.opposite()),
-                    &self.0.dimensions
-                );
+        let event = Event { tick: self.tick, coord, kind };
+        self.step_events.push(event);
+        self.events.record(event);
+    }
 
-                if !self.exists(child_coord) {
-                    self.get(coord).update_agent(|mut agent| {
-                        agent.fitness = Self::REPRODUCTION_THRESHOLD;
-                    } );
+    // Records an interaction between the Agents at the given Coords
+    fn interact(&mut self, source: coord::Coord, target: coord::Coord, kind: InteractionKind) {
+        let interaction = Interaction {
+            tick: self.tick,
+            source: self.get(source).agent().id,
+            target: self.get(target).agent().id,
+            kind
+        };
 
-                    let child = self.get(coord).agent().reproduce();
-                    if let Ok(child) = child  {
-                        self.0.put(child_coord, tile::Tile::new_agent(child));
-                    }
-                }
+        self.interactions.record(interaction);
+    }
 
-            }
+    // The Agent at `coord` eats a unit of food from `target`, crediting whoever left it there
+    fn eat(&mut self, coord: coord::Coord, target: coord::Coord) {
+        if let Some(source) = self.provenance.get(&target).copied() {
+            let interaction = Interaction {
+                tick: self.tick,
+                source,
+                target: self.get(coord).agent().id,
+                kind: InteractionKind::Fed
+            };
+
+            self.interactions.record(interaction);
         }
 
-        // agents perform actions
-        for coord in self.agents() {
-            if self.exists(coord) {
-                if let tile::Tile::Agent(..) = self.get(coord) {
-                    let action = self.get(coord).agent().process(
-                        &Sense::new(&self.0, coord)
-                    );
+        let toxic = self.get(target).toxic();
 
-                    if let Some(action) = action {
-                        self.act(coord, action);
-                    }
-                }
-            }
-        }
+        self.remove_food_at(target);
+        self.ledger.book(Flow::Eaten, 1);
 
-        // food randomly decays
-        for coord in self.food() {
-            if thread_rng().gen_range(0..=tile::Tile::DIFFUSION_THRESHOLD) == self.get(coord).food() {
-                self.remove_food_at(coord);
-            }
-        }
+        let energy = self.get(coord).agent().energy;
+        self.get(coord).update_agent(|mut agent| {
+            if toxic { agent.poison(); } else { agent.sate(); }
+        } );
+
+        let sated = u8::from(self.get(coord).agent().energy) - u8::from(energy);
+        self.ledger.book(Flow::Sated, sated as u64);
 
+        self.log(coord, EventKind::Eat { agent: self.id(coord) });
     }
 
-    fn act(&mut self, mut coord: coord::Coord, action: gene::ActionType) {
-        let direction = self.get(coord).agent().direction;
-        let facing = coord.sample_offset(
-            coord::Offset::from_direction(direction),
-            &self.0.dimensions
-        );
+    // The tiles that receive food when the Agent at the given Coord produces it
+    // An Agent facing a solid edge produces nothing, whatever its pattern
+    fn yield_tiles(&self, coord: coord::Coord) -> Vec<coord::Coord> {
+        let agent = self.get(coord).agent();
+        let ahead = match self.tiles.facing(coord, agent.direction) {
+            Some(ahead) => ahead,
+            None => return Vec::new()
+        };
 
-        use gene::ActionType::*;
-        match action {
-            Move => {
-                if !self.exists(facing) {
-                    coord = self.0.walk_towards(coord, direction);
+        use agent::YieldPattern::*;
+        let tiles: Vec<coord::Coord> = match self.settings.production.unwrap_or_else(|| agent.attributes.yield_pattern()) {
+            Ahead => vec![ahead],
+            Arc => std::iter::once(ahead)
+                .chain(self.tiles.facing(ahead, agent.direction.left()))
+                .chain(self.tiles.facing(ahead, agent.direction.right()))
+                .collect(),
+            Beneath => self.tiles.facing(coord, agent.direction.opposite()).into_iter().collect()
+        };
 
-                } else if self.0.contains_food(facing) {
-                    self.remove_food_at(facing);
+        // food can't be produced across the barrier
+        tiles.into_iter().filter(|tile| !self.divided(coord, *tile)).collect()
+    }
 
-                    self.get(coord).update_agent(|mut agent| {
-                        agent.sate();
-                    } );
-                }
-            },
-            TurnLeft | TurnRight => {
-                self.get(coord).update_agent(|mut agent| {
-                    agent.direction = match action {
-                        TurnLeft => agent.direction.left(),
-                        TurnRight => agent.direction.right(),
-                        _ => unreachable!()
-                    };
-                } );
-            },
-            Kill => {
-                if self.exists(facing) && self.contains_agent(facing) {
-                    self.kill(facing);
-                }
-            },
-            ProduceFood => {
-                self.add_food_at(facing);
-            }
+    // returns true if the food took
+    fn produce_food(&mut self, coord: coord::Coord, target: coord::Coord) -> bool {
+        // out of season, food often fails to take
+        let abundance = self.abundance();
+        if abundance < 1f32 && !self.prng.gen_bool(abundance as f64) {
+            return false;
         }
 
-        self.get(coord).update_agent(|mut agent| {
-            agent.acted(action);
-        } );
+        // storms spoil produced food too
+        let storm = self.storm();
+        if storm > 0f32 && self.prng.gen_bool(storm as f64) {
+            return false;
+        }
+
+        let toxic = Self::toxin(self.settings.toxicity, &mut self.prng);
+        if !self.add_food_at(target, toxic) {
+            return false;
+        }
+
+        self.ledger.book(Flow::Produced, 1);
+
+        let producer = self.get(coord).agent().id;
+        self.provenance.insert(target, producer);
+
+        self.log(target, EventKind::ProduceFood { agent: producer });
+        true
     }
 
-    fn kill(&mut self, coord: coord::Coord) {
-        if self.0.contains_agent(coord) {
+    fn kill(&mut self, coord: coord::Coord, cause: Cause) {
+        if self.tiles.contains_agent(coord) {
             let amount = self.get(coord).agent().fitness;
-            self.0.clear(coord);
+            let id = self.get(coord).agent().id;
+
+            let obituary = {
+                let agent = self.get(coord).agent();
+                Obituary {
+                    id,
+                    marker: agent.marker,
+                    tick: self.tick,
+                    age: self.tick.saturating_sub(agent.born),
+                    offspring: agent.offspring,
+                    fitness: u8::from(agent.fitness),
+                    cause,
+                    genome: agent.genome.clone()
+                }
+            };
+
+            self.morgue.record(obituary);
+
+            let energy = self.get(coord).agent().energy;
+            self.ledger.book(Flow::Died, u8::from(energy) as u64);
+
+            // remember the genome in case this was the last of its lineage
+            let marker = self.get(coord).agent().marker;
+            if let Some(marker) = marker {
+                let genome = self.get(coord).agent().genome.clone();
+                self.fallen.insert(marker, genome);
+            }
+
+            self.tiles.clear(coord);
+            self.log(coord, EventKind::Death { agent: id });
 
+            let toxic = Self::toxin(self.settings.toxicity, &mut self.prng);
             for _ in 0..u8::from(amount) {
-                self.add_food_at(coord);
+                if self.add_food_at(coord, toxic) {
+                    self.ledger.book(Flow::Remains, 1);
+                }
+            }
+
+            // the remains are credited to the dead Agent
+            if self.tiles.contains_food(coord) {
+                self.provenance.insert(coord, id);
             }
 
             return;
         }
 
-        panic!()
+        tracing::error!(tick = self.tick, ?coord, "attempted to kill a tile without an agent");
+    }
+
+    fn reproduction_threshold(&self) -> ux::u5 {
+        ux::u5::new(self.settings.reproduction_threshold.clamp(1, Basics::MAX_REPRODUCTION_THRESHOLD))
     }
 
     // assumes Tile is an Agent
     fn should_die(&self, coord: coord::Coord) -> bool {
         let fitness = self.get(coord).agent().fitness;
-        let starving = self.get(coord).agent().starving();
+        let starving = self.get(coord).agent().starving() || self.get(coord).agent().dehydrated();
 
-        // Agents have a random chance to die if they are starving
+        // Agents have a random chance to die if they are starving or dehydrated
         // Fitter creatures have a lower chance of dying
-        if starving && fitness < Self::REPRODUCTION_THRESHOLD {
+        if starving && fitness < self.reproduction_threshold() {
             return true;
         }
 
@@ -224,8 +2317,17 @@ impl Simulation {
     }
 
     fn topple(&mut self, coord: coord::Coord) {
-        for neighbor in coord.neighbors(&self.0.dimensions) {
-            self.add_food_at(neighbor);
+        // diffused food carries its toxin with it
+        let toxic = self.get(coord).toxic();
+        let storm = self.storm();
+        for neighbor in self.tiles.neighbors(coord, self.settings.neighborhood) {
+            // food that can't spread into its neighbor is lost, including food that meets the barrier
+            // or is blown away by a storm
+            let blown = storm > 0f32 && self.prng.gen_bool(storm as f64);
+            if self.divided(coord, neighbor) || blown || !self.add_food_at(neighbor, toxic) {
+                self.ledger.book(Flow::Spilled, 1);
+            }
+
             if self.remove_food_at(coord) {
                 break;
             }
@@ -233,30 +2335,47 @@ impl Simulation {
     }
 
     // returns true if food was successfully added
-    fn add_food_at(&mut self, coord: coord::Coord) -> bool {
-        if self.0.contains_food(coord) {
+    // `toxic` only applies to new food, existing food keeps its toxicity
+    fn add_food_at(&mut self, coord: coord::Coord, toxic: bool) -> bool {
+        if self.tiles.contains_food(coord) {
             self.get(coord).add_food();
             return true;
         } else if !self.exists(coord) {
-            self.0.put(coord, tile::Tile::new_food(1));
-            return true;
+            return self.tiles.put(coord, if toxic { tile::Tile::new_toxic_food(1) } else { tile::Tile::new_food(1) }).is_ok();
         }
 
         false
     }
 
+    // decides whether newly grown food is toxic
+    // the prng is left untouched when toxins are disabled, so those runs are unaffected
+    // NaN and out of range toxicities are clamped, rather than left to panic in gen_bool
+    fn toxin(toxicity: f32, prng: &mut Prng) -> bool {
+        toxicity > 0f32 && prng.gen_bool(toxicity.min(1f32) as f64)
+    }
+
+    fn grow_food(density: u8, toxicity: f32, prng: &mut Prng) -> tile::Tile {
+        if Self::toxin(toxicity, prng) {
+            tile::Tile::new_toxic_food(density)
+        } else {
+            tile::Tile::new_food(density)
+        }
+    }
+
     // returns true if the tile is removed
     fn remove_food_at(&mut self, coord: coord::Coord) -> bool {
-        if self.0.contains_food(coord) {
+        if self.tiles.contains_food(coord) {
             if self.get(coord).remove_food() {
-                self.0.clear(coord);
+                self.tiles.clear(coord);
+                self.provenance.remove(&coord);
                 return true;
             }
 
             return false;
         }
 
-        panic!()
+        tracing::error!(tick = self.tick, ?coord, "attempted to remove food from a tile without food");
+        false
     }
 }
 
@@ -268,34 +2387,309 @@ impl Default for Simulation {
 
 // helper methods
 impl Simulation {
-    pub(crate) fn get(&self, coord: coord::Coord) -> &tile::Tile {
-        self.0.get(coord)
+    /// Returns the Tile at the Coord.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the Tile is empty, use [`Simulation::exists`] to check first.
+    pub fn get(&self, coord: coord::Coord) -> &tile::Tile {
+        self.tiles.get(coord)
+    }
+
+    /// Returns whether the Tile at the Coord holds anything.
+    pub fn exists(&self, coord: coord::Coord) -> bool {
+        self.tiles.exists(coord)
+    }
+
+    /// A non-panicking alternative to `get(coord).agent()`, for use outside of the step.
+    pub fn try_agent(&self, coord: coord::Coord) -> Result<std::cell::Ref<'_, agent::Agent>, crate::error::AccessError> {
+        if !self.exists(coord) {
+            return Err(crate::error::AccessError::NoAgent);
+        }
+
+        self.get(coord).try_agent()
+    }
+
+    // Finds the Agent with the given id, wherever it has moved
+    // The hint is checked first, so Agents that stayed put are found at once
+    pub fn locate(&self, id: u64, hint: Option<coord::Coord>) -> Option<coord::Coord> {
+        let is_agent = |coord: &coord::Coord| self.try_agent(*coord).is_ok_and(|agent| agent.id == id);
+        hint.filter(is_agent).or_else(|| self.agents().into_iter().find(is_agent))
+    }
+
+    pub fn contains_agent(&self, coord: coord::Coord) -> bool {
+        self.tiles.contains_agent(coord)
     }
 
-    pub(crate) fn exists(&self, coord: coord::Coord) -> bool {
-        self.0.exists(coord)
+    /// The number of steps taken since the world was created.
+    pub fn tick(&self) -> u64 {
+        self.tick
     }
 
-    pub(crate) fn contains_agent(&self, coord: coord::Coord) -> bool {
-        self.0.contains_agent(coord)
+    /// The settings the world was created from, with the drawn seed filled in.
+    pub fn settings(&self) -> &SimulationSettings {
+        &self.settings
     }
 
-    pub(crate) fn size(&self) -> iced::Size<usize> {
-        self.0.dimensions
+    /// The world's width and height, in Tiles.
+    pub fn size(&self) -> iced::Size<usize> {
+        self.tiles.dimensions
     }
 
-    pub(crate) fn coords(&self) -> Vec<coord::Coord> {
-        self.0.coords()
+    /// The Coord of every occupied Tile, in order.
+    pub fn coords(&self) -> Vec<coord::Coord> {
+        self.tiles.coords()
     }
 
-    pub(crate) fn food(&self) -> Vec<coord::Coord> {
+    /// The Coord of every Tile holding food, in order.
+    pub fn food(&self) -> Vec<coord::Coord> {
         let mut coords = self.coords();
         coords.drain(0..coords.len()).filter(|coord| {
             matches!(self.get(*coord), tile::Tile::Food(..))
         } ).collect::<Vec<coord::Coord>>()
     }
 
-    pub(crate) fn agents(&self) -> Vec<coord::Coord> {
+    // The interface adds the memory held by its own statistics
+    pub fn memory(&self) -> MemoryUsage {
+        use std::mem::size_of;
+
+        let agents = self.agents().iter().map(|coord| self.get(*coord).agent().memory()).sum();
+
+        let genomes = |genome: &Vec<gene::Gene>| genome.capacity() * size_of::<gene::Gene>();
+        let history = self.fallen.values().map(genomes).sum::<usize>()
+            + self.extinctions.iter().map(|extinction| size_of::<Extinction>() + genomes(&extinction.genome)).sum::<usize>()
+            + self.watched.as_ref().map_or(0, |watched| watched.records.capacity() * size_of::<(u64, TileRecord)>())
+            + self.morgue.memory();
+
+        MemoryUsage {
+            tiles: self.tiles.memory(),
+            agents,
+            history,
+            statistics: 0,
+            snapshots: 0,
+            budget: self.settings.memory_budget
+        }
+    }
+
+    // The rate offspring are mutated at this step, raised while hypermutating or during a mutation pulse
+    pub fn mutation_rate(&self) -> f32 {
+        self.settings.mutation_rate * self.mutation_rate * self.pulses.mutation(self.tick)
+    }
+
+    pub fn statistics(&self) -> WorldStats {
+        let agents = self.agents();
+
+        let (fitness, energy, brain_size, genome_length) = agents.iter().fold((0u32, 0u32, 0usize, 0usize), |totals, coord| {
+            let agent = self.get(*coord).agent();
+            (
+                totals.0 + u8::from(agent.fitness) as u32,
+                totals.1 + u8::from(agent.energy) as u32,
+                totals.2 + agent.brain.node_count(),
+                totals.3 + agent.genome.len()
+            )
+        } );
+
+        let mean = |total: f32| total / agents.len().max(1) as f32;
+
+        WorldStats {
+            tick: self.tick,
+            agents: agents.len(),
+            food: self.food().iter().map(|coord| self.get(*coord).food() as usize).sum(),
+            mean_fitness: mean(fitness as f32),
+            mean_energy: mean(energy as f32),
+            mean_brain_size: mean(brain_size as f32),
+            mean_genome_length: mean(genome_length as f32),
+            lineages: self.markers().len(),
+            mutation_rate: self.mutation_rate(),
+            stagnant_epochs: self.stagnation.epochs,
+            abundance: self.abundance()
+        }
+    }
+
+    // Starts recording the contents of the Tile at the given Coord, replacing any previous history
+    pub fn watch(&mut self, coord: coord::Coord) {
+        if matches!(&self.watched, Some(watched) if watched.coord == coord) {
+            return;
+        }
+
+        let mut watched = TileHistory { coord, records: VecDeque::new() };
+        watched.record(self.tick, self.tile_record(coord));
+
+        self.watched = Some(watched);
+    }
+
+    pub fn watched(&self) -> Option<&TileHistory> {
+        self.watched.as_ref()
+    }
+
+    fn tile_record(&self, coord: coord::Coord) -> TileRecord {
+        if !self.exists(coord) {
+            return TileRecord::Empty;
+        }
+
+        match self.get(coord) {
+            tile::Tile::Agent(..) => {
+                let agent = self.get(coord).agent();
+                TileRecord::Agent { marker: agent.marker, fitness: u8::from(agent.fitness) }
+            },
+            tile::Tile::Food(..) => TileRecord::Food(self.get(coord).food()),
+            tile::Tile::Wall => TileRecord::Wall,
+            tile::Tile::Water => TileRecord::Water
+        }
+    }
+
+    // Fails if the Tile holds an Agent that is already borrowed
+    pub fn summarize(&self, coord: coord::Coord) -> Result<TileSummary, crate::error::AccessError> {
+        if !self.exists(coord) {
+            return Ok(TileSummary::Empty(self.diffusion(coord)));
+        }
+
+        Ok(match self.get(coord) {
+            tile::Tile::Agent(..) => TileSummary::Agent(self.get(coord).try_agent()?.id),
+            tile::Tile::Food(..) => {
+                let density = self.get(coord).food();
+                TileSummary::Food {
+                    density,
+                    toxic: self.get(coord).toxic(),
+                    decay: self.decay_probability(density),
+                    diffusion: self.diffusion(coord)
+                }
+            },
+            tile::Tile::Wall => TileSummary::Wall,
+            tile::Tile::Water => TileSummary::Water
+        } )
+    }
+
+    fn diffusion(&self, coord: coord::Coord) -> Diffusion {
+        let mut diffusion = Diffusion::default();
+        for neighbor in self.tiles.neighbors(coord, self.settings.neighborhood) {
+            if self.divided(coord, neighbor) {
+                diffusion.divided += 1;
+                continue;
+            }
+
+            if !self.exists(neighbor) || self.tiles.contains_food(neighbor) {
+                diffusion.open += 1;
+            } else {
+                diffusion.blocked += 1;
+            }
+
+            if self.tiles.contains_food(neighbor) && self.get(neighbor).should_diffuse() {
+                diffusion.toppling += 1;
+            }
+        }
+
+        diffusion
+    }
+
+    // Mirrors the decay rolls made during the step, any of which removes a unit of the food
+    fn decay_probability(&self, density: u8) -> f32 {
+        let sides = (tile::Tile::DIFFUSION_THRESHOLD + 1) as f32;
+
+        // the roll over the density can only match densities up to the threshold
+        let spontaneous = if density <= tile::Tile::DIFFUSION_THRESHOLD { 1f32 / sides } else { 0f32 };
+        let scarcity = ((1f32 - self.abundance()).max(0f32) / sides).min(1f32);
+        let storm = (self.storm() / sides).min(1f32);
+
+        1f32 - (1f32 - spontaneous) * (1f32 - scarcity) * (1f32 - storm)
+    }
+
+    // The recorded events from the given tick onwards
+    // Only the most recent EventLog::CAPACITY events are kept
+    pub fn events_since(&self, tick: u64) -> impl Iterator<Item = &Event> {
+        self.events.since(tick)
+    }
+
+    pub fn interactions(&self) -> &InteractionLog {
+        &self.interactions
+    }
+
+    pub fn ledger(&self) -> &Ledger {
+        &self.ledger
+    }
+
+    // Scales food production and decay, always 1 without seasons
+    pub fn abundance(&self) -> f32 {
+        self.settings.seasons.map_or(1f32, |seasons| seasons.abundance(self.tick))
+    }
+
+    // None without seasons
+    pub fn season(&self) -> Option<&'static str> {
+        self.settings.seasons.map(|seasons| seasons.name(self.tick))
+    }
+
+    // The severity of the weather between 0 and 1, None without Weather
+    pub fn weather(&self) -> Option<f32> {
+        self.settings.weather.map(|_| self.weather)
+    }
+
+    // The chance that food spoils in the current weather, always 0 without Weather
+    fn storm(&self) -> f32 {
+        self.settings.weather.map_or(0f32, |weather| weather.spoilage(self.weather))
+    }
+
+    // Mild everywhere without a Climate
+    pub fn temperature(&self, coord: coord::Coord) -> f32 {
+        self.temperature.as_ref().map_or(Temperature::MILD, |temperature| temperature.get(coord))
+    }
+
+    // What the Agent at the given Coord senses before it chooses its next action
+    pub fn sense(&self, coord: coord::Coord) -> Sense {
+        Sense::new(&self.tiles, coord, self.settings.neighborhood, self.temperature(coord), self.weather)
+    }
+
+    pub fn actions(&self) -> &[StepAction] {
+        &self.actions
+    }
+
+    // Every event recorded during the latest step, unlike events_since which only reaches back EventLog::CAPACITY events
+    pub fn step_events(&self) -> &[Event] {
+        &self.step_events
+    }
+
+    // The births and deaths during the latest step
+    pub fn births(&self) -> usize {
+        self.births
+    }
+
+    pub fn deaths(&self) -> usize {
+        self.deaths
+    }
+
+    pub fn morgue(&self) -> &Morgue {
+        &self.morgue
+    }
+
+    // Imports the genome of a recently deceased Agent, as though it had been pasted in
+    pub fn resurrect(&mut self, id: u64) -> Option<Result<Import, crate::error::GenomeError>> {
+        let genome = self.morgue.get(id)?.genome.clone();
+        Some(self.import(gene::Genome::get(genome)))
+    }
+
+    // The energy held by the world: every unit of food, plus the energy of every Agent
+    fn energy(tiles: &tile::TileMap) -> u64 {
+        tiles.coords().into_iter().fold(0u64, |energy, coord| {
+            energy + match tiles.get(coord) {
+                tile::Tile::Agent(..) => u8::from(tiles.get(coord).agent().energy),
+                tile::Tile::Food(..) => tiles.get(coord).food(),
+                tile::Tile::Wall | tile::Tile::Water => 0
+            } as u64
+        } )
+    }
+
+    // Lineages that are currently marked are archived upon extinction
+    pub fn extinctions(&self) -> &[Extinction] {
+        &self.extinctions
+    }
+
+    pub fn markers(&self) -> HashSet<u8> {
+        self.agents().drain(0..).filter_map(|coord| {
+            self.get(coord).agent().marker
+        } ).collect::<HashSet<u8>>()
+    }
+
+    /// The Coord of every Tile holding an Agent, in order.
+    pub fn agents(&self) -> Vec<coord::Coord> {
         let mut coords = self.coords();
         let mut coords = coords.drain(0..coords.len()).filter(|coord| {
             matches!(self.get(*coord), tile::Tile::Agent(..))
@@ -312,96 +2706,127 @@ impl Simulation {
     }
 }
 
-/*
-
-0: empty tile
-1: agent
-2: food
-
- */
-
-pub(crate) struct Sense {
+pub struct Sense {
     visible_tiles: Vec<u8>,
-    direction: agent::Direction
+    nearby_tiles: Vec<u8>,
+    direction: agent::Direction,
+    last_action: Option<gene::ActionType>,
+    // whether the faced Tile holds toxic food
+    toxic: bool,
+    thirst: ux::u5,
+    temperature: f32,
+    weather: f32
 }
 
 impl Sense {
     const VISION_DISTANCE: usize = 6;
 
-    pub(crate) fn new(tiles: &tile::TileMap, mut coord: coord::Coord) -> Self {
-        let direction = tiles.get(coord).agent().direction;
+    // what each seen Tile holds
+    const EMPTY: u8 = 0;
+    const AGENT: u8 = 1;
+    const FOOD: u8 = 2;
+    // the contents of a wall, or of any Tile beyond a solid boundary
+    const WALL: u8 = 3;
+    const WATER: u8 = 4;
+
+    pub fn new(tiles: &tile::TileMap, mut coord: coord::Coord, neighborhood: coord::Neighborhood, temperature: f32, weather: f32) -> Self {
+        let (direction, last_action, thirst) = {
+            let agent = tiles.get(coord).agent();
+            (agent.direction, agent.last_action(), agent.thirst)
+        };
+
+        // density senses only cover the line of sight, unless the Moore neighborhood is enabled
+        // the faced Tile is already part of the line of sight
+        let facing = tiles.facing(coord, direction);
+        let nearby_tiles = match neighborhood {
+            coord::Neighborhood::VonNeumann => Vec::new(),
+            coord::Neighborhood::Moore => tiles.neighbors(coord, neighborhood)
+                .drain(0..)
+                .filter(|neighbor| Some(*neighbor) != facing)
+                .map(|neighbor| Self::contents(tiles, neighbor))
+                .collect::<Vec<u8>>()
+        };
 
         Self {
             visible_tiles: {
                 let mut visible_tiles = Vec::new();
                 for _ in 0..Self::VISION_DISTANCE {
-                    coord.apply_offset(
-                        coord::Offset::from_direction(direction),
-                        &tiles.dimensions
-                    );
+                    // nothing can be seen past a solid boundary
+                    if !coord.apply_offset(coord::Offset::from_direction(direction), &tiles.dimensions, tiles.boundary) {
+                        visible_tiles.resize(Self::VISION_DISTANCE, Self::WALL);
+                        break;
+                    }
 
-                    visible_tiles.push(
-                        if tiles.exists(coord) {
-                            match tiles.get(coord) {
-                                tile::Tile::Agent(..) => 1,
-                                tile::Tile::Food(..) => 2
-                            }
-                        } else {
-                            0
-                        }
-                    );
+                    // nor past a wall
+                    let contents = Self::contents(tiles, coord);
+                    visible_tiles.push(contents);
+                    if contents == Self::WALL {
+                        visible_tiles.resize(Self::VISION_DISTANCE, Self::WALL);
+                        break;
+                    }
                 }
 
                 visible_tiles
             },
-            direction
+            nearby_tiles,
+            direction,
+            last_action,
+            toxic: facing.is_some_and(|facing| tiles.exists(facing) && tiles.get(facing).toxic()),
+            thirst,
+            temperature,
+            weather
+        }
+    }
+
+    fn contents(tiles: &tile::TileMap, coord: coord::Coord) -> u8 {
+        if tiles.exists(coord) {
+            match tiles.get(coord) {
+                tile::Tile::Agent(..) => Self::AGENT,
+                tile::Tile::Food(..) => Self::FOOD,
+                tile::Tile::Wall => Self::WALL,
+                tile::Tile::Water => Self::WATER
+            }
+        } else {
+            Self::EMPTY
         }
     }
 
-    pub(crate) fn get(&self, sense: &gene::SenseType) -> f32 {
+    // Density senses cover the line of sight, and the Agent's neighborhood if it is Moore
+    fn density(&self, contents: u8) -> f32 {
+        let count = self.visible_tiles.iter()
+            .chain(self.nearby_tiles.iter())
+            .filter(|tile| **tile == contents)
+            .count();
+
+        count as f32 / (self.visible_tiles.len() + self.nearby_tiles.len()) as f32
+    }
+
+    pub fn get(&self, sense: &gene::SenseType) -> f32 {
         use gene::SenseType::*;
         match sense {
             Blocked => {
-                if self.visible_tiles[0] == 1 {
+                if matches!(self.visible_tiles[0], Self::AGENT | Self::WALL | Self::WATER) {
                     1f32
                 } else {
                     0f32
                 }
             },
             Agent => {
-                if self.visible_tiles[0] == 1 {
+                if self.visible_tiles[0] == Self::AGENT {
                     1f32
                 } else {
                     0f32
                 }
             },
-            AgentDensity => {
-                let mut count = 0;
-                for tile in self.visible_tiles.iter() {
-                    if *tile == 1 {
-                        count += 1;
-                    }
-                }
-
-                count as f32 / Self::VISION_DISTANCE as f32
-            },
+            AgentDensity => self.density(Self::AGENT),
             Food => {
-                if self.visible_tiles[0] == 2 {
+                if self.visible_tiles[0] == Self::FOOD {
                     1f32
                 } else {
                     0f32
                 }
             },
-            FoodDensity => {
-                let mut count = 0;
-                for tile in self.visible_tiles.iter() {
-                    if *tile == 2 {
-                        count += 1;
-                    }
-                }
-
-                count as f32 / Self::VISION_DISTANCE as f32
-            },
+            FoodDensity => self.density(Self::FOOD),
             Direction => {
                 use agent::Direction::*;
                 match self.direction {
@@ -410,7 +2835,25 @@ impl Sense {
                     Down => 0.66f32,
                     Left => 1f32
                 }
-            }
+            },
+            LastAction => self.last_action.map_or(0f32, |action| action.sensed()),
+            FoodToxicity => {
+                if self.toxic {
+                    1f32
+                } else {
+                    0f32
+                }
+            },
+            Water => {
+                if self.visible_tiles[0] == Self::WATER {
+                    1f32
+                } else {
+                    0f32
+                }
+            },
+            Thirst => u8::from(self.thirst) as f32 / u8::from(ux::u5::MAX) as f32,
+            Temperature => self.temperature,
+            Weather => self.weather
         }
     }
 }
@@ -418,13 +2861,145 @@ impl Sense {
 impl fmt::Debug for Sense {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         use gene::SenseType::*;
-        write!(f, "blocked: {}\nagent: {}\n agent_density: {}\nfood: {}\nfood_density: {}\ndirection: {}",
+        write!(f, "blocked: {}\nagent: {}\n agent_density: {}\nfood: {}\nfood_density: {}\ndirection: {}\nlast_action: {}\nfood_toxicity: {}\nwater: {}\nthirst: {}",
             self.get(&Blocked),
             self.get(&Agent),
             self.get(&AgentDensity),
             self.get(&Food),
             self.get(&FoodDensity),
-            self.get(&Direction)
+            self.get(&Direction),
+            self.get(&LastAction),
+            self.get(&FoodToxicity),
+            self.get(&Water),
+            self.get(&Thirst)
         )
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An empty, seeded world that accepts every placed Agent without quarantine
+    fn world(width: usize, height: usize) -> Simulation {
+        Simulation::new(SimulationSettings {
+            dimensions: iced::Size::new(width, height),
+            agents: 0,
+            seed: Some(1),
+            quarantine: false,
+            update: UpdateMode::Synchronous,
+            ..SimulationSettings::default()
+        } )
+    }
+
+    fn place(simulation: &mut Simulation, coord: coord::Coord, direction: agent::Direction) {
+        let genome = agent::library::LIBRARY[0].genome.to_string();
+        assert!(matches!(simulation.place(coord, genome), Ok(Import::Inserted(..))));
+        simulation.get(coord).update_agent(|mut agent| agent.direction = direction);
+    }
+
+    // An Agent killed in the same step that it produces food yields nothing, rather than producing from a cleared Tile
+    #[test]
+    fn killed_producer_yields_no_food() {
+        let mut simulation = world(3, 1);
+
+        let killer = coord::Coord::new(0, 0);
+        let producer = coord::Coord::new(1, 0);
+        place(&mut simulation, killer, agent::Direction::Right);
+        place(&mut simulation, producer, agent::Direction::Right);
+
+        simulation.resolve(vec![(producer, gene::ActionType::ProduceFood), (killer, gene::ActionType::Kill)]);
+
+        assert!(simulation.contains_agent(killer));
+        assert!(!simulation.contains_agent(producer));
+        assert!(!simulation.tiles.contains_food(coord::Coord::new(2, 0)));
+    }
+
+    // A move and a production into the same Tile contend for it, so neither takes effect
+    #[test]
+    fn production_is_contested_like_moves() {
+        let mut simulation = world(3, 1);
+        simulation.settings.production = Some(agent::YieldPattern::Ahead);
+
+        let producer = coord::Coord::new(0, 0);
+        let mover = coord::Coord::new(2, 0);
+        place(&mut simulation, producer, agent::Direction::Right);
+        place(&mut simulation, mover, agent::Direction::Left);
+
+        simulation.resolve(vec![(producer, gene::ActionType::ProduceFood), (mover, gene::ActionType::Move)]);
+
+        assert!(!simulation.exists(coord::Coord::new(1, 0)));
+        assert!(simulation.contains_agent(mover));
+    }
+
+    // A step's births stay counted once its other events have pushed them out of the EventLog
+    #[test]
+    fn step_counts_outlast_the_event_log() {
+        let mut simulation = world(2, 1);
+        simulation.step();
+
+        let coord = coord::Coord::new(0, 0);
+        simulation.log(coord, EventKind::Birth { parent: 0, child: 1 });
+        for _ in 0..EventLog::CAPACITY {
+            simulation.log(coord, EventKind::Eat { agent: 1 });
+        }
+
+        assert!(simulation.events_since(simulation.tick()).all(|event| !matches!(event.kind, EventKind::Birth { .. })));
+        assert_eq!(crate::stats::Sample::new(&simulation).births, 1);
+        assert_eq!(simulation.step_events().len(), EventLog::CAPACITY + 1);
+
+        simulation.step();
+        assert_eq!((simulation.births(), simulation.step_events().len()), (0, 0));
+    }
+
+    // No brain can be built from a genome of complexity 0, so generation gives up rather than looping forever
+    #[test]
+    fn unworkable_complexity_places_no_agents() {
+        let simulation = Simulation::new(SimulationSettings {
+            dimensions: iced::Size::new(4, 4),
+            agents: 4,
+            complexity: 0,
+            seed: Some(1),
+            ..SimulationSettings::default()
+        } );
+
+        assert!(simulation.agents().is_empty());
+    }
+
+    // In a world one tile across, an Agent faces itself, and must not kill itself under either update mode
+    #[test]
+    fn lone_agent_cannot_kill_itself() {
+        let coord = coord::Coord::new(0, 0);
+
+        let mut simulation = world(1, 1);
+        place(&mut simulation, coord, agent::Direction::Right);
+        simulation.resolve(vec![(coord, gene::ActionType::Kill)]);
+        assert!(simulation.contains_agent(coord));
+
+        let mut simulation = world(1, 1);
+        place(&mut simulation, coord, agent::Direction::Right);
+        simulation.act(coord, gene::ActionType::Kill);
+        assert!(simulation.contains_agent(coord));
+    }
+
+    // Interventions just past the right edge are refused under either storage,
+    // rather than landing on the next row or being kept outside of the world
+    #[test]
+    fn interventions_outside_of_the_world_are_refused() {
+        for storage in [tile::TileStorage::Dense, tile::TileStorage::Sparse] {
+            let mut simulation = world(4, 3);
+            simulation.tiles = tile::TileMap::new(iced::Size::new(4, 3), storage, coord::BoundaryMode::default());
+
+            let outside = coord::Coord::new(4, 0);
+            let genome = agent::library::LIBRARY[0].genome.to_string();
+            assert!(!simulation.build_wall(outside));
+            assert!(!simulation.place_food(outside));
+            assert!(matches!(simulation.spawn_agent(outside), Ok(false)));
+            assert!(matches!(simulation.place(outside, genome), Ok(Import::NoSpace)));
+            assert!(matches!(simulation.try_agent(outside), Err(crate::error::AccessError::NoAgent)));
+
+            assert!(simulation.coords().is_empty());
+            assert!(simulation.interventions.is_empty());
+        }
+    }
+}
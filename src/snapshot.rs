@@ -0,0 +1,154 @@
+use std::collections::VecDeque;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+use crate::error::LoadError;
+use crate::simulation::Simulation;
+
+// Copies of the Simulation taken before steps, so the run can be stepped back
+// The newest snapshots are kept in memory, one step apart while stepping by hand and PLAYING_INTERVAL steps apart while playing
+// As they age, every SPILL_INTERVAL-th is compressed into a file on disk and the rest are dropped,
+// so the rewind horizon reaches far further back than memory alone would allow
+// Without the fs feature nothing is spilled, and the horizon is the snapshots in memory
+pub struct SnapshotRing {
+    // oldest first, each with its estimated size in bytes
+    memory: VecDeque<(usize, Simulation)>,
+    // the estimated size of every snapshot in memory
+    bytes: usize,
+    // the tick and file of each spilled snapshot, oldest first
+    disk: VecDeque<(u64, PathBuf)>,
+    // created on the first spill, removed along with the ring
+    directory: Option<PathBuf>
+}
+
+impl Default for SnapshotRing {
+    fn default() -> Self {
+        Self {
+            memory: VecDeque::new(),
+            bytes: 0,
+            disk: VecDeque::new(),
+            directory: crate::platform::temp_dir()
+                .map(|temp| temp.join(format!("evolution_simulation_{}", std::process::id())))
+        }
+    }
+}
+
+impl SnapshotRing {
+    // the number of snapshots held in memory
+    const MEMORY: usize = 32;
+    // the number of steps between spilled snapshots
+    const SPILL_INTERVAL: u64 = 10;
+    // the number of steps between snapshots while the run is playing, as copying the world every step would slow it down
+    const PLAYING_INTERVAL: u64 = Self::SPILL_INTERVAL;
+    // the number of snapshots kept on disk, covering DISK * SPILL_INTERVAL steps
+    const DISK: usize = 1000;
+
+    // Takes a snapshot if one is due, spilling the oldest snapshot in memory once the memory is full
+    // While the run is playing, snapshots are only taken every PLAYING_INTERVAL steps
+    pub fn push(&mut self, simulation: &Simulation, playing: bool) -> std::io::Result<()> {
+        if playing && !simulation.tick().is_multiple_of(Self::PLAYING_INTERVAL) {
+            return Ok(());
+        }
+
+        let bytes = simulation.memory().total();
+        self.memory.push_back((bytes, simulation.clone()));
+        self.bytes += bytes;
+        if self.memory.len() <= Self::MEMORY {
+            return Ok(());
+        }
+
+        match self.memory.pop_front() {
+            Some((bytes, oldest)) => {
+                self.bytes -= bytes;
+                if oldest.tick().is_multiple_of(Self::SPILL_INTERVAL) {
+                    self.spill(&oldest)
+                } else {
+                    Ok(())
+                }
+            },
+            None => Ok(())
+        }
+    }
+
+    // Removes and returns the newest snapshot
+    pub fn pop(&mut self) -> Result<Option<Simulation>, LoadError> {
+        if let Some((bytes, simulation)) = self.memory.pop_back() {
+            self.bytes -= bytes;
+            return Ok(Some(simulation));
+        }
+
+        let (_, path) = match self.disk.pop_back() {
+            Some(spilled) => spilled,
+            None => return Ok(None)
+        };
+
+        let loaded = std::fs::File::open(&path)
+            .map_err(LoadError::Io)
+            .and_then(|file| {
+                let decoder = flate2::read::GzDecoder::new(BufReader::new(file));
+                serde_json::from_reader(decoder).map_err(LoadError::Parse)
+            } );
+
+        if let Err(e) = std::fs::remove_file(&path) {
+            tracing::warn!("failed to remove the snapshot at {}: {}", path.display(), e);
+        }
+
+        loaded.map(Some)
+    }
+
+    // The tick of the oldest snapshot, None if there are none
+    pub fn horizon(&self) -> Option<u64> {
+        self.disk.front()
+            .map(|(tick, _)| *tick)
+            .or_else(|| self.memory.front().map(|(_, simulation)| simulation.tick()))
+    }
+
+    // The estimated size of the snapshots held in memory, in bytes
+    pub fn memory(&self) -> usize {
+        self.bytes
+    }
+
+    // Discards every snapshot, as a new run's history has nothing to do with the old one's
+    pub fn clear(&mut self) {
+        self.memory.clear();
+        self.bytes = 0;
+        for (_, path) in self.disk.drain(..) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    fn spill(&mut self, simulation: &Simulation) -> std::io::Result<()> {
+        let directory = match &self.directory {
+            Some(directory) => directory,
+            None => return Ok(())
+        };
+
+        std::fs::create_dir_all(directory)?;
+
+        let path = directory.join(format!("snapshot_{}.json.gz", simulation.tick()));
+        let mut encoder = flate2::write::GzEncoder::new(
+            BufWriter::new(std::fs::File::create(&path)?),
+            flate2::Compression::fast());
+
+        serde_json::to_writer(&mut encoder, simulation).map_err(std::io::Error::from)?;
+        encoder.finish()?.flush()?;
+
+        self.disk.push_back((simulation.tick(), path));
+        if self.disk.len() > Self::DISK {
+            if let Some((_, path)) = self.disk.pop_front() {
+                std::fs::remove_file(path)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for SnapshotRing {
+    fn drop(&mut self) {
+        self.clear();
+        if let Some(directory) = &self.directory {
+            let _ = std::fs::remove_dir(directory);
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::io::Write;
+
+use crate::simulation::Simulation;
+
+// The world-level metrics recorded after a single step
+#[derive(Debug, Copy, Clone)]
+pub struct Sample {
+    pub tick: u64,
+    pub population: usize,
+    pub mean_fitness: f32,
+    pub max_fitness: u8,
+    // births and deaths during the step
+    pub births: usize,
+    pub deaths: usize,
+    // the sum of food density across every Tile
+    pub food: usize,
+    // None without Weather
+    pub weather: Option<f32>,
+    // the rate offspring are mutated at, which rises while the population is hypermutating
+    pub mutation_rate: f32,
+    // the mean number of nodes in each Agent's brain
+    pub brain_size: f32
+}
+
+impl Sample {
+    // Measures the world after its latest step
+    pub fn new(simulation: &Simulation) -> Self {
+        let tick = simulation.tick();
+
+        let agents = simulation.agents();
+        let (fitness, max_fitness, nodes) = agents.iter()
+            .map(|coord| simulation.get(*coord).agent())
+            .fold((0u32, 0u8, 0usize), |(fitness, max_fitness, nodes), agent| (
+                fitness + u8::from(agent.fitness) as u32,
+                max_fitness.max(u8::from(agent.fitness)),
+                nodes + agent.brain.node_count()
+            ));
+
+        Self {
+            tick,
+            population: agents.len(),
+            mean_fitness: fitness as f32 / agents.len().max(1) as f32,
+            max_fitness,
+            births: simulation.births(),
+            deaths: simulation.deaths(),
+            food: simulation.food().iter().map(|coord| simulation.get(*coord).food() as usize).sum(),
+            weather: simulation.weather(),
+            mutation_rate: simulation.mutation_rate(),
+            brain_size: nodes as f32 / agents.len().max(1) as f32
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Series {
+    Population,
+    Fitness,
+    Births,
+    Deaths,
+    Food,
+    Weather,
+    MutationRate
+}
+
+impl Series {
+    pub const ALL: [Series; 7] = [
+        Series::Population,
+        Series::Fitness,
+        Series::Births,
+        Series::Deaths,
+        Series::Food,
+        Series::Weather,
+        Series::MutationRate
+    ];
+
+    // None if the metric wasn't recorded
+    fn value(&self, sample: &Sample) -> Option<f32> {
+        match self {
+            Series::Population => Some(sample.population as f32),
+            Series::Fitness => Some(sample.mean_fitness),
+            Series::Births => Some(sample.births as f32),
+            Series::Deaths => Some(sample.deaths as f32),
+            Series::Food => Some(sample.food as f32),
+            Series::Weather => sample.weather,
+            Series::MutationRate => Some(sample.mutation_rate)
+        }
+    }
+}
+
+impl fmt::Display for Series {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Series::Population => "population",
+            Series::Fitness => "mean fitness",
+            Series::Births => "births",
+            Series::Deaths => "deaths",
+            Series::Food => "food",
+            Series::Weather => "weather",
+            Series::MutationRate => "mutation rate"
+        } )
+    }
+}
+
+// The number of living Agents in each age band, youngest first
+// Bands are equally wide, and together reach the oldest Agent
+#[derive(Debug, Clone)]
+pub struct Pyramid {
+    pub tick: u64,
+    // the number of steps covered by each band
+    pub band: u64,
+    pub counts: Vec<usize>
+}
+
+impl Pyramid {
+    pub const BANDS: usize = 10;
+
+    fn new(simulation: &Simulation) -> Self {
+        let tick = simulation.tick();
+        let ages = simulation.agents().iter()
+            .map(|coord| tick.saturating_sub(simulation.get(*coord).agent().born))
+            .collect::<Vec<u64>>();
+
+        let oldest = ages.iter().copied().max().unwrap_or(0);
+        let band = (oldest / Self::BANDS as u64 + 1).max(1);
+
+        let mut counts = vec![0; Self::BANDS];
+        for age in ages {
+            counts[((age / band) as usize).min(Self::BANDS - 1)] += 1;
+        }
+
+        Self { tick, band, counts }
+    }
+
+    // The youngest and oldest age in the band
+    pub fn ages(&self, index: usize) -> (u64, u64) {
+        let start = index as u64 * self.band;
+        (start, start + self.band - 1)
+    }
+}
+
+// Records the population, fitness, births, deaths and food after every step,
+// so boom and bust cycles can be watched as they happen
+// The age structure is recorded less often, since it changes slowly and every Agent must be visited
+#[derive(Default)]
+pub struct PopulationStats {
+    // oldest first
+    samples: VecDeque<Sample>,
+    // the latest age structure
+    pyramid: Option<Pyramid>
+}
+
+impl PopulationStats {
+    pub const CAPACITY: usize = 2000;
+    // the number of steps between age structures
+    pub const PYRAMID_INTERVAL: u64 = 25;
+
+    // Records the latest step
+    pub fn poll(&mut self, simulation: &Simulation) {
+        let tick = simulation.tick();
+
+        if self.samples.len() == Self::CAPACITY {
+            self.samples.pop_front();
+        }
+
+        self.samples.push_back(Sample::new(simulation));
+
+        if self.pyramid.is_none() || tick.is_multiple_of(Self::PYRAMID_INTERVAL) {
+            self.pyramid = Some(Pyramid::new(simulation));
+        }
+    }
+
+    pub fn latest(&self) -> Option<&Sample> {
+        self.samples.back()
+    }
+
+    pub fn pyramid(&self) -> Option<&Pyramid> {
+        self.pyramid.as_ref()
+    }
+
+    // The value of the metric after each recorded step, oldest first
+    // Empty if the metric isn't recorded, like the weather of a world without Weather
+    pub fn series(&self, series: Series) -> Vec<(u64, f32)> {
+        self.samples.iter()
+            .filter_map(|sample| Some((sample.tick, series.value(sample)?)))
+            .collect()
+    }
+
+    pub fn report(&self) -> String {
+        let latest = match self.samples.back() {
+            Some(latest) => latest,
+            None => return String::from("No steps have been recorded")
+        };
+
+        let report = Series::ALL.iter().fold(format!("Step {}", latest.tick), |output, series| {
+            let value = match series.value(latest) {
+                Some(value) => value,
+                None => return output
+            };
+
+            let values = self.samples.iter().filter_map(|sample| series.value(sample));
+            let peak = values.clone().fold(0f32, f32::max);
+            let mean = values.clone().sum::<f32>() / values.count().max(1) as f32;
+
+            output + &format!("\n{}: {:.2} (mean {:.2}, peak {:.2})", series, value, mean, peak)
+        } );
+
+        match &self.pyramid {
+            Some(pyramid) => pyramid.counts.iter().enumerate().fold(
+                format!("{}\n\nAges at step {}", report, pyramid.tick),
+                |output, (index, count)| {
+                    let (youngest, oldest) = pyramid.ages(index);
+                    output + &format!("\n{}-{}: {}", youngest, oldest, count)
+                } ),
+            None => report
+        }
+    }
+}
+
+// Appends a row of statistics to a CSV file after every step, for analysis in other tools
+// Rows are added to the end of an existing file, and the header is only written to an empty one
+pub struct CsvWriter {
+    file: fs::File
+}
+
+impl CsvWriter {
+    pub const HEADER: &'static str = "tick,population,births,deaths,mean_fitness,max_fitness,food,mean_brain_size";
+
+    pub fn create(path: impl AsRef<std::path::Path>) -> io::Result<Self> {
+        let mut file = crate::platform::append(path)?;
+
+        if file.metadata()?.len() == 0 {
+            writeln!(file, "{}", Self::HEADER)?;
+        }
+
+        Ok(Self { file })
+    }
+
+    // Each row is written at once, so a run that is interrupted leaves no partial rows behind
+    pub fn write(&mut self, sample: &Sample) -> io::Result<()> {
+        let row = format!("{},{},{},{},{:.3},{},{},{:.3}\n",
+            sample.tick,
+            sample.population,
+            sample.births,
+            sample.deaths,
+            sample.mean_fitness,
+            sample.max_fitness,
+            sample.food,
+            sample.brain_size);
+
+        self.file.write_all(row.as_bytes())
+    }
+}
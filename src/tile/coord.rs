@@ -1,68 +1,110 @@
 use std::cell::Cell;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
-pub(crate) struct Coord {
-    pub(crate) x: usize,
-    pub(crate) y: usize
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, serde::Serialize, serde::Deserialize)]
+pub struct Coord {
+    pub x: usize,
+    pub y: usize
 }
 
 impl Coord {
-    pub(crate) fn new(x: usize, y: usize) -> Self {
+    pub fn new(x: usize, y: usize) -> Self {
         Self { x, y }
     }
 
-    pub(crate) fn apply_offset(&mut self, offset: Offset, dimensions: &iced::Size<usize>) {
+    // Returns false, leaving the Coord untouched, if the Offset would carry it past a solid boundary
+    pub fn apply_offset(&mut self, offset: Offset, dimensions: &iced::Size<usize>, boundary: BoundaryMode) -> bool {
         use Offset::*;
-        match offset {
-            X(d) => {
-                let x = self.x as isize + d.get();
-                self.x = if x >= 0 {
-                    x as usize % dimensions.width
-                } else {
-                    dimensions.width - x.abs() as usize
-                }
-            },
-            Y(d) => {
-                let y = self.y as isize + d.get();
-                self.y = if y >= 0 {
-                    y as usize % dimensions.width
-                } else {
-                    dimensions.width - y.abs() as usize
-                }
+        let (position, length) = match offset {
+            X(..) => (self.x, dimensions.width),
+            Y(..) => (self.y, dimensions.height)
+        };
+
+        let moved = position as isize + offset.distance();
+        let moved = if (0..length as isize).contains(&moved) {
+            moved as usize
+        } else {
+            match boundary {
+                BoundaryMode::Wrap => moved.rem_euclid(length as isize) as usize,
+                BoundaryMode::Wall | BoundaryMode::Kill => return false
             }
+        };
+
+        match offset {
+            X(..) => self.x = moved,
+            Y(..) => self.y = moved
         }
+
+        true
     }
 
-    pub(crate) fn sample_offset(&self, offset: Offset, dimensions: &iced::Size<usize>) -> Coord {
-        let mut coord = self.clone();
-        coord.apply_offset(offset, dimensions);
-        coord
+    // None if the Offset leads out of the world
+    pub fn sample_offset(&self, offset: Offset, dimensions: &iced::Size<usize>, boundary: BoundaryMode) -> Option<Coord> {
+        let mut coord = *self;
+        if coord.apply_offset(offset, dimensions, boundary) {
+            Some(coord)
+        } else {
+            None
+        }
     }
 
-    pub(crate) fn neighbors(&self, dimensions: &iced::Size<usize>) -> Vec<Coord> {
+    // Neighbors beyond a solid boundary are left out
+    pub fn neighbors(&self, dimensions: &iced::Size<usize>, neighborhood: Neighborhood, boundary: BoundaryMode) -> Vec<Coord> {
         use crate::agent::Direction::*;
-        vec![Up, Down, Left, Right].drain(0..4).map(|direction| {
-            self.sample_offset(Offset::from_direction(direction), dimensions)
-        } ).collect()
+        let mut neighbors: Vec<Coord> = vec![Up, Down, Left, Right].drain(0..4).filter_map(|direction| {
+            self.sample_offset(Offset::from_direction(direction), dimensions, boundary)
+        } ).collect();
+
+        // Offsets are restricted to a single axis, so diagonals take two steps
+        if let Neighborhood::Moore = neighborhood {
+            for vertical in [Up, Down] {
+                for horizontal in [Left, Right] {
+                    let diagonal = self.sample_offset(Offset::from_direction(vertical), dimensions, boundary)
+                        .and_then(|coord| coord.sample_offset(Offset::from_direction(horizontal), dimensions, boundary));
+
+                    neighbors.extend(diagonal);
+                }
+            }
+        }
+
+        neighbors
     }
 }
 
+// What lies beyond the edges of the world
+// Wrap joins opposite edges, Wall blocks movement and sight, Kill removes any Agent that walks off
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum BoundaryMode {
+    #[default]
+    Wrap,
+    Wall,
+    Kill
+}
+
+// The set of Tiles considered adjacent to a Coord
+// VonNeumann includes the 4 orthogonal Tiles, Moore adds the 4 diagonals
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum Neighborhood {
+    #[default]
+    VonNeumann,
+    Moore
+}
+
 #[derive(Debug)]
-pub(crate) enum Offset {
+pub enum Offset {
     X(Cell<isize>),
     Y(Cell<isize>)
 }
 
 impl Offset {
-    pub(crate) fn new_x(distance: isize) -> Self {
+    pub fn new_x(distance: isize) -> Self {
         Self::X(Cell::new(distance))
     }
 
-    pub(crate) fn new_y(distance: isize) -> Self {
+    pub fn new_y(distance: isize) -> Self {
         Self::Y(Cell::new(distance))
     }
 
-    pub(crate) fn from_direction(direction: crate::agent::Direction) -> Self {
+    pub fn from_direction(direction: crate::agent::Direction) -> Self {
         use crate::agent::Direction::*;
         match direction {
             Up => Self::new_y(-1),
@@ -72,29 +114,28 @@ impl Offset {
         }
     }
 
-    // unsure if this method is needed
-    pub(crate) fn distance(&self) -> isize {
+    pub fn distance(&self) -> isize {
         use Offset::*;
         match self {
             X(d) | Y(d) => d.get()
         }
     }
 
-    pub(crate) fn blank(&self) -> bool {
+    pub fn blank(&self) -> bool {
         use Offset::*;
         match self {
             X(d) | Y(d) => d.get() == 0
         }
     }
 
-    pub(crate) fn signum(&mut self) -> Self {
+    pub fn signum(&mut self) -> Self {
         use Offset::*;
         match self {
             X(d) | Y(d) => {
                 let s = d.get().signum();
 
                 // reduce the distance of the Offset
-                d.set(d.get() - 1isize * s);
+                d.set(d.get() - s);
 
                 match self {
                     X(..) => X(Cell::new(s)),
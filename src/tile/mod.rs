@@ -1,4 +1,4 @@
-pub(crate) mod coord;
+pub mod coord;
 
 use std::fmt;
 use std::cell;
@@ -7,16 +7,58 @@ use std::collections::HashMap;
 use coord::Coord;
 
 use crate::agent::Agent;
+use crate::error::{AccessError, PlacementError};
 
-#[derive(Clone)]
-pub(crate) enum Tile {
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(from = "legacy::Tile")]
+pub enum Tile {
     Agent(cell::RefCell<Agent>),
-    Food(cell::Cell<u8>)
+    /// Food's density, and whether it is toxic.
+    Food(cell::Cell<u8>, bool),
+    /// Blocks movement and sight, and never holds food.
+    Wall,
+    /// Blocks movement, and quenches the thirst of Agents that drink from it.
+    /// Water never runs dry.
+    Water
+}
+
+// Saves from before food could be toxic hold each Food Tile's density alone
+mod legacy {
+    use std::cell;
+
+    use crate::agent::Agent;
+
+    #[derive(serde::Deserialize)]
+    pub(super) enum Tile {
+        Agent(cell::RefCell<Agent>),
+        Food(Food),
+        Wall,
+        Water
+    }
+
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    pub(super) enum Food {
+        Density(u8),
+        Toxic(u8, bool)
+    }
+
+    impl From<Tile> for super::Tile {
+        fn from(tile: Tile) -> Self {
+            match tile {
+                Tile::Agent(agent) => Self::Agent(agent),
+                Tile::Food(Food::Density(density)) => Self::new_food(density),
+                Tile::Food(Food::Toxic(density, toxic)) => Self::Food(cell::Cell::new(density), toxic),
+                Tile::Wall => Self::Wall,
+                Tile::Water => Self::Water
+            }
+        }
+    }
 }
 
 impl Tile {
     /// Creates a new Tile with the provided Agent.
-    pub(crate) fn new_agent(agent: Agent) -> Tile {
+    pub fn new_agent(agent: Agent) -> Tile {
         Self::Agent(cell::RefCell::new(agent))
     }
 
@@ -24,7 +66,7 @@ impl Tile {
     ///
     /// # Panics
     /// If the Tile does not contain an Agent.
-    pub(crate) fn agent(&self) -> cell::Ref<'_, Agent> {
+    pub fn agent(&self) -> cell::Ref<'_, Agent> {
         if let Self::Agent(agent) = self {
             return agent.borrow();
         }
@@ -36,7 +78,7 @@ impl Tile {
     ///
     /// # Panics
     /// If the Tile does not contain an Agent.
-    pub(crate) fn update_agent<F>(&self, f: F) where F: Fn(cell::RefMut<'_, Agent>) {
+    pub fn update_agent<F>(&self, f: F) where F: Fn(cell::RefMut<'_, Agent>) {
         if let Self::Agent(agent) = self {
             f(agent.borrow_mut());
             return;
@@ -44,35 +86,67 @@ impl Tile {
 
         panic!()
     }
+
+    /// Attempts to borrow the Tile's Agent.
+    /// Unlike Tile::agent, this never panics.
+    pub fn try_agent(&self) -> Result<cell::Ref<'_, Agent>, AccessError> {
+        match self {
+            Self::Agent(agent) => agent.try_borrow().map_err(|_| AccessError::Borrowed),
+            _ => Err(AccessError::NoAgent)
+        }
+    }
+
+    /// Attempts to modify the Tile's Agent through a closure.
+    /// Unlike Tile::update_agent, this never panics.
+    pub fn try_update_agent<F>(&self, f: F) -> Result<(), AccessError> where F: FnOnce(cell::RefMut<'_, Agent>) {
+        match self {
+            Self::Agent(agent) => {
+                f(agent.try_borrow_mut().map_err(|_| AccessError::Borrowed)?);
+                Ok(())
+            },
+            _ => Err(AccessError::NoAgent)
+        }
+    }
 }
 
 impl Tile {
     /// When a Tile's food density exceeds the DIFFUSION_THRESHOLD, it spreads into neighboring Tiles.
-    pub(crate) const DIFFUSION_THRESHOLD: u8 = 4; // food diffuses above this value
+    pub const DIFFUSION_THRESHOLD: u8 = 4; // food diffuses above this value
 
     /// Creates a new Tile with Food in the given density.
-    pub(crate) fn new_food(density: u8) -> Tile {
-        Self::Food(cell::Cell::new(density))
+    pub fn new_food(density: u8) -> Tile {
+        Self::Food(cell::Cell::new(density), false)
+    }
+
+    /// Creates a new Tile with toxic Food in the given density.
+    pub fn new_toxic_food(density: u8) -> Tile {
+        Self::Food(cell::Cell::new(density), true)
     }
 
     /// Gets the density of Food in the given Tile.
     ///
     /// # Panics
     /// If the Tile does not contain food.
-    pub(crate) fn food(&self) -> u8 {
-        if let Self::Food(density) = self {
+    pub fn food(&self) -> u8 {
+        if let Self::Food(density, _) = self {
             return density.get();
         }
 
         panic!()
     }
 
+    /// Returns true if the Tile holds toxic Food.
+    /// Unlike Tile::food, this never panics.
+    pub fn toxic(&self) -> bool {
+        matches!(self, Self::Food(_, true))
+    }
+
     /// Add food to the Tile.
     ///
     /// # Panics
     /// If the Tile does not contain food.
-    pub(crate) fn add_food(&self) {
-        if let Self::Food(amount) = self {
+    pub fn add_food(&self) {
+        if let Self::Food(amount, _) = self {
             amount.set(amount.get() + 1);
             return;
         }
@@ -84,7 +158,7 @@ impl Tile {
     ///
     /// # Panics
     /// When Tile::food panics.
-    pub(crate) fn should_diffuse(&self) -> bool {
+    pub fn should_diffuse(&self) -> bool {
         self.food() > Self::DIFFUSION_THRESHOLD
     }
 
@@ -93,8 +167,8 @@ impl Tile {
     ///
     /// # Panics
     /// If the tile does not contain food.
-    pub(crate) fn remove_food(&self) -> bool {
-        if let Self::Food(amount) = self {
+    pub fn remove_food(&self) -> bool {
+        if let Self::Food(amount, _) = self {
             return if amount.get() == 1 {
                 true
             } else {
@@ -113,38 +187,114 @@ impl fmt::Debug for Tile {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use Tile::*;
         write!(f, "{}", match self {
-            Food(amount) => format!("Food ({})", amount.get()),
-            Agent(..) => format!("{}", self.agent())
+            Food(amount, false) => format!("Food ({})", amount.get()),
+            Food(amount, true) => format!("Toxic Food ({})", amount.get()),
+            Agent(..) => format!("{}", self.agent()),
+            Wall => String::from("Wall"),
+            Water => String::from("Water")
         } )
     }
 }
 
-pub(crate) struct TileMap {
-    tiles: HashMap<Coord, Tile>,
-    pub(crate) dimensions: iced::Size<usize>
+/// Determines how a TileMap stores its Tiles.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum TileStorage {
+    /// Dense, unless the world is too large to allocate every Tile up front.
+    #[default]
+    Auto,
+    /// A HashMap holding only the occupied Tiles.
+    Sparse,
+    /// A Vec with a slot for every Coord, indexed by `y * width + x`.
+    Dense
+}
+
+impl TileStorage {
+    /// Worlds with more Tiles than this are stored sparsely by TileStorage::Auto.
+    pub const DENSE_LIMIT: usize = 1 << 20;
+}
+
+#[derive(Clone)]
+enum Tiles {
+    Sparse(HashMap<Coord, Tile>),
+    Dense(Vec<Option<Tile>>)
+}
+
+#[derive(Clone)]
+pub struct TileMap {
+    tiles: Tiles,
+    pub dimensions: iced::Size<usize>,
+    pub boundary: coord::BoundaryMode,
+    /// Saves include each Agent's pruned brain when set.
+    pub cache_brains: bool
 }
 
 impl TileMap {
     /// Create a new TileMap of a given Size.
-    pub(crate) fn new(dimensions: iced::Size<usize>) -> Self {
+    pub fn new(dimensions: iced::Size<usize>, storage: TileStorage, boundary: coord::BoundaryMode) -> Self {
+        let area = dimensions.width * dimensions.height;
+
+        use TileStorage::*;
+        let tiles = match storage {
+            Sparse => Tiles::Sparse(HashMap::new()),
+            Auto if area > TileStorage::DENSE_LIMIT => Tiles::Sparse(HashMap::new()),
+            Auto | Dense => Tiles::Dense(std::iter::repeat_with(|| None).take(area).collect())
+        };
+
         Self {
-            tiles: HashMap::new(),
-            dimensions
+            tiles,
+            dimensions,
+            boundary,
+            cache_brains: false
         }
     }
 
+    // Converts a Coord into its slot in a dense TileMap, None if it lies outside of the world
+    // Sparse TileMaps use it as a bounds check, so neither storage holds Tiles outside of the world
+    fn index(&self, coord: Coord) -> Option<usize> {
+        if self.in_world(coord) {
+            Some(coord.y * self.dimensions.width + coord.x)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the Coord lies within the TileMap's dimensions.
+    pub fn in_world(&self, coord: Coord) -> bool {
+        coord.x < self.dimensions.width && coord.y < self.dimensions.height
+    }
+
     /// Puts a Tile at a given Coord.
     /// If a tile was previously present, returns it, otherwise None.
-    pub(crate) fn put(&mut self, coord: Coord, tile: Tile) -> Option<Tile> {
-        self.tiles.insert(coord, tile)
+    ///
+    /// # Errors
+    /// If the Coord lies outside of the world, in which case the Tile is dropped.
+    pub fn put(&mut self, coord: Coord, tile: Tile) -> Result<Option<Tile>, PlacementError> {
+        let index = match self.index(coord) {
+            Some(index) => index,
+            None => return Err(PlacementError::OutOfBounds { x: coord.x, y: coord.y })
+        };
+
+        Ok(match &mut self.tiles {
+            Tiles::Sparse(tiles) => tiles.insert(coord, tile),
+            Tiles::Dense(tiles) => tiles[index].replace(tile)
+        } )
+    }
+
+    // Gets the Tile at a given Coord, if one is present
+    fn lookup(&self, coord: Coord) -> Option<&Tile> {
+        let index = self.index(coord)?;
+        match &self.tiles {
+            Tiles::Sparse(tiles) => tiles.get(&coord),
+            Tiles::Dense(tiles) => tiles[index].as_ref()
+        }
     }
 
     /// Gets a reference to the Tile at a given Coord.
     ///
     /// # Panics
     /// If the Coord is not a key to a Tile in the TileMap.
-    pub(crate) fn get(&self, coord: Coord) -> &Tile {
-        match self.tiles.get(&coord) {
+    pub fn get(&self, coord: Coord) -> &Tile {
+        match self.lookup(coord) {
             Some(tile) => tile,
             None => panic!()
         }
@@ -152,16 +302,12 @@ impl TileMap {
 
     /// Returns true if a Tile is present at the Coord.
     /// Used to validate the existence of a Tile before calling methods that can panic!
-    pub(crate) fn exists(&self, coord: Coord) -> bool {
-        if let Some(..) = self.tiles.get(&coord) {
-            return true;
-        }
-
-        false
+    pub fn exists(&self, coord: Coord) -> bool {
+        self.lookup(coord).is_some()
     }
 
     /// Returns true if the given Coord contains a Tile::Agent.
-    pub(crate) fn contains_agent(&self, coord: Coord) -> bool {
+    pub fn contains_agent(&self, coord: Coord) -> bool {
         if !self.exists(coord) {
             return false;
         }
@@ -170,7 +316,7 @@ impl TileMap {
     }
 
     /// Returns true if the given Coord contains food.
-    pub(crate) fn contains_food(&self, coord: Coord) -> bool {
+    pub fn contains_food(&self, coord: Coord) -> bool {
         if !self.exists(coord) {
             return false;
         }
@@ -178,11 +324,33 @@ impl TileMap {
         matches!(self.get(coord), Tile::Food(..))
     }
 
+    /// Returns true if the given Coord contains a wall.
+    pub fn contains_wall(&self, coord: Coord) -> bool {
+        if !self.exists(coord) {
+            return false;
+        }
+
+        matches!(self.get(coord), Tile::Wall)
+    }
+
+    /// Returns true if the given Coord contains water.
+    pub fn contains_water(&self, coord: Coord) -> bool {
+        if !self.exists(coord) {
+            return false;
+        }
+
+        matches!(self.get(coord), Tile::Water)
+    }
+
     /// Remove a Tile from the TileMap.
     /// Returns the removed Tile, if it was present.
     /// Otherwise, returns None.
-    pub(crate) fn clear(&mut self, coord: Coord) -> Option<Tile> {
-        self.tiles.remove(&coord)
+    pub fn clear(&mut self, coord: Coord) -> Option<Tile> {
+        let index = self.index(coord)?;
+        match &mut self.tiles {
+            Tiles::Sparse(tiles) => tiles.remove(&coord),
+            Tiles::Dense(tiles) => tiles[index].take()
+        }
     }
 
     /// Applies an Offset, one step at a time by using Offset::signum.
@@ -191,12 +359,13 @@ impl TileMap {
     ///
     /// # Panics
     /// If the provided Coord does not contain a Tile.
-    pub(crate) fn walk(&mut self, mut coord: Coord, offset: coord::Offset) -> Coord {
-        match self.tiles.remove(&coord) {
+    pub fn walk(&mut self, mut coord: Coord, offset: coord::Offset) -> Coord {
+        match self.clear(coord) {
             Some(tile) => {
                 // get the new Coord and put the Tile at the new location
+                // walking never leaves the world, so the Tile always has somewhere to go
                 self.walk_by_tiles(&mut coord, offset);
-                self.put(coord, tile);
+                let _ = self.put(coord, tile);
             },
             None => panic!()
         }
@@ -209,17 +378,30 @@ impl TileMap {
     ///
     /// # Panics
     /// When TileMap::walk panics!
-    pub(crate) fn walk_towards(&mut self, coord: Coord, direction: crate::agent::Direction) -> Coord {
+    pub fn walk_towards(&mut self, coord: Coord, direction: crate::agent::Direction) -> Coord {
         self.walk(
             coord,
             coord::Offset::from_direction(direction)
         )
     }
 
+    /// The Coord one step away in the given direction.
+    /// None if that step would leave the world.
+    pub fn facing(&self, coord: Coord, direction: crate::agent::Direction) -> Option<Coord> {
+        coord.sample_offset(coord::Offset::from_direction(direction), &self.dimensions, self.boundary)
+    }
+
+    /// The Coords adjacent to the given Coord, respecting the TileMap's boundary.
+    pub fn neighbors(&self, coord: Coord, neighborhood: coord::Neighborhood) -> Vec<Coord> {
+        coord.neighbors(&self.dimensions, neighborhood, self.boundary)
+    }
+
     // Helper function for TileMap::walk
     fn walk_by_tiles(&mut self, coord: &mut Coord, mut offset: coord::Offset) {
-        // update the Coord
-        coord.apply_offset(offset.signum(), &self.dimensions);
+        // update the Coord, a solid boundary ends the walk
+        if !coord.apply_offset(offset.signum(), &self.dimensions, self.boundary) {
+            return;
+        }
 
         // return if the Offset is empty
         // or if the corresponding Tile is occupied
@@ -231,8 +413,194 @@ impl TileMap {
         self.walk_by_tiles(coord, offset)
     }
 
-    /// Returns a vector of all Coords in the TileMap
-    pub(crate) fn coords(&self) -> Vec<Coord> {
-        self.tiles.keys().cloned().collect::<Vec<Coord>>()
+    /// Approximates the memory held by the TileMap's table, in bytes.
+    /// Memory owned by the Agents themselves is not included.
+    pub fn memory(&self) -> usize {
+        match &self.tiles {
+            // each bucket also carries a single control byte
+            Tiles::Sparse(tiles) => tiles.capacity() * (std::mem::size_of::<(Coord, Tile)>() + 1),
+            Tiles::Dense(tiles) => tiles.capacity() * std::mem::size_of::<Option<Tile>>()
+        }
+    }
+
+    /// Provides exclusive access to every Agent in the TileMap, sorted by Coord.
+    /// The references are Send, so the Agents can be processed on other threads.
+    pub fn agents_mut(&mut self) -> Vec<(Coord, &mut Agent)> {
+        let width = self.dimensions.width;
+        let mut agents = match &mut self.tiles {
+            Tiles::Sparse(tiles) => tiles.iter_mut()
+                .filter_map(|(coord, tile)| match tile {
+                    Tile::Agent(agent) => Some((*coord, agent.get_mut())),
+                    _ => None
+                } )
+                .collect::<Vec<(Coord, &mut Agent)>>(),
+            Tiles::Dense(tiles) => tiles.iter_mut()
+                .enumerate()
+                .filter_map(|(index, tile)| match tile {
+                    Some(Tile::Agent(agent)) => Some((Coord::new(index % width, index / width), agent.get_mut())),
+                    _ => None
+                } )
+                .collect::<Vec<(Coord, &mut Agent)>>()
+        };
+
+        agents.sort_by_key(|(coord, ..)| *coord);
+        agents
+    }
+
+    /// The storage actually in use, which is never TileStorage::Auto.
+    pub fn storage(&self) -> TileStorage {
+        match self.tiles {
+            Tiles::Sparse(..) => TileStorage::Sparse,
+            Tiles::Dense(..) => TileStorage::Dense
+        }
+    }
+
+    /// Returns a vector of all Coords in the TileMap.
+    /// Coords are sorted, so the order does not depend on the HashMap's internal layout.
+    pub fn coords(&self) -> Vec<Coord> {
+        let width = self.dimensions.width;
+        let mut coords = match &self.tiles {
+            Tiles::Sparse(tiles) => tiles.keys().cloned().collect::<Vec<Coord>>(),
+            Tiles::Dense(tiles) => tiles.iter()
+                .enumerate()
+                .filter(|(_, tile)| tile.is_some())
+                .map(|(index, _)| Coord::new(index % width, index / width))
+                .collect::<Vec<Coord>>()
+        };
+
+        coords.sort();
+        coords
+    }
+}
+
+// TileMaps are saved as a list of occupied Tiles, whatever their storage
+#[derive(serde::Serialize, serde::Deserialize)]
+struct TileMapState<T> {
+    dimensions: [usize; 2],
+    storage: TileStorage,
+    #[serde(default)]
+    boundary: coord::BoundaryMode,
+    #[serde(default)]
+    cache_brains: bool,
+    tiles: Vec<(Coord, T)>
+}
+
+impl TileMap {
+    fn state<T>(&self, tiles: Vec<(Coord, T)>) -> TileMapState<T> {
+        TileMapState {
+            dimensions: [self.dimensions.width, self.dimensions.height],
+            storage: self.storage(),
+            boundary: self.boundary,
+            cache_brains: self.cache_brains,
+            tiles
+        }
+    }
+}
+
+// Mirrors Tile, so cached brains can be written without changing how Tiles are read
+#[derive(serde::Serialize)]
+enum CachedTile<'a> {
+    Agent(crate::agent::WithBrain<'a>),
+    Food(u8, bool),
+    Wall,
+    Water
+}
+
+impl serde::Serialize for TileMap {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::Error;
+
+        if !self.cache_brains {
+            return self.state(self.coords().into_iter().map(|coord| (coord, self.get(coord))).collect()).serialize(serializer);
+        }
+
+        // the Agents must stay borrowed until they have been written
+        let coords = self.coords();
+        let agents = coords.iter()
+            .map(|coord| self.get(*coord).try_agent().ok())
+            .collect::<Vec<Option<cell::Ref<'_, Agent>>>>();
+
+        let tiles = coords.iter().zip(agents.iter()).map(|(coord, agent)| {
+            Ok((*coord, match (self.get(*coord), agent) {
+                (Tile::Agent(..), Some(agent)) => CachedTile::Agent(crate::agent::WithBrain(agent)),
+                // an Agent being updated elsewhere can't be written, just as it can't be without caching
+                (Tile::Agent(..), None) => return Err(S::Error::custom(format!("the Agent at {:?} is already borrowed", coord))),
+                (Tile::Food(density, toxic), _) => CachedTile::Food(density.get(), *toxic),
+                (Tile::Wall, _) => CachedTile::Wall,
+                (Tile::Water, _) => CachedTile::Water
+            } ))
+        } ).collect::<Result<Vec<_>, S::Error>>()?;
+
+        self.state(tiles).serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for TileMap {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let state = TileMapState::<Tile>::deserialize(deserializer)?;
+
+        let [width, height] = state.dimensions;
+        let mut tiles = TileMap::new(iced::Size::new(width, height), state.storage, state.boundary);
+        tiles.cache_brains = state.cache_brains;
+        for (coord, tile) in state.tiles {
+            tiles.put(coord, tile).map_err(serde::de::Error::custom)?;
+        }
+
+        Ok(tiles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn food_loads_with_and_without_toxicity() {
+        let legacy: Tile = serde_json::from_str(r#"{"Food":3}"#).unwrap();
+        assert_eq!((legacy.food(), legacy.toxic()), (3, false));
+
+        let toxic: Tile = serde_json::from_str(&serde_json::to_string(&Tile::new_toxic_food(2)).unwrap()).unwrap();
+        assert_eq!((toxic.food(), toxic.toxic()), (2, true));
     }
-}
\ No newline at end of file
+
+    // (4, 0) would share a slot with (0, 1) in a dense TileMap of width 4 if it weren't bounds checked
+    #[test]
+    fn coords_past_the_edge_hold_nothing() {
+        for storage in [TileStorage::Dense, TileStorage::Sparse] {
+            let mut tiles = TileMap::new(iced::Size::new(4, 3), storage, coord::BoundaryMode::default());
+            assert!(tiles.put(Coord::new(0, 1), Tile::Wall).is_ok());
+
+            let outside = Coord::new(4, 0);
+            assert!(!tiles.exists(outside));
+            assert!(!tiles.contains_wall(outside));
+            assert!(tiles.put(outside, Tile::Water).is_err());
+            assert!(tiles.clear(outside).is_none());
+
+            assert_eq!(tiles.coords(), vec![Coord::new(0, 1)]);
+            assert!(tiles.contains_wall(Coord::new(0, 1)));
+        }
+    }
+
+    // A borrowed Agent fails the save whether or not brains are cached, rather than being written as a wall
+    #[test]
+    fn borrowed_agents_are_not_saved() {
+        let mut prng: crate::simulation::Prng = rand::SeedableRng::seed_from_u64(1);
+        let genome = crate::agent::library::LIBRARY[0].genome.to_string();
+        let agent = Agent::from_string(genome, &mut prng).unwrap();
+
+        for cache_brains in [false, true] {
+            let mut tiles = TileMap::new(iced::Size::new(2, 1), TileStorage::Dense, coord::BoundaryMode::default());
+            tiles.cache_brains = cache_brains;
+            assert!(tiles.put(Coord::new(0, 0), Tile::Agent(cell::RefCell::new(agent.clone()))).is_ok());
+
+            let borrowed = match tiles.get(Coord::new(0, 0)) {
+                Tile::Agent(agent) => agent.borrow_mut(),
+                _ => unreachable!()
+            };
+
+            assert!(serde_json::to_string(&tiles).is_err());
+            drop(borrowed);
+            assert!(serde_json::to_string(&tiles).is_ok());
+        }
+    }
+}
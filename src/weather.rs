@@ -0,0 +1,55 @@
+use rand::Rng;
+
+use crate::simulation::Prng;
+
+// A slowly varying global modifier, the severity wanders between 0 (calm) and 1 (a full storm)
+// Storms hasten food decay, blow away diffusing food and spoil produced food
+// The severity is drawn from the run's Prng, so seeded runs see the same weather
+#[derive(Debug, Copy, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Weather {
+    // the severity the weather drifts back towards
+    mean: f32,
+    // the largest change in severity over a single step
+    volatility: f32,
+    // the fraction of the gap to the mean closed each step, so storms eventually pass
+    reversion: f32,
+    // how strongly a full storm affects the world, between 0 and 1
+    intensity: f32
+}
+
+impl Default for Weather {
+    fn default() -> Self {
+        Self {
+            mean: 0.3f32,
+            volatility: 0.05f32,
+            reversion: 0.01f32,
+            intensity: 0.5f32
+        }
+    }
+}
+
+impl Weather {
+    pub fn initial(&self) -> f32 {
+        self.mean.clamp(0f32, 1f32)
+    }
+
+    // Advances the severity by a single step, drawing once from the Prng
+    pub fn advance(&self, severity: f32, prng: &mut Prng) -> f32 {
+        let shock = prng.gen_range(-1f32..=1f32) * self.volatility;
+        (severity + self.reversion * (self.mean - severity) + shock).clamp(0f32, 1f32)
+    }
+
+    // The chance that food spoils at the given severity, whether it is decaying, diffusing or being produced
+    pub fn spoilage(&self, severity: f32) -> f32 {
+        self.intensity.clamp(0f32, 1f32) * severity
+    }
+
+    pub fn name(severity: f32) -> &'static str {
+        match severity {
+            s if s < 0.25f32 => "Calm",
+            s if s < 0.5f32 => "Breezy",
+            s if s < 0.75f32 => "Stormy",
+            _ => "Tempest"
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::simulation::Simulation;
+use crate::tile;
+
+// A minimal front-end for the browser, as iced's web backend can't draw a Canvas
+// The world is drawn onto the page's <canvas id="simulation"> and stepped on every animation frame
+// Space pauses and resumes the run, N takes a single step while paused,
+// S saves the world as a download and R resets it from its settings
+struct WebFrontEnd {
+    simulation: RefCell<Simulation>,
+    context: web_sys::CanvasRenderingContext2d,
+    canvas: web_sys::HtmlCanvasElement,
+    playing: Cell<bool>
+}
+
+impl WebFrontEnd {
+    const CANVAS_ID: &'static str = "simulation";
+    // worlds are saved as downloads with this name
+    const SAVE_NAME: &'static str = "world.json";
+
+    const COLOR_EMPTY: &'static str = "#1A1A1A";
+    const COLOR_AGENT: &'static str = "#6464FF";
+    const COLOR_FOOD: [u8; 3] = [0xFF, 0x50, 0x50];
+    const COLOR_TOXIC_FOOD: [u8; 3] = [0xA0, 0xE0, 0x30];
+    const COLOR_WALL: &'static str = "#5A5A5A";
+    const COLOR_WATER: &'static str = "#3C78DC";
+
+    fn draw(&self) {
+        let simulation = self.simulation.borrow();
+        let dimensions = simulation.size();
+
+        let width = self.canvas.width() as f64;
+        let height = self.canvas.height() as f64;
+        let tile = (width / dimensions.width as f64, height / dimensions.height as f64);
+
+        self.context.set_fill_style_str(Self::COLOR_EMPTY);
+        self.context.fill_rect(0f64, 0f64, width, height);
+
+        for coord in simulation.coords() {
+            use tile::Tile::*;
+            let color = match simulation.get(coord) {
+                Agent(..) => String::from(Self::COLOR_AGENT),
+                Food(_, toxic) => {
+                    let color = if *toxic { Self::COLOR_TOXIC_FOOD } else { Self::COLOR_FOOD };
+                    let alpha = simulation.get(coord).food() as f32 / tile::Tile::DIFFUSION_THRESHOLD as f32;
+                    format!("rgba({}, {}, {}, {})", color[0], color[1], color[2], alpha.min(1f32))
+                },
+                Wall => String::from(Self::COLOR_WALL),
+                Water => String::from(Self::COLOR_WATER)
+            };
+
+            self.context.set_fill_style_str(&color);
+            self.context.begin_path();
+            let _ = self.context.arc(
+                (coord.x as f64 + 0.5f64) * tile.0,
+                (coord.y as f64 + 0.5f64) * tile.1,
+                tile.0.min(tile.1) / 2f64,
+                0f64,
+                std::f64::consts::TAU);
+            self.context.fill();
+        }
+    }
+
+    fn key(&self, key: &str) {
+        match key {
+            " " => self.playing.set(!self.playing.get()),
+            "n" if !self.playing.get() => self.simulation.borrow_mut().step(),
+            "s" => if let Err(e) = self.simulation.borrow().save_to(Self::SAVE_NAME) {
+                tracing::error!("failed to save the world: {}", e);
+            },
+            "r" => {
+                let settings = self.simulation.borrow().settings().clone();
+                *self.simulation.borrow_mut() = Simulation::new(settings);
+            },
+            _ => return
+        }
+
+        self.draw();
+    }
+}
+
+// Attaches the front-end to the page and starts the run
+pub(crate) fn start() -> Result<(), JsValue> {
+    let window = web_sys::window().ok_or("the page has no window")?;
+    let document = window.document().ok_or("the page has no document")?;
+
+    let canvas = document.get_element_by_id(WebFrontEnd::CANVAS_ID)
+        .ok_or("the page has no canvas with the id 'simulation'")?
+        .dyn_into::<web_sys::HtmlCanvasElement>()?;
+
+    let context = canvas.get_context("2d")?
+        .ok_or("the canvas has no 2d context")?
+        .dyn_into::<web_sys::CanvasRenderingContext2d>()?;
+
+    let front_end = Rc::new(WebFrontEnd {
+        simulation: RefCell::new(Simulation::default()),
+        context,
+        canvas,
+        playing: Cell::new(true)
+    } );
+
+    let keyboard = {
+        let front_end = Rc::clone(&front_end);
+        Closure::<dyn FnMut(web_sys::KeyboardEvent)>::new(move |event: web_sys::KeyboardEvent| {
+            front_end.key(&event.key().to_ascii_lowercase());
+        } )
+    };
+
+    document.add_event_listener_with_callback("keydown", keyboard.as_ref().unchecked_ref())?;
+    // the listener lives as long as the page
+    keyboard.forget();
+
+    // each frame schedules the next, so the closure holds a handle to itself
+    let frame = Rc::new(RefCell::new(None::<Closure<dyn FnMut()>>));
+    let next = Rc::clone(&frame);
+    *frame.borrow_mut() = Some(Closure::new(move || {
+        if front_end.playing.get() {
+            front_end.simulation.borrow_mut().step();
+            front_end.draw();
+        }
+
+        if let (Some(window), Some(callback)) = (web_sys::window(), next.borrow().as_ref()) {
+            let _ = window.request_animation_frame(callback.as_ref().unchecked_ref());
+        }
+    } ));
+
+    if let Some(callback) = frame.borrow().as_ref() {
+        window.request_animation_frame(callback.as_ref().unchecked_ref())?;
+    }
+
+    Ok(())
+}
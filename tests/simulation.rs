@@ -0,0 +1,190 @@
+use std::cell::RefCell;
+
+use evolution_simulation::agent::library::LIBRARY;
+use evolution_simulation::error::AccessError;
+use evolution_simulation::pulse::Modifier;
+use evolution_simulation::recording::Replay;
+use evolution_simulation::simulation::Basics;
+use evolution_simulation::tile::coord::Coord;
+use evolution_simulation::{Simulation, SimulationSettings};
+
+// Integration tests of the engine's public API
+// Worlds are seeded, so every run of a test steps through the same states
+
+const STEPS: u64 = 200;
+
+fn settings(seed: u64) -> SimulationSettings {
+    let settings = SimulationSettings::default();
+    settings.with_basics(Basics {
+        seed: Some(seed),
+        ..settings.basics()
+    } )
+}
+
+fn run(settings: SimulationSettings, steps: u64) -> Simulation {
+    let mut simulation = Simulation::new(settings);
+    while simulation.tick() < steps {
+        simulation.step();
+    }
+
+    simulation
+}
+
+// The tick and the contents of every Tile, which two identical worlds share
+// Saves aren't compared directly, as some of the Simulation's maps serialize in no particular order
+fn fingerprint(simulation: &Simulation) -> (u64, Vec<(Coord, String)>) {
+    let tiles = simulation.coords().into_iter().map(|coord| {
+        (coord, serde_json::to_string(simulation.get(coord)).unwrap())
+    } ).collect();
+
+    (simulation.tick(), tiles)
+}
+
+// A path in the temporary directory that no other test writes to
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("evolution_simulation_test_{}_{}", std::process::id(), name))
+}
+
+#[test]
+fn same_seed_same_world() {
+    let first = run(settings(1), STEPS);
+    let second = run(settings(1), STEPS);
+
+    assert_eq!(fingerprint(&first), fingerprint(&second));
+}
+
+#[test]
+fn different_seeds_diverge() {
+    let first = run(settings(1), STEPS);
+    let second = run(settings(2), STEPS);
+
+    assert_ne!(fingerprint(&first), fingerprint(&second));
+}
+
+#[test]
+fn saved_world_resumes_where_it_left_off() {
+    let mut simulation = run(settings(3), STEPS);
+
+    let path = temp_path("save.json");
+    simulation.save_to(&path).unwrap();
+    let loaded = Simulation::load_from(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    let mut loaded = loaded.unwrap();
+    assert_eq!(fingerprint(&loaded), fingerprint(&simulation));
+
+    // the prng is saved too, so both worlds keep stepping identically
+    for _ in 0..10 {
+        simulation.step();
+        loaded.step();
+    }
+
+    assert_eq!(fingerprint(&loaded), fingerprint(&simulation));
+}
+
+// The first Tile that holds nothing at all
+fn vacant(simulation: &Simulation) -> Coord {
+    let size = simulation.size();
+    (0..size.height).flat_map(|y| (0..size.width).map(move |x| Coord::new(x, y)))
+        .find(|coord| !simulation.exists(*coord))
+        .expect("the world is full")
+}
+
+// Each kind of intervention is made on a different tick, before and after the first checkpoint of a Replay
+#[test]
+fn recording_replays_the_run() {
+    let mut simulation = Simulation::new(settings(4));
+    while simulation.tick() < STEPS {
+        match simulation.tick() {
+            20 => assert!(simulation.place_food(vacant(&simulation))),
+            50 => assert!(simulation.build_wall(vacant(&simulation))),
+            80 => assert!(simulation.import(LIBRARY[1].genome.to_string()).is_ok()),
+            110 => simulation.pulse(Modifier::Mutation(4f32), 30),
+            140 => {
+                let coord = *simulation.agents().first().expect("every agent died");
+                assert!(simulation.remove_agent(coord).is_ok());
+            },
+            _ => {  }
+        }
+
+        simulation.step();
+    }
+
+    let recording = simulation.record();
+    let replayed = Simulation::replay(&recording, simulation.tick());
+    assert_eq!(fingerprint(&replayed), fingerprint(&simulation));
+
+    // seeking past a checkpoint lays it down, and seeking again starts from it
+    let mut replay = Replay::new(recording.clone());
+    assert_eq!(fingerprint(&replay.seek(STEPS)), fingerprint(&simulation));
+    for tick in [100, 150, STEPS] {
+        assert_eq!(fingerprint(&replay.seek(tick)), fingerprint(&Simulation::replay(&recording, tick)), "seeking to {} diverged", tick);
+    }
+}
+
+#[test]
+fn invalid_settings_are_rejected() {
+    for contents in ["dimensions = [0, 0]", "toxicity = 2.0", "reproduction_threshold = 0", "[food.Uniform]\nprobability = 1.5"] {
+        let path = temp_path("settings.toml");
+        std::fs::write(&path, contents).unwrap();
+        let settings = SimulationSettings::from_path(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(settings, Err(evolution_simulation::error::LoadError::Invalid(..))), "{} was accepted", contents);
+    }
+}
+
+// The interface inspects and marks Agents between steps, from clicks that can land on any Tile
+// None of it may panic, however the world has changed since the last click
+#[test]
+fn inspecting_while_stepping() {
+    let simulation = RefCell::new(Simulation::new(settings(5)));
+    let mut tracked = None;
+    for _ in 0..STEPS {
+        simulation.borrow_mut().step();
+
+        let simulation = simulation.borrow();
+        for coord in simulation.coords() {
+            match simulation.try_agent(coord) {
+                Ok(..) => assert!(simulation.contains_agent(coord)),
+                Err(AccessError::NoAgent) => assert!(!simulation.contains_agent(coord)),
+                Err(e) => panic!("the agent at {:?} couldn't be inspected, {}", coord, e)
+            }
+        }
+
+        // an Agent followed from step to step is found wherever it moved, or not at all once it dies
+        let hint = simulation.agents().first().copied();
+        tracked = tracked.or_else(|| hint.and_then(|coord| simulation.try_agent(coord).ok().map(|agent| (agent.id, coord))));
+        if let Some((id, coord)) = tracked {
+            tracked = simulation.locate(id, Some(coord)).map(|coord| (id, coord));
+            if let Some((id, coord)) = tracked {
+                assert_eq!(simulation.try_agent(coord).map(|agent| agent.id), Ok(id));
+            }
+        }
+
+        // clicks on an empty or out of bounds Tile find no Agent
+        let size = simulation.size();
+        assert!(matches!(simulation.try_agent(Coord::new(size.width, size.height)), Err(AccessError::NoAgent)));
+        assert!(matches!(simulation.try_agent(Coord::new(size.width, 0)), Err(AccessError::NoAgent)));
+    }
+}
+
+// An Agent that is already borrowed is reported as in use, rather than panicking
+#[test]
+fn inspecting_a_borrowed_agent() {
+    let simulation = run(settings(6), 10);
+    let coord = simulation.agents()[0];
+
+    simulation.get(coord).try_update_agent(|_agent| {
+        assert!(matches!(simulation.try_agent(coord), Err(AccessError::Borrowed)));
+        assert!(matches!(simulation.get(coord).try_update_agent(|_| {  }), Err(AccessError::Borrowed)));
+    } ).unwrap();
+
+    // while the world steps, the interface can't borrow it at all, and skips the click
+    let simulation = RefCell::new(simulation);
+    let stepping = simulation.borrow_mut();
+    assert!(simulation.try_borrow().is_err());
+    drop(stepping);
+
+    assert!(simulation.borrow().try_agent(coord).is_ok());
+}